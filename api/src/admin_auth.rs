@@ -0,0 +1,349 @@
+//! Authentication of the admin endpoints.
+//!
+//! Admins are identified by a configured set of pubkeys. A request authenticates either with an
+//! API key issued to an admin pubkey, sent in [`API_KEY_HEADER`], or with a message signed by
+//! the admin keypair:
+//!
+//! - [`PUBKEY_HEADER`]: admin pubkey
+//! - [`TIMESTAMP_HEADER`]: unix timestamp in seconds, at most [`MAX_SIGNATURE_AGE_SECS`] off
+//! - [`SIGNATURE_HEADER`]: base58 ed25519 signature of [`AdminAuth::signed_message`]
+//!
+//! A signature is accepted once: signatures seen within the last [`MAX_SIGNATURE_AGE_SECS`] are
+//! kept in memory, per API instance, and a replayed one is rejected.
+
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use axum::http::{HeaderMap, Method, Uri};
+use chrono::{DateTime, Utc};
+use solana_pubkey::Pubkey;
+use solana_signature::Signature;
+
+use crate::error::ApiError;
+
+/// Header carrying an admin API key
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Header carrying the admin pubkey of a signed request
+pub const PUBKEY_HEADER: &str = "x-admin-pubkey";
+
+/// Header carrying the timestamp of a signed request
+pub const TIMESTAMP_HEADER: &str = "x-admin-timestamp";
+
+/// Header carrying the signature of a signed request
+pub const SIGNATURE_HEADER: &str = "x-admin-signature";
+
+/// Maximum difference between the timestamp of a signed request and the server time
+pub const MAX_SIGNATURE_AGE_SECS: i64 = 300;
+
+#[derive(Clone, Default)]
+pub struct AdminAuth {
+    /// Admin pubkeys
+    admin_pubkeys: Arc<HashSet<Pubkey>>,
+
+    /// API keys and the admin pubkey each is issued to
+    api_keys: Arc<Vec<(String, Pubkey)>>,
+
+    /// Signatures accepted within the last [`MAX_SIGNATURE_AGE_SECS`], with their timestamp
+    seen_signatures: Arc<Mutex<HashMap<Signature, i64>>>,
+}
+
+impl AdminAuth {
+    /// Initialize an [`AdminAuth`]
+    ///
+    /// `api_keys` are formatted as `<admin pubkey>:<key>`, the pubkey must be one of
+    /// `admin_pubkeys`.
+    pub fn new(admin_pubkeys: &[Pubkey], api_keys: &[String]) -> Result<Self, String> {
+        let admin_pubkeys: HashSet<Pubkey> = admin_pubkeys.iter().copied().collect();
+
+        let api_keys = api_keys
+            .iter()
+            .map(|api_key| {
+                let (pubkey, key) = api_key
+                    .split_once(':')
+                    .ok_or("admin API keys must be formatted as <admin pubkey>:<key>")?;
+                let pubkey = Pubkey::from_str(pubkey)
+                    .map_err(|e| format!("invalid admin API key pubkey {pubkey}: {e}"))?;
+                if !admin_pubkeys.contains(&pubkey) {
+                    return Err(format!("admin API key issued to unknown admin {pubkey}"));
+                }
+                if key.is_empty() {
+                    return Err(format!("empty admin API key for {pubkey}"));
+                }
+                Ok((key.to_string(), pubkey))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self {
+            admin_pubkeys: Arc::new(admin_pubkeys),
+            api_keys: Arc::new(api_keys),
+            seen_signatures: Arc::default(),
+        })
+    }
+
+    /// Message an admin signs for a request: the method, path and query, and timestamp on
+    /// separate lines, followed by the body
+    pub fn signed_message(method: &Method, uri: &Uri, timestamp: i64, body: &[u8]) -> Vec<u8> {
+        let path = uri.path_and_query().map_or(uri.path(), |p| p.as_str());
+        let mut message = format!("{method} {path}\n{timestamp}\n").into_bytes();
+        message.extend_from_slice(body);
+        message
+    }
+
+    /// Authenticate a request, returns the admin pubkey that made it
+    pub fn authenticate(
+        &self,
+        headers: &HeaderMap,
+        method: &Method,
+        uri: &Uri,
+        body: &[u8],
+        now: DateTime<Utc>,
+    ) -> Result<Pubkey, ApiError> {
+        if self.admin_pubkeys.is_empty() {
+            return Err(ApiError::unauthorized("admin API is not configured"));
+        }
+
+        if let Some(api_key) = header(headers, API_KEY_HEADER)? {
+            return self
+                .api_keys
+                .iter()
+                .find(|(key, _)| constant_time_eq(key.as_bytes(), api_key.as_bytes()))
+                .map(|(_, pubkey)| *pubkey)
+                .ok_or_else(|| ApiError::unauthorized("invalid API key"));
+        }
+
+        let (Some(pubkey), Some(timestamp), Some(signature)) = (
+            header(headers, PUBKEY_HEADER)?,
+            header(headers, TIMESTAMP_HEADER)?,
+            header(headers, SIGNATURE_HEADER)?,
+        ) else {
+            return Err(ApiError::unauthorized(format!(
+                "missing {API_KEY_HEADER} header, or {PUBKEY_HEADER}, {TIMESTAMP_HEADER} and {SIGNATURE_HEADER} headers"
+            )));
+        };
+
+        let pubkey = Pubkey::from_str(pubkey)
+            .map_err(|_| ApiError::unauthorized(format!("invalid {PUBKEY_HEADER} header")))?;
+        if !self.admin_pubkeys.contains(&pubkey) {
+            return Err(ApiError::unauthorized(format!("{pubkey} is not an admin")));
+        }
+
+        let timestamp: i64 = timestamp
+            .parse()
+            .map_err(|_| ApiError::unauthorized(format!("invalid {TIMESTAMP_HEADER} header")))?;
+        if (now.timestamp() - timestamp).abs() > MAX_SIGNATURE_AGE_SECS {
+            return Err(ApiError::unauthorized("signature expired"));
+        }
+
+        let signature = Signature::from_str(signature)
+            .map_err(|_| ApiError::unauthorized(format!("invalid {SIGNATURE_HEADER} header")))?;
+        let message = Self::signed_message(method, uri, timestamp, body);
+        if !signature.verify(pubkey.as_ref(), &message) {
+            return Err(ApiError::unauthorized("invalid signature"));
+        }
+        self.accept_signature(signature, timestamp, now)?;
+
+        Ok(pubkey)
+    }
+
+    /// Record a verified signature, rejecting it when it was already accepted
+    ///
+    /// Signatures older than [`MAX_SIGNATURE_AGE_SECS`] are dropped, they fail the timestamp
+    /// check anyway.
+    fn accept_signature(
+        &self,
+        signature: Signature,
+        timestamp: i64,
+        now: DateTime<Utc>,
+    ) -> Result<(), ApiError> {
+        let mut seen_signatures = self
+            .seen_signatures
+            .lock()
+            .map_err(|_| ApiError::Internal("admin signature cache poisoned".to_string()))?;
+        seen_signatures.retain(|_, seen_at| now.timestamp() - *seen_at <= MAX_SIGNATURE_AGE_SECS);
+
+        if seen_signatures.insert(signature, timestamp).is_some() {
+            return Err(ApiError::unauthorized("signature already used"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Value of a header, `None` when it is absent
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Result<Option<&'a str>, ApiError> {
+    headers
+        .get(name)
+        .map(|value| {
+            value
+                .to_str()
+                .map_err(|_| ApiError::unauthorized(format!("invalid {name} header")))
+        })
+        .transpose()
+}
+
+/// Compare `a` and `b` in time independent of where they differ
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use chrono::Duration;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    use super::*;
+
+    const BODY: &[u8] = br#"{"vote_account": "J1to1yufRnoWn81KYg1XkTWzmKjnYSnmE2VY8DGUJ9Qv"}"#;
+
+    fn admin_auth(admin: &Keypair) -> AdminAuth {
+        AdminAuth::new(&[admin.pubkey()], &[format!("{}:secret", admin.pubkey())]).unwrap()
+    }
+
+    fn uri() -> Uri {
+        Uri::from_static("/api/v1/admin/bam_delegation_blacklist")
+    }
+
+    fn api_key_headers(api_key: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(API_KEY_HEADER, HeaderValue::from_str(api_key).unwrap());
+        headers
+    }
+
+    /// Headers of a request signed by `signer` at `timestamp`
+    fn signed_headers(signer: &Keypair, uri: &Uri, timestamp: i64, body: &[u8]) -> HeaderMap {
+        let message = AdminAuth::signed_message(&Method::POST, uri, timestamp, body);
+        let signature = signer.sign_message(&message);
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in [
+            (PUBKEY_HEADER, signer.pubkey().to_string()),
+            (TIMESTAMP_HEADER, timestamp.to_string()),
+            (SIGNATURE_HEADER, signature.to_string()),
+        ] {
+            headers.insert(name, HeaderValue::from_str(&value).unwrap());
+        }
+        headers
+    }
+
+    fn is_unauthorized(result: Result<Pubkey, ApiError>) -> bool {
+        matches!(result, Err(ApiError::Unauthorized { .. }))
+    }
+
+    #[test]
+    fn test_api_key() {
+        let admin = Keypair::new();
+        let auth = admin_auth(&admin);
+        let now = Utc::now();
+
+        let result =
+            auth.authenticate(&api_key_headers("secret"), &Method::POST, &uri(), BODY, now);
+        assert_eq!(result.unwrap(), admin.pubkey());
+
+        let result = auth.authenticate(
+            &api_key_headers("secrets"),
+            &Method::POST,
+            &uri(),
+            BODY,
+            now,
+        );
+        assert!(is_unauthorized(result));
+    }
+
+    #[test]
+    fn test_signed_request() {
+        let admin = Keypair::new();
+        let auth = admin_auth(&admin);
+        let now = Utc::now();
+
+        let headers = signed_headers(&admin, &uri(), now.timestamp(), BODY);
+        let result = auth.authenticate(&headers, &Method::POST, &uri(), BODY, now);
+        assert_eq!(result.unwrap(), admin.pubkey());
+    }
+
+    #[test]
+    fn test_non_admin_pubkey() {
+        let auth = admin_auth(&Keypair::new());
+        let now = Utc::now();
+
+        let headers = signed_headers(&Keypair::new(), &uri(), now.timestamp(), BODY);
+        let result = auth.authenticate(&headers, &Method::POST, &uri(), BODY, now);
+        assert!(is_unauthorized(result));
+    }
+
+    #[test]
+    fn test_expired_timestamp() {
+        let admin = Keypair::new();
+        let auth = admin_auth(&admin);
+        let now = Utc::now();
+
+        for timestamp in [
+            now - Duration::seconds(MAX_SIGNATURE_AGE_SECS + 1),
+            now + Duration::seconds(MAX_SIGNATURE_AGE_SECS + 1),
+        ] {
+            let headers = signed_headers(&admin, &uri(), timestamp.timestamp(), BODY);
+            let result = auth.authenticate(&headers, &Method::POST, &uri(), BODY, now);
+            assert!(is_unauthorized(result));
+        }
+    }
+
+    #[test]
+    fn test_tampered_request() {
+        let admin = Keypair::new();
+        let auth = admin_auth(&admin);
+        let now = Utc::now();
+        let headers = signed_headers(&admin, &uri(), now.timestamp(), BODY);
+
+        let tampered_body = br#"{"vote_account": "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d"}"#;
+        let result = auth.authenticate(&headers, &Method::POST, &uri(), tampered_body, now);
+        assert!(is_unauthorized(result));
+
+        let tampered_uri = Uri::from_static(
+            "/api/v1/admin/bam_delegation_blacklist/J1to1yufRnoWn81KYg1XkTWzmKjnYSnmE2VY8DGUJ9Qv",
+        );
+        let result = auth.authenticate(&headers, &Method::POST, &tampered_uri, BODY, now);
+        assert!(is_unauthorized(result));
+
+        let result = auth.authenticate(&headers, &Method::DELETE, &uri(), BODY, now);
+        assert!(is_unauthorized(result));
+    }
+
+    #[test]
+    fn test_empty_admin_set() {
+        let auth = AdminAuth::new(&[], &[]).unwrap();
+        let now = Utc::now();
+
+        let result =
+            auth.authenticate(&api_key_headers("secret"), &Method::POST, &uri(), BODY, now);
+        assert!(is_unauthorized(result));
+
+        let headers = signed_headers(&Keypair::new(), &uri(), now.timestamp(), BODY);
+        let result = auth.authenticate(&headers, &Method::POST, &uri(), BODY, now);
+        assert!(is_unauthorized(result));
+    }
+
+    #[test]
+    fn test_replayed_signature() {
+        let admin = Keypair::new();
+        let auth = admin_auth(&admin);
+        let now = Utc::now();
+        let headers = signed_headers(&admin, &uri(), now.timestamp(), BODY);
+
+        assert!(auth
+            .authenticate(&headers, &Method::POST, &uri(), BODY, now)
+            .is_ok());
+        let replayed_at = now + Duration::seconds(10);
+        let result = auth.authenticate(&headers, &Method::POST, &uri(), BODY, replayed_at);
+        assert!(is_unauthorized(result));
+
+        // A clone shares the signatures seen, as every request handler gets a clone
+        let result = auth
+            .clone()
+            .authenticate(&headers, &Method::POST, &uri(), BODY, replayed_at);
+        assert!(is_unauthorized(result));
+    }
+}
@@ -15,6 +15,9 @@ pub enum ApiError {
     #[error("Invalid request: {message}")]
     BadRequest { message: String },
 
+    #[error("Unauthorized: {message}")]
+    Unauthorized { message: String },
+
     #[error("Resource not found: {resource}")]
     NotFound { resource: String },
 
@@ -36,6 +39,7 @@ impl IntoResponse for ApiError {
                 format!("Request validation failed: {message}"),
             ),
             ApiError::BadRequest { message } => (StatusCode::BAD_REQUEST, message.clone()),
+            ApiError::Unauthorized { message } => (StatusCode::UNAUTHORIZED, message.clone()),
             ApiError::NotFound { resource } => {
                 (StatusCode::NOT_FOUND, format!("{resource} not found"))
             }
@@ -69,6 +73,7 @@ impl From<QueryResolverError> for ApiError {
     fn from(err: QueryResolverError) -> Self {
         match err {
             QueryResolverError::InvalidRequest(msg) => ApiError::BadRequest { message: msg },
+            QueryResolverError::NotFound(resource) => ApiError::NotFound { resource },
             QueryResolverError::DataStoreError(_) | QueryResolverError::MongoDBError(_) => {
                 ApiError::Database
             }
@@ -100,6 +105,13 @@ impl ApiError {
         }
     }
 
+    /// Initiallize Unauthorized Error
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::Unauthorized {
+            message: message.into(),
+        }
+    }
+
     /// Initiallize NotFound Error
     pub fn not_found(resource: impl Into<String>) -> Self {
         Self::NotFound {
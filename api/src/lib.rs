@@ -1,3 +1,4 @@
+pub mod admin_auth;
 pub mod error;
 pub mod resolvers;
 pub mod schemas;
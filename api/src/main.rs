@@ -1,52 +1,73 @@
 use std::{
     net::{IpAddr, SocketAddr},
-    str::FromStr,
+    path::PathBuf,
     time::Duration,
 };
 
 use axum::{
+    body::Bytes,
     error_handling::HandleErrorLayer,
     extract::{Path, Query},
-    http::{Method, StatusCode},
-    response::IntoResponse,
-    routing::get,
+    http::{HeaderMap, Method, StatusCode, Uri},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+    routing::{get, post, put},
     Extension, Json, Router, Server,
 };
+use chrono::Utc;
 use clap::Parser;
 use env_logger::{Builder, Target};
+use futures::{future, StreamExt};
 use kobe_api::{
+    admin_auth::AdminAuth,
     error::{handle_error, ApiError},
     resolvers::query_resolver::{
-        daily_mev_rewards_cacheable_wrapper, get_bam_boost_claim_wrapper,
-        get_bam_boost_validators_wrapper, get_bam_delegation_blacklist_wrapper,
-        get_bam_epoch_metrics_wrapper, get_bam_validator_score_wrapper, get_bam_validators_wrapper,
+        daily_mev_rewards_cacheable_wrapper, delegation_explainer_cacheable_wrapper,
+        get_bam_boost_claim_wrapper, get_bam_boost_validators_wrapper,
+        get_bam_delegation_blacklist_wrapper, get_bam_delegation_plan_wrapper,
+        get_bam_eligibility_wrapper, get_bam_epoch_metrics_wrapper,
+        get_bam_validator_score_wrapper, get_bam_validators_wrapper,
         get_validator_histories_wrapper, jito_stake_over_time_ratio_cacheable_wrapper,
         jitosol_ratio_cacheable_wrapper, jitosol_validators_cacheable_wrapper,
         mev_commission_average_over_time_cacheable_wrapper, mev_rewards_cacheable_wrapper,
         preferred_withdraw_validator_list_cacheable_wrapper, stake_pool_stats_cacheable_wrapper,
         staker_rewards_cacheable_wrapper, steward_events_cacheable_wrapper,
+        steward_rebalance_summary_cacheable_wrapper, steward_state_snapshots_cacheable_wrapper,
         validator_by_vote_account_cacheable_wrapper, validator_rewards_cacheable_wrapper,
+        validator_score_cacheable_wrapper, validator_steward_state_cacheable_wrapper,
         validators_cacheable_wrapper, QueryResolver,
     },
     schemas::{
         bam_boost_validator::BamBoostValidatorsRequest,
+        bam_delegation_blacklist::{
+            AddBamDelegationBlacklistRequest, BamDelegationBlacklistAuditLogRequest,
+            UpdateBamDelegationBlacklistRequest,
+        },
+        bam_delegation_plan::BamDelegationPlanRequest,
         bam_epoch_metrics::BamEpochMetricsRequest,
         bam_validator::{BamValidatorRequest, BamValidatorsRequest},
+        delegation_explainer::DelegationExplainerRequest,
         jitosol_ratio::JitoSolRatioRequest,
         mev_rewards::{MevRewardsRequest, StakerRewardsRequest, ValidatorRewardsRequest},
         preferred_withdraw::PreferredWithdrawRequest,
         stake_pool_stats::GetStakePoolStatsRequest,
-        steward_events::StewardEventsRequest,
+        steward_events::{StewardEventsRequest, StewardRebalanceSummaryRequest},
+        steward_state::{StewardStateSnapshotsRequest, ValidatorStewardStateRequest},
         validator::ValidatorsRequest,
         validator_history::EpochQuery,
+        validator_score::ValidatorScoreRequest,
     },
 };
 use kobe_core::{
+    cluster_config::ClusterConfig,
     db_models::mev_rewards::{StakerRewardsStore, ValidatorRewardsStore},
     validators_app::Cluster,
 };
 use log::*;
 use mongodb::Client;
+use serde::de::DeserializeOwned;
 use serde_json::json;
 use solana_pubkey::Pubkey;
 use tower::{buffer::BufferLayer, limit::RateLimitLayer, timeout::TimeoutLayer, ServiceBuilder};
@@ -178,9 +199,96 @@ async fn staker_rewards_handler_v1(
 
 async fn steward_events_handler(
     resolver: Extension<QueryResolver>,
-    request: Query<StewardEventsRequest>,
+    Query(request): Query<StewardEventsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    request.validate()?;
+
+    Ok(steward_events_cacheable_wrapper(resolver, request).await)
+}
+
+async fn steward_events_stream_handler(
+    resolver: Extension<QueryResolver>,
+    Query(request): Query<StewardEventsRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    request.validate_stream()?;
+
+    // An error ends the stream with an `error` event, so clients know to reconnect
+    let events = resolver
+        .stream_steward_events(&request)
+        .await?
+        .scan(false, |failed, event| {
+            future::ready(match event {
+                _ if *failed => None,
+                Ok(event) => Some(
+                    Event::default()
+                        .event(event.event_type.clone())
+                        .json_data(event),
+                ),
+                Err(e) => {
+                    error!("Error streaming steward events: {e:?}");
+                    *failed = true;
+                    Some(Ok(Event::default()
+                        .event("error")
+                        .data("Steward events stream failed, reconnect to resume")))
+                }
+            })
+        });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+async fn delegation_explainer_handler(
+    resolver: Extension<QueryResolver>,
+    Path(vote_account): Path<String>,
+    Query(request): Query<DelegationExplainerRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    request.validate()?;
+
+    Ok(delegation_explainer_cacheable_wrapper(resolver, vote_account, request).await)
+}
+
+async fn validator_score_handler(
+    resolver: Extension<QueryResolver>,
+    Path(vote_account): Path<String>,
+    request: Query<ValidatorScoreRequest>,
+) -> impl IntoResponse {
+    validator_score_cacheable_wrapper(resolver, vote_account, request.0).await
+}
+
+async fn steward_rebalance_summary_handler(
+    resolver: Extension<QueryResolver>,
+    Query(request): Query<StewardRebalanceSummaryRequest>,
+) -> impl IntoResponse {
+    steward_rebalance_summary_cacheable_wrapper(resolver, request.epoch).await
+}
+
+async fn steward_state_snapshots_handler(
+    resolver: Extension<QueryResolver>,
+    request: Query<StewardStateSnapshotsRequest>,
 ) -> impl IntoResponse {
-    steward_events_cacheable_wrapper(resolver, request.0).await
+    steward_state_snapshots_cacheable_wrapper(resolver, request.0).await
+}
+
+async fn validator_steward_state_handler(
+    resolver: Extension<QueryResolver>,
+    Path(vote_account): Path<String>,
+    request: Query<ValidatorStewardStateRequest>,
+) -> impl IntoResponse {
+    if let Some(limit) = request.limit {
+        if limit > ValidatorStewardStateRequest::MAX_LIMIT {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": format!("Limit exceeds maximum allowed value of {}", ValidatorStewardStateRequest::MAX_LIMIT)
+                })),
+            )
+                .into_response();
+        }
+    }
+
+    validator_steward_state_cacheable_wrapper(resolver, vote_account, request.0)
+        .await
+        .into_response()
 }
 
 async fn jitosol_sol_ratio_handler(
@@ -224,6 +332,20 @@ async fn get_bam_validator_score_handler(
     get_bam_validator_score_wrapper(resolver, query.epoch, &query.vote_account).await
 }
 
+async fn get_bam_delegation_plan_handler(
+    resolver: Extension<QueryResolver>,
+    Query(query): Query<BamDelegationPlanRequest>,
+) -> impl IntoResponse {
+    get_bam_delegation_plan_wrapper(resolver, query.epoch).await
+}
+
+async fn get_bam_eligibility_handler(
+    resolver: Extension<QueryResolver>,
+    Path(vote_account): Path<String>,
+) -> impl IntoResponse {
+    get_bam_eligibility_wrapper(resolver, vote_account).await
+}
+
 async fn preferred_withdraw_validator_list_handler(
     resolver: Extension<QueryResolver>,
     request: Query<PreferredWithdrawRequest>,
@@ -235,6 +357,81 @@ async fn bam_delegation_blacklist_handler(resolver: Extension<QueryResolver>) ->
     get_bam_delegation_blacklist_wrapper(resolver).await
 }
 
+async fn bam_delegation_blacklist_audit_log_handler(
+    resolver: Extension<QueryResolver>,
+    Query(request): Query<BamDelegationBlacklistAuditLogRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    request.validate()?;
+
+    Ok(Json(
+        resolver
+            .get_bam_delegation_blacklist_audit_log(&request)
+            .await?,
+    ))
+}
+
+/// Parse the JSON body of an admin request, read as bytes since the signature covers it
+fn admin_request<T: DeserializeOwned>(body: &[u8]) -> Result<T, ApiError> {
+    serde_json::from_slice(body)
+        .map_err(|e| ApiError::validation_error(format!("invalid request body: {e}")))
+}
+
+async fn add_bam_delegation_blacklist_handler(
+    resolver: Extension<QueryResolver>,
+    admin_auth: Extension<AdminAuth>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor = admin_auth.authenticate(&headers, &method, &uri, &body, Utc::now())?;
+    let request: AddBamDelegationBlacklistRequest = admin_request(&body)?;
+    request.validate()?;
+
+    let audit_entry = resolver
+        .add_bam_delegation_blacklist_entry(&actor, request)
+        .await?;
+    Ok((StatusCode::CREATED, Json(audit_entry)))
+}
+
+async fn update_bam_delegation_blacklist_handler(
+    resolver: Extension<QueryResolver>,
+    admin_auth: Extension<AdminAuth>,
+    Path(vote_account): Path<String>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor = admin_auth.authenticate(&headers, &method, &uri, &body, Utc::now())?;
+    let request: UpdateBamDelegationBlacklistRequest = admin_request(&body)?;
+    request.validate()?;
+
+    Ok(Json(
+        resolver
+            .update_bam_delegation_blacklist_entry(&actor, &vote_account, request)
+            .await?,
+    ))
+}
+
+async fn remove_bam_delegation_blacklist_handler(
+    resolver: Extension<QueryResolver>,
+    admin_auth: Extension<AdminAuth>,
+    Path(vote_account): Path<String>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<impl IntoResponse, ApiError> {
+    let actor = admin_auth.authenticate(&headers, &method, &uri, &body, Utc::now())?;
+
+    Ok(Json(
+        resolver
+            .remove_bam_delegation_blacklist_entry(&actor, &vote_account)
+            .await?,
+    ))
+}
+
 async fn bam_boost_claim_handler(
     resolver: Extension<QueryResolver>,
     Path((cluster, epoch, validator_id)): Path<(String, u64, String)>,
@@ -276,13 +473,26 @@ struct Args {
     #[arg(long, env)]
     rpc_url: String,
 
-    /// Solana cluster e.g. testnet, mainnet, devnet
+    /// Solana cluster e.g. testnet, mainnet, devnet, localnet
     #[arg(long, short, env, default_value_t=String::from("testnet"))]
     solana_cluster: String,
 
-    /// Steward config public key
+    /// Cluster config file (TOML), defaults to the profiles embedded in kobe-core
     #[arg(long, env)]
-    steward_config: Pubkey,
+    cluster_config: Option<PathBuf>,
+
+    /// Steward config public key, overrides the cluster config
+    #[arg(long, env)]
+    steward_config: Option<Pubkey>,
+
+    /// Admin pubkeys allowed to use the admin endpoints, comma separated. The admin endpoints
+    /// reject every request when unset
+    #[arg(long, env, value_delimiter = ',')]
+    admin_pubkeys: Vec<Pubkey>,
+
+    /// Admin API keys formatted as `<admin pubkey>:<key>`, comma separated
+    #[arg(long, env, value_delimiter = ',', hide_env_values = true)]
+    admin_api_keys: Vec<String>,
 }
 
 fn main() {
@@ -318,19 +528,26 @@ async fn run_server(args: &Args) {
         .expect("Mongo connection failed.");
     let db = c.database(&args.mongo_db_name);
     let cluster = Cluster::get_cluster(&args.solana_cluster).expect("Failed to get cluster");
+    let mut cluster_config = ClusterConfig::load(args.cluster_config.as_deref(), cluster)
+        .expect("Failed to load cluster config");
+    if let Some(steward_config) = args.steward_config {
+        cluster_config.steward_config = steward_config;
+    }
 
-    let query_resolver = QueryResolver::new(
-        &db,
-        args.rpc_url.to_owned(),
-        cluster,
-        args.steward_config,
-        Pubkey::from_str("BoostxbPp2ENYHGcTLYt1obpcY13HE4NojdqNWdzqSSb")
-            .expect("Failed to read jito bam boost program ID"),
-    );
+    let admin_auth = AdminAuth::new(&args.admin_pubkeys, &args.admin_api_keys)
+        .expect("Failed to load admin API keys");
+
+    let query_resolver = QueryResolver::new(&db, args.rpc_url.to_owned(), cluster_config);
 
     let cors = CorsLayer::new()
         .allow_headers(Any)
-        .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS])
+        .allow_methods(vec![
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
         .allow_origin(Any)
         .allow_credentials(false);
 
@@ -384,6 +601,30 @@ async fn run_server(args: &Args) {
             "/api/v1/steward_events",
             get(steward_events_handler).post(steward_events_handler),
         )
+        .route(
+            "/api/v1/steward_events/stream",
+            get(steward_events_stream_handler),
+        )
+        .route(
+            "/api/v1/steward_rebalance_summary",
+            get(steward_rebalance_summary_handler),
+        )
+        .route(
+            "/api/v1/steward_state_snapshots",
+            get(steward_state_snapshots_handler),
+        )
+        .route(
+            "/api/v1/validators/:vote_account/delegation_explainer",
+            get(delegation_explainer_handler),
+        )
+        .route(
+            "/api/v1/validators/:vote_account/score",
+            get(validator_score_handler),
+        )
+        .route(
+            "/api/v1/validators/:vote_account/steward_state",
+            get(validator_steward_state_handler),
+        )
         .route(
             "/api/v1/staker_rewards",
             get(staker_rewards_handler_v1).post(staker_rewards_handler_v1),
@@ -409,6 +650,14 @@ async fn run_server(args: &Args) {
             "/api/v1/bam_validator_score",
             get(get_bam_validator_score_handler),
         )
+        .route(
+            "/api/v1/bam_delegation_plan",
+            get(get_bam_delegation_plan_handler),
+        )
+        .route(
+            "/api/v1/bam_eligibility/:vote_account",
+            get(get_bam_eligibility_handler),
+        )
         .route(
             "/api/v1/preferred_withdraw_validator_list",
             get(preferred_withdraw_validator_list_handler),
@@ -417,6 +666,19 @@ async fn run_server(args: &Args) {
             "/api/v1/bam_delegation_blacklist",
             get(bam_delegation_blacklist_handler),
         )
+        .route(
+            "/api/v1/bam_delegation_blacklist/audit_log",
+            get(bam_delegation_blacklist_audit_log_handler),
+        )
+        .route(
+            "/api/v1/admin/bam_delegation_blacklist",
+            post(add_bam_delegation_blacklist_handler),
+        )
+        .route(
+            "/api/v1/admin/bam_delegation_blacklist/:vote_account",
+            put(update_bam_delegation_blacklist_handler)
+                .delete(remove_bam_delegation_blacklist_handler),
+        )
         .route(
             "/api/v1/claim/:network/:epoch/:validator_id",
             get(bam_boost_claim_handler),
@@ -426,6 +688,7 @@ async fn run_server(args: &Args) {
             get(bam_boost_validators_handler),
         )
         .layer(Extension(query_resolver))
+        .layer(Extension(admin_auth))
         .layer(middleware)
         .layer(cors);
 
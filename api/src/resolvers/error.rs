@@ -12,6 +12,9 @@ pub enum QueryResolverError {
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
 
+    #[error("Not found: {0}")]
+    NotFound(String),
+
     #[error("Reqwest error: {0}")]
     ReqwestError(#[from] reqwest::Error),
 
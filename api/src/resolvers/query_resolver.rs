@@ -1,27 +1,43 @@
 use std::{
     collections::{HashMap, HashSet},
+    future::Future,
     str::FromStr,
     sync::Arc,
+    time::Duration,
 };
 
 use anchor_lang::AccountDeserialize;
 use axum::{http::StatusCode, Extension, Json};
 use cached::{proc_macro::cached, TimedCache};
+use chrono::Utc;
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt, TryStreamExt,
+};
 use jito_bam_boost_merkle_tree::bam_boost_merkle_tree::BamBoostMerkleTree;
 use jito_steward::constants::MAX_VALIDATORS;
 use kobe_core::{
-    constants::{JITOSOL_MINT, JITOSOL_VALIDATOR_LIST_MAINNET, JITOSOL_VALIDATOR_LIST_TESTNET},
+    bam_eligibility_rules::BamEligibilityRules,
+    bam_validator_eligibility::BamValidatorEligibility,
+    cluster_config::ClusterConfig,
     db_models::{
         bam_boost_validators::BamBoostValidatorsStore,
         bam_delegation_blacklist::{BamDelegationBlacklistEntry, BamDelegationBlacklistStore},
+        bam_delegation_blacklist_audit::{
+            BamDelegationBlacklistAction, BamDelegationBlacklistAuditEntry,
+            BamDelegationBlacklistAuditStore,
+        },
+        bam_delegation_plans::BamDelegationPlanStore,
         bam_epoch_metrics::BamEpochMetricsStore,
         bam_validators::BamValidatorStore,
+        is_duplicate_key_error,
         mev_rewards::{StakerRewardsStore, ValidatorRewardsStore},
         stake_pool_stats::{StakePoolStats, StakePoolStatsStore},
-        steward_events::StewardEventsStore,
+        steward_events::{self, StewardEventsCursor, StewardEventsStore},
+        steward_parameters::{StewardParameters, StewardParametersStore},
+        steward_state_snapshots::StewardStateSnapshotStore,
         validators::ValidatorStore,
     },
-    validators_app::Cluster,
     SortOrder, LAMPORTS_PER_SOL,
 };
 use log::{error, warn};
@@ -30,8 +46,13 @@ use serde::{Deserialize, Serialize};
 use solana_borsh::v1::try_from_slice_unchecked;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_pubkey::Pubkey;
-use spl_stake_pool::{find_stake_program_address, state::ValidatorList};
-use stakenet_sdk::utils::accounts::{get_all_steward_accounts, get_validator_history_address};
+use spl_stake_pool::{
+    find_stake_program_address,
+    state::{StakePool, ValidatorList},
+};
+use stakenet_sdk::utils::accounts::{
+    get_all_steward_accounts, get_all_validator_history_accounts, get_validator_history_address,
+};
 use validator_history::ValidatorHistory;
 
 use crate::{
@@ -41,8 +62,19 @@ use crate::{
             claim_status_address, merkle_distributor_address, BamBoostClaimResponse,
             BamBoostValidatorsResponse,
         },
+        bam_delegation_blacklist::{
+            AddBamDelegationBlacklistRequest, BamDelegationBlacklistAuditLogRequest,
+            BamDelegationBlacklistAuditLogResponse, UpdateBamDelegationBlacklistRequest,
+            DEFAULT_AUDIT_LOG_LIMIT,
+        },
+        bam_delegation_plan::BamDelegationPlanResponse,
+        bam_eligibility::BamEligibilityResponse,
         bam_epoch_metrics::BamEpochMetricsResponse,
         bam_validator::{BamValidatorScoreResponse, BamValidatorsResponse},
+        delegation_explainer::{
+            default_epoch_range, DelegationExplainer, DelegationExplainerRequest,
+            DelegationExplainerResponse, EXPLAINER_EVENT_TYPES,
+        },
         jitosol_ratio::{JitoSolRatioRequest, JitoSolRatioResponse},
         mev_rewards::{
             MevRewards, MevRewardsRequest, StakerRewards, StakerRewardsRequest,
@@ -54,15 +86,29 @@ use crate::{
             round_to_hour, F64DataPoint, GetStakePoolStatsRequest, GetStakePoolStatsResponse,
             I64DataPoint,
         },
-        steward_events::{StewardEvent, StewardEventsRequest, StewardEventsResponse},
+        steward_events::{
+            StewardEvent, StewardEventsRequest, StewardEventsResponse,
+            StewardRebalanceSummaryResponse,
+        },
+        steward_state::{
+            StewardStateSnapshotsRequest, StewardStateSnapshotsResponse,
+            ValidatorStewardStateRequest, ValidatorStewardStateResponse,
+        },
         validator::{
             AverageMevCommissionOverTimeResponse, JitoStakeOverTimeResponse,
             ValidatorByVoteAccountResponse, ValidatorEntry, ValidatorsRequest, ValidatorsResponse,
         },
         validator_history::{EpochQuery, ValidatorHistoryEntryResponse, ValidatorHistoryResponse},
+        validator_score::{ValidatorScore, ValidatorScoreRequest, ValidatorScoreResponse},
     },
 };
 
+/// Interval between polls of the steward events stream when change streams are unavailable
+pub const STEWARD_EVENTS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Maximum number of steward events read per poll
+const STEWARD_EVENTS_POLL_LIMIT: i64 = 500;
+
 #[derive(Clone)]
 pub struct QueryResolver {
     stake_pool_store: StakePoolStatsStore,
@@ -71,6 +117,12 @@ pub struct QueryResolver {
     staker_rewards_store: StakerRewardsStore,
     steward_events_store: StewardEventsStore,
 
+    /// Steward state snapshots store
+    steward_state_snapshot_store: StewardStateSnapshotStore,
+
+    /// Steward config parameters recorded over time
+    steward_parameters_store: StewardParametersStore,
+
     /// BAM epoch metrics store
     bam_epoch_metrics_store: BamEpochMetricsStore,
 
@@ -80,19 +132,50 @@ pub struct QueryResolver {
     /// BAM Delegation Blacklist Store
     bam_delegation_blacklist_store: BamDelegationBlacklistStore,
 
+    /// BAM delegation blacklist audit log store
+    bam_delegation_blacklist_audit_store: BamDelegationBlacklistAuditStore,
+
+    /// BAM delegation plan store
+    bam_delegation_plan_store: BamDelegationPlanStore,
+
     /// BAM Boost Validators Store
     bam_boost_validators_store: BamBoostValidatorsStore,
 
     /// RPC Client URL
     rpc_client: Arc<RpcClient>,
-    /// Solana Cluster
-    cluster: Cluster,
+    /// Program IDs and addresses of the cluster
+    cluster_config: ClusterConfig,
+}
+
+/// Normalized vote account pubkey of a request
+fn parse_vote_account(vote_account: &str) -> Result<String> {
+    let vote_account = Pubkey::from_str(vote_account).map_err(|e| {
+        QueryResolverError::InvalidRequest(format!("invalid vote account {vote_account}: {e}"))
+    })?;
+    Ok(vote_account.to_string())
+}
 
-    /// Steward Config Public Key
-    steward_config: Pubkey,
+/// Next steward events after `cursor`, read by `fetch`, and the cursor after the last of them
+///
+/// Polls `fetch` every `poll_interval` until it returns some events.
+async fn poll_steward_events<F, Fut>(
+    mut fetch: F,
+    cursor: Option<StewardEventsCursor>,
+    poll_interval: Duration,
+) -> Result<(Vec<StewardEvent>, StewardEventsCursor)>
+where
+    F: FnMut(Option<StewardEventsCursor>) -> Fut,
+    Fut: Future<Output = mongodb::error::Result<Vec<steward_events::StewardEvent>>>,
+{
+    loop {
+        let events = fetch(cursor.clone()).await?;
+        if let Some(last) = events.last() {
+            let cursor = StewardEventsCursor::after(last);
+            return Ok((events.into_iter().map(StewardEvent::from).collect(), cursor));
+        }
 
-    /// Jito BAM Boost program ID
-    jito_bam_boost_program_id: Pubkey,
+        tokio::time::sleep(poll_interval).await;
+    }
 }
 
 fn aggregate_mev_rewards(stats_entries: &[StakePoolStats]) -> u64 {
@@ -275,26 +358,141 @@ pub async fn steward_events_cacheable_wrapper(
     resolver: Extension<QueryResolver>,
     req: StewardEventsRequest,
 ) -> (StatusCode, Json<StewardEventsResponse>) {
-    let offset = match (req.page, req.limit) {
-        (Some(page), Some(limit)) => Some((page - 1) * limit),
-        _ => None,
-    };
+    match resolver.get_steward_events(&req).await {
+        Ok(res) => (StatusCode::OK, Json(res)),
+        Err(e) => {
+            error!("Error fetching steward events: {e:?}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(StewardEventsResponse {
+                    events: vec![],
+                    total_count: 0,
+                    next_cursor: None,
+                }),
+            )
+        }
+    }
+}
+
+#[cached(
+    type = "TimedCache<String, (StatusCode, Json<StewardRebalanceSummaryResponse>)>",
+    create = "{ TimedCache::with_lifespan_and_capacity(60, 1000) }",
+    key = "String",
+    convert = r#"{ format!("steward-rebalance-summary-{epoch}") }"#
+)]
+pub async fn steward_rebalance_summary_cacheable_wrapper(
+    resolver: Extension<QueryResolver>,
+    epoch: u64,
+) -> (StatusCode, Json<StewardRebalanceSummaryResponse>) {
+    match resolver.get_steward_rebalance_summary(epoch).await {
+        Ok(res) => (StatusCode::OK, Json(res)),
+        Err(e) => {
+            error!("Error fetching steward rebalance summary: {e:?}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(StewardRebalanceSummaryResponse::default()),
+            )
+        }
+    }
+}
+
+#[cached(
+    type = "TimedCache<String, (StatusCode, Json<DelegationExplainerResponse>)>",
+    create = "{ TimedCache::with_lifespan_and_capacity(60, 1000) }",
+    key = "String",
+    convert = r#"{ format!("delegation-explainer-{vote_account}-{}", req.to_string()) }"#
+)]
+pub async fn delegation_explainer_cacheable_wrapper(
+    resolver: Extension<QueryResolver>,
+    vote_account: String,
+    req: DelegationExplainerRequest,
+) -> (StatusCode, Json<DelegationExplainerResponse>) {
+    match resolver.get_delegation_explainer(&vote_account, &req).await {
+        Ok(res) => (StatusCode::OK, Json(res)),
+        Err(QueryResolverError::InvalidRequest(e)) => {
+            warn!("Invalid delegation explainer request for {vote_account}: {e}");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(DelegationExplainerResponse::default()),
+            )
+        }
+        Err(e) => {
+            error!("Error explaining delegation of {vote_account}: {e:?}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(DelegationExplainerResponse::default()),
+            )
+        }
+    }
+}
+
+#[cached(
+    type = "TimedCache<String, (StatusCode, Json<ValidatorScoreResponse>)>",
+    create = "{ TimedCache::with_lifespan_and_capacity(60, 1000) }",
+    key = "String",
+    convert = r#"{ format!("validator-score-{vote_account}-{}", req.to_string()) }"#
+)]
+pub async fn validator_score_cacheable_wrapper(
+    resolver: Extension<QueryResolver>,
+    vote_account: String,
+    req: ValidatorScoreRequest,
+) -> (StatusCode, Json<ValidatorScoreResponse>) {
+    match resolver.get_validator_score(&vote_account, req.epoch).await {
+        Ok(res) if res.score.is_none() => (StatusCode::NOT_FOUND, Json(res)),
+        Ok(res) => (StatusCode::OK, Json(res)),
+        Err(e) => {
+            error!("Error fetching score of {vote_account}: {e:?}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ValidatorScoreResponse::default()),
+            )
+        }
+    }
+}
+
+#[cached(
+    type = "TimedCache<String, (StatusCode, Json<StewardStateSnapshotsResponse>)>",
+    create = "{ TimedCache::with_lifespan_and_capacity(60, 1000) }",
+    key = "String",
+    convert = r#"{ format!("steward-state-snapshots-{}", req.to_string()) }"#
+)]
+pub async fn steward_state_snapshots_cacheable_wrapper(
+    resolver: Extension<QueryResolver>,
+    req: StewardStateSnapshotsRequest,
+) -> (StatusCode, Json<StewardStateSnapshotsResponse>) {
+    match resolver.get_steward_state_snapshots(req.epoch).await {
+        Ok(res) => (StatusCode::OK, Json(res)),
+        Err(e) => {
+            error!("Error fetching steward state snapshots: {e:?}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(StewardStateSnapshotsResponse::default()),
+            )
+        }
+    }
+}
+
+#[cached(
+    type = "TimedCache<String, (StatusCode, Json<ValidatorStewardStateResponse>)>",
+    create = "{ TimedCache::with_lifespan_and_capacity(60, 1000) }",
+    key = "String",
+    convert = r#"{ format!("validator-steward-state-{vote_account}-{}", req.to_string()) }"#
+)]
+pub async fn validator_steward_state_cacheable_wrapper(
+    resolver: Extension<QueryResolver>,
+    vote_account: String,
+    req: ValidatorStewardStateRequest,
+) -> (StatusCode, Json<ValidatorStewardStateResponse>) {
     match resolver
-        .get_steward_events(
-            req.event_type,
-            req.vote_account,
-            req.epoch,
-            offset,
-            req.limit,
-        )
+        .get_validator_steward_state(&vote_account, req.epoch, req.limit)
         .await
     {
-        Ok(res) => (StatusCode::OK, Json(StewardEventsResponse { events: res })),
+        Ok(res) => (StatusCode::OK, Json(res)),
         Err(e) => {
-            error!("Error fetching steward events: {e:?}");
+            error!("Error fetching steward state of {vote_account}: {e:?}");
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(StewardEventsResponse { events: vec![] }),
+                Json(ValidatorStewardStateResponse::default()),
             )
         }
     }
@@ -465,6 +663,85 @@ pub async fn get_bam_validator_score_wrapper(
     }
 }
 
+#[cached(
+    type = "TimedCache<String, (StatusCode, Json<BamDelegationPlanResponse>)>",
+    create = "{ TimedCache::with_lifespan_and_capacity(60, 1000) }",
+    key = "String",
+    convert = r#"{ format!("bam-delegation-plan-{epoch:?}") }"#
+)]
+pub async fn get_bam_delegation_plan_wrapper(
+    resolver: Extension<QueryResolver>,
+    epoch: Option<u64>,
+) -> (StatusCode, Json<BamDelegationPlanResponse>) {
+    match resolver.get_bam_delegation_plan(epoch).await {
+        Ok(res) if res.bam_delegation_plan.is_none() => (StatusCode::NOT_FOUND, Json(res)),
+        Ok(res) => (StatusCode::OK, Json(res)),
+        Err(e) => {
+            error!("Error fetching BAM delegation plan of epoch {epoch:?}: {e:?}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(BamDelegationPlanResponse::default()),
+            )
+        }
+    }
+}
+
+/// Eligibility checker of `epoch` under `rules`, shared by the what-if eligibility requests of
+/// every validator, so all validator histories are fetched once per epoch and rules version
+/// rather than once per request
+#[cached(
+    type = "TimedCache<String, Arc<BamValidatorEligibility>>",
+    create = "{ TimedCache::with_lifespan_and_capacity(600, 10) }",
+    key = "String",
+    convert = r#"{ format!("bam-eligibility-checker-{epoch}-{}", rules.version) }"#,
+    result = true
+)]
+async fn bam_eligibility_checker(
+    rpc_client: &RpcClient,
+    validator_history_program_id: Pubkey,
+    epoch: u64,
+    rules: &BamEligibilityRules,
+) -> Result<Arc<BamValidatorEligibility>> {
+    let validator_histories =
+        get_all_validator_history_accounts(rpc_client, validator_history_program_id).await?;
+
+    Ok(Arc::new(BamValidatorEligibility::new(
+        epoch,
+        &validator_histories,
+        rules,
+    )))
+}
+
+#[cached(
+    type = "TimedCache<String, (StatusCode, Json<BamEligibilityResponse>)>",
+    create = "{ TimedCache::with_lifespan_and_capacity(60, 1000) }",
+    key = "String",
+    convert = r#"{ format!("bam-eligibility-{vote_account}") }"#
+)]
+pub async fn get_bam_eligibility_wrapper(
+    resolver: Extension<QueryResolver>,
+    vote_account: String,
+) -> (StatusCode, Json<BamEligibilityResponse>) {
+    match resolver.get_bam_eligibility(&vote_account).await {
+        Ok(res) if res.vote_account.is_none() => (StatusCode::NOT_FOUND, Json(res)),
+        Ok(res) => (StatusCode::OK, Json(res)),
+        Err(QueryResolverError::InvalidRequest(e)) => {
+            warn!("Invalid BAM eligibility request for {vote_account}: {e}");
+            (
+                StatusCode::BAD_REQUEST,
+                Json(BamEligibilityResponse::default()),
+            )
+        }
+        Err(e) => {
+            error!("Error checking BAM eligibility of {vote_account}: {e:?}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(BamEligibilityResponse::default()),
+            )
+        }
+    }
+}
+
 #[cached(
     type = "TimedCache<String, Vec<PreferredWithdraw>>",
     create = "{ TimedCache::with_lifespan_and_capacity(10, 100) }",
@@ -579,13 +856,7 @@ pub async fn get_bam_boost_validators_wrapper(
 }
 
 impl QueryResolver {
-    pub fn new(
-        database: &Database,
-        rpc_client_url: String,
-        cluster: Cluster,
-        steward_config: Pubkey,
-        jito_bam_boost_program_id: Pubkey,
-    ) -> Self {
+    pub fn new(database: &Database, rpc_client_url: String, cluster_config: ClusterConfig) -> Self {
         let client = RpcClient::new(rpc_client_url);
 
         Self {
@@ -602,6 +873,12 @@ impl QueryResolver {
             steward_events_store: StewardEventsStore::new(
                 database.collection(StewardEventsStore::COLLECTION),
             ),
+            steward_state_snapshot_store: StewardStateSnapshotStore::new(
+                database.collection(StewardStateSnapshotStore::COLLECTION),
+            ),
+            steward_parameters_store: StewardParametersStore::new(
+                database.collection(StewardParametersStore::COLLECTION),
+            ),
             bam_epoch_metrics_store: BamEpochMetricsStore::new(
                 database.collection(BamEpochMetricsStore::COLLECTION),
             ),
@@ -611,16 +888,38 @@ impl QueryResolver {
             bam_delegation_blacklist_store: BamDelegationBlacklistStore::new(
                 database.collection(BamDelegationBlacklistStore::COLLECTION),
             ),
+            bam_delegation_blacklist_audit_store: BamDelegationBlacklistAuditStore::new(
+                database.collection(BamDelegationBlacklistAuditStore::COLLECTION),
+            ),
+            bam_delegation_plan_store: BamDelegationPlanStore::new(
+                database.collection(BamDelegationPlanStore::COLLECTION),
+            ),
             bam_boost_validators_store: BamBoostValidatorsStore::new(
                 database.collection(BamBoostValidatorsStore::COLLECTION),
             ),
             rpc_client: Arc::new(client),
-            cluster,
-            steward_config,
-            jito_bam_boost_program_id,
+            cluster_config,
         }
     }
 
+    /// JitoSOL validator list address, read from the stake pool account when the cluster config
+    /// does not set one
+    async fn jitosol_validator_list_address(&self) -> Result<Pubkey> {
+        if let Some(validator_list_address) = self.cluster_config.validator_list_address {
+            return Ok(validator_list_address);
+        }
+
+        let stake_pool_account = self
+            .rpc_client
+            .get_account_data(&self.cluster_config.stake_pool_address)
+            .await
+            .map_err(|e| QueryResolverError::RpcError(e.to_string()))?;
+        let stake_pool = try_from_slice_unchecked::<StakePool>(stake_pool_account.as_slice())
+            .map_err(|e| QueryResolverError::CustomError(e.to_string()))?;
+
+        Ok(stake_pool.validator_list)
+    }
+
     pub async fn get_stake_pool_stats(
         &self,
         req: &GetStakePoolStatsRequest,
@@ -716,6 +1015,13 @@ impl QueryResolver {
                         jito_directed_stake_target: v.jito_directed_stake_target,
                         jito_directed_stake_lamports: v.jito_directed_stake_lamports,
                         jito_sol_active_lamports: None,
+                        estimated_inflation_rewards_lamports: v.inflation_rewards_lamports,
+                        inflation_rewards_status: v.inflation_rewards_status,
+                        paid_inflation_rewards_lamports: v.paid_inflation_rewards_lamports,
+                        vote_account_inflation_rewards_lamports: v
+                            .vote_account_inflation_rewards_lamports,
+                        target_pool_inflation_rewards_lamports: v
+                            .target_pool_inflation_rewards_lamports,
                     }
                 })
                 .collect(),
@@ -739,17 +1045,7 @@ impl QueryResolver {
             .get_mev_rewards_per_validator(epoch)
             .await?;
 
-        let jito_sol_validator_list_address = match self.cluster {
-            Cluster::MainnetBeta => JITOSOL_VALIDATOR_LIST_MAINNET,
-            Cluster::Testnet => JITOSOL_VALIDATOR_LIST_TESTNET,
-            Cluster::Devnet => {
-                return Err(QueryResolverError::InvalidRequest(
-                    "Devnet is not supported yet".to_string(),
-                ));
-            }
-        };
-        let jitosol_validator_list = Pubkey::from_str(jito_sol_validator_list_address)
-            .map_err(|e| QueryResolverError::CustomError(e.to_string()))?;
+        let jitosol_validator_list = self.jitosol_validator_list_address().await?;
 
         // Get stake pool validator list
         let validator_list_account = self
@@ -799,6 +1095,13 @@ impl QueryResolver {
                         jito_directed_stake_target: v.jito_directed_stake_target,
                         jito_directed_stake_lamports: v.jito_directed_stake_lamports,
                         jito_sol_active_lamports: Some(v.target_pool_active_lamports),
+                        estimated_inflation_rewards_lamports: v.inflation_rewards_lamports,
+                        inflation_rewards_status: v.inflation_rewards_status,
+                        paid_inflation_rewards_lamports: v.paid_inflation_rewards_lamports,
+                        vote_account_inflation_rewards_lamports: v
+                            .vote_account_inflation_rewards_lamports,
+                        target_pool_inflation_rewards_lamports: v
+                            .target_pool_inflation_rewards_lamports,
                     }
                 })
                 .collect(),
@@ -959,24 +1262,265 @@ impl QueryResolver {
         })
     }
 
+    /// Page of the steward events matching `request`, by cursor or by page number
     pub async fn get_steward_events(
         &self,
-        event_type: Option<String>,
-        vote_account: Option<String>,
-        epoch: Option<u64>,
-        page: Option<u32>,
-        limit: Option<u32>,
-    ) -> Result<Vec<StewardEvent>> {
-        let limit = limit.unwrap_or(100);
-        let page = page.unwrap_or(1);
-        let offset = (page - 1) * limit;
+        request: &StewardEventsRequest,
+    ) -> Result<StewardEventsResponse> {
+        let limit = request.limit.unwrap_or(100);
+        let cursor = request
+            .cursor
+            .as_deref()
+            .map(str::parse::<StewardEventsCursor>)
+            .transpose()
+            .map_err(QueryResolverError::InvalidRequest)?;
+        let skip = request
+            .page
+            .map(|page| (page.max(1) - 1) as u64 * limit as u64);
+        let first_page = cursor.is_none() && request.page.unwrap_or(1) <= 1;
+
+        let (events, total_count) = self
+            .steward_events_store
+            .find_steward_events(
+                &request.filter(),
+                request.sort_order(),
+                cursor.as_ref(),
+                skip,
+                limit as i64,
+                first_page,
+            )
+            .await?;
+
+        let next_cursor = (events.len() == limit as usize)
+            .then(|| {
+                events
+                    .last()
+                    .map(|event| StewardEventsCursor::after(event).to_string())
+            })
+            .flatten();
+
+        Ok(StewardEventsResponse {
+            events: events.into_iter().map(StewardEvent::from).collect(),
+            total_count,
+            next_cursor,
+        })
+    }
+
+    /// Steward events matching the filters of `request` written from now on
+    ///
+    /// Backed by a change stream, or by polling the collection every
+    /// [`STEWARD_EVENTS_POLL_INTERVAL`] when change streams are unavailable.
+    pub async fn stream_steward_events(
+        &self,
+        request: &StewardEventsRequest,
+    ) -> Result<BoxStream<'static, Result<StewardEvent>>> {
+        let filter = request.filter();
+
+        match self
+            .steward_events_store
+            .watch_steward_events(&filter)
+            .await
+        {
+            Ok(change_stream) => Ok(change_stream
+                .try_filter_map(|event| async move { Ok(event.full_document) })
+                .map_ok(StewardEvent::from)
+                .map_err(QueryResolverError::from)
+                .boxed()),
+            Err(e) => {
+                warn!("Change streams unavailable, polling steward events instead: {e}");
+
+                let store = self.steward_events_store.clone();
+                let (latest, _) = store
+                    .find_steward_events(&filter, SortOrder::Desc, None, None, 1, false)
+                    .await?;
+                let cursor = latest.first().map(StewardEventsCursor::after);
+
+                Ok(stream::try_unfold(
+                    (store, filter, cursor),
+                    |(store, filter, cursor)| async move {
+                        let (events, cursor) = poll_steward_events(
+                            |cursor| {
+                                let (store, filter) = (&store, &filter);
+                                async move {
+                                    store
+                                        .find_steward_events_after(
+                                            filter,
+                                            cursor.as_ref(),
+                                            STEWARD_EVENTS_POLL_LIMIT,
+                                        )
+                                        .await
+                                }
+                            },
+                            cursor,
+                            STEWARD_EVENTS_POLL_INTERVAL,
+                        )
+                        .await?;
+
+                        Ok::<_, QueryResolverError>(Some((
+                            stream::iter(events.into_iter().map(Ok)),
+                            (store, filter, Some(cursor)),
+                        )))
+                    },
+                )
+                .try_flatten()
+                .boxed())
+            }
+        }
+    }
+
+    /// Stake moved to and from each validator by the steward rebalances of `epoch`
+    pub async fn get_steward_rebalance_summary(
+        &self,
+        epoch: u64,
+    ) -> Result<StewardRebalanceSummaryResponse> {
+        let rebalances = self
+            .steward_events_store
+            .get_rebalance_summary(epoch)
+            .await?;
+        let failed_transactions = self
+            .steward_events_store
+            .count_failed_rebalance_transactions(epoch)
+            .await?;
+
+        Ok(StewardRebalanceSummaryResponse::new(
+            epoch,
+            &rebalances,
+            failed_transactions,
+        ))
+    }
+
+    /// Timeline of the steward events and validator history of a validator over an epoch range,
+    /// with the probable causes of the stake moved away from it
+    ///
+    /// The range is the last few epochs when the request sets none, see [`default_epoch_range`].
+    pub async fn get_delegation_explainer(
+        &self,
+        vote_account: &str,
+        req: &DelegationExplainerRequest,
+    ) -> Result<DelegationExplainerResponse> {
+        req.validate()
+            .map_err(|e| QueryResolverError::InvalidRequest(e.to_string()))?;
+        let (start_epoch, end_epoch) = match (req.start_epoch, req.end_epoch) {
+            (Some(start_epoch), Some(end_epoch)) => (start_epoch, end_epoch),
+            _ => default_epoch_range(
+                self.rpc_client
+                    .get_epoch_info()
+                    .await
+                    .map_err(|e| QueryResolverError::RpcError(e.to_string()))?
+                    .epoch,
+            ),
+        };
 
         let events = self
             .steward_events_store
-            .find_steward_events(event_type, vote_account, epoch, limit as i64, offset as u64)
+            .find_validator_events(vote_account, start_epoch, end_epoch, &EXPLAINER_EVENT_TYPES)
+            .await?;
+        let mut rebalance_signatures: Vec<String> = events
+            .iter()
+            .filter(|event| event.event_type == "RebalanceEvent")
+            .map(|event| event.signature.clone())
+            .collect();
+        rebalance_signatures.sort();
+        rebalance_signatures.dedup();
+        let decrease_components = self
+            .steward_events_store
+            .find_by_signatures("DecreaseComponents", &rebalance_signatures)
+            .await?;
+
+        let mut history = self
+            .get_validator_histories(vote_account.to_string(), EpochQuery { epoch: None })
+            .await?
+            .history;
+        history.sort_by_key(|entry| entry.epoch);
+
+        Ok(
+            DelegationExplainer::new(&events, &decrease_components, &history).explain(
+                vote_account,
+                start_epoch,
+                end_epoch,
+            ),
+        )
+    }
+
+    /// Score breakdown of the latest ScoreComponents event of a validator, of `epoch` when set
+    ///
+    /// Thresholds are those of the steward config parameters recorded last before the event, or
+    /// of the current steward config when none were recorded.
+    pub async fn get_validator_score(
+        &self,
+        vote_account: &str,
+        epoch: Option<u64>,
+    ) -> Result<ValidatorScoreResponse> {
+        let Some(event) = self
+            .steward_events_store
+            .find_latest_score_components(vote_account, epoch)
+            .await?
+        else {
+            return Ok(ValidatorScoreResponse::default());
+        };
+
+        let (parameters, parameters_slot) = match self
+            .steward_parameters_store
+            .find_in_effect(event.slot)
+            .await?
+        {
+            Some(parameters) => {
+                let slot = parameters.slot;
+                (parameters, Some(slot))
+            }
+            None => (self.current_steward_parameters().await?, None),
+        };
+
+        Ok(ValidatorScoreResponse {
+            score: ValidatorScore::from_event(event, &parameters, parameters_slot),
+        })
+    }
+
+    /// Parameters of the current steward config
+    async fn current_steward_parameters(&self) -> Result<StewardParameters> {
+        let all_steward_accounts = get_all_steward_accounts(
+            &self.rpc_client,
+            &self.cluster_config.steward_program_id,
+            &self.cluster_config.steward_config,
+        )
+        .await?;
+        let epoch_info = self
+            .rpc_client
+            .get_epoch_info()
+            .await
+            .map_err(|e| QueryResolverError::RpcError(e.to_string()))?;
+
+        Ok(StewardParameters::from_parameters(
+            &all_steward_accounts.config_account.parameters,
+            epoch_info.epoch,
+            epoch_info.absolute_slot,
+        ))
+    }
+
+    /// Latest steward state snapshot, of `epoch` when set
+    pub async fn get_steward_state_snapshots(
+        &self,
+        epoch: Option<u64>,
+    ) -> Result<StewardStateSnapshotsResponse> {
+        let snapshots = self.steward_state_snapshot_store.find_latest(epoch).await?;
+
+        Ok(StewardStateSnapshotsResponse { snapshots })
+    }
+
+    /// Steward state snapshots of a validator, newest first
+    pub async fn get_validator_steward_state(
+        &self,
+        vote_account: &str,
+        epoch: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<ValidatorStewardStateResponse> {
+        let limit = limit.unwrap_or(100);
+        let snapshots = self
+            .steward_state_snapshot_store
+            .find_by_vote_account(vote_account, epoch, limit as i64)
             .await?;
 
-        Ok(events.into_iter().map(|event| event.into()).collect())
+        Ok(ValidatorStewardStateResponse { snapshots })
     }
 
     pub async fn get_jitosol_ratio(
@@ -1041,8 +1585,10 @@ impl QueryResolver {
     ) -> Result<ValidatorHistoryResponse> {
         let vote_account = Pubkey::from_str(&vote_account)
             .map_err(|e| QueryResolverError::CustomError(e.to_string()))?;
-        let history_account =
-            get_validator_history_address(&vote_account, &validator_history::id());
+        let history_account = get_validator_history_address(
+            &vote_account,
+            &self.cluster_config.validator_history_program_id,
+        );
         let account = self
             .rpc_client
             .get_account(&history_account)
@@ -1142,11 +1688,131 @@ impl QueryResolver {
         {
             res.vote_account = Some(bam_validator.get_vote_account());
             res.score = bam_validator.get_score();
+            res.is_eligible = Some(bam_validator.is_eligible());
+            res.ineligibility_reasons = bam_validator.get_ineligibility_reasons().to_vec();
         }
 
         Ok(res)
     }
 
+    /// Retrieves the BAM delegation plan of an epoch, the latest one when unset
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// GET /bam_delegation_plan?epoch=800
+    /// ```
+    /// This request retrieves the per-validator directed stake targets for epoch 800.
+    pub async fn get_bam_delegation_plan(
+        &self,
+        epoch: Option<u64>,
+    ) -> Result<BamDelegationPlanResponse> {
+        let bam_delegation_plan = self.bam_delegation_plan_store.find_by_epoch(epoch).await?;
+
+        Ok(BamDelegationPlanResponse {
+            bam_delegation_plan,
+        })
+    }
+
+    /// What-if BAM eligibility of a validator for the next epoch
+    ///
+    /// The lookback windows end at the current epoch, whose vote credits and client are still
+    /// being recorded, so the answer assumes the validator keeps its current behavior until the
+    /// epoch ends.
+    ///
+    /// Runs the checks of the BAM writer against the current validator history, the steward
+    /// config blacklist and the BAM delegation blacklist, with the eligibility rules of the latest
+    /// BAM epoch metrics record, or the embedded JIP-28 rules when no record has them.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// GET /bam_eligibility/J1to1yufRnoWn81KYg1XkTWzmKjnYSnmE2VY8DGUJ9Qv
+    /// ```
+    pub async fn get_bam_eligibility(&self, vote_account: &str) -> Result<BamEligibilityResponse> {
+        let vote_account = Pubkey::from_str(vote_account).map_err(|e| {
+            QueryResolverError::InvalidRequest(format!("invalid vote account {vote_account}: {e}"))
+        })?;
+
+        let epoch = self
+            .rpc_client
+            .get_epoch_info()
+            .await
+            .map_err(|e| QueryResolverError::RpcError(e.to_string()))?
+            .epoch
+            + 1;
+        let eligibility_rules = match self
+            .bam_epoch_metrics_store
+            .find_latest_with_eligibility_rules()
+            .await?
+            .and_then(|metrics| metrics.get_eligibility_rules().cloned())
+        {
+            Some(eligibility_rules) => eligibility_rules,
+            None => BamEligibilityRules::load(None)
+                .map_err(|e| QueryResolverError::CustomError(e.to_string()))?,
+        };
+        let mut res = BamEligibilityResponse {
+            epoch,
+            eligibility_rules_version: eligibility_rules.version.clone(),
+            ..Default::default()
+        };
+
+        let history_address = get_validator_history_address(
+            &vote_account,
+            &self.cluster_config.validator_history_program_id,
+        );
+        let Some(history_account) = self
+            .rpc_client
+            .get_account_with_commitment(&history_address, self.rpc_client.commitment())
+            .await
+            .map_err(|e| QueryResolverError::RpcError(e.to_string()))?
+            .value
+        else {
+            return Ok(res);
+        };
+        let validator_history = ValidatorHistory::try_deserialize(
+            &mut history_account.data.as_slice(),
+        )
+        .map_err(|e| {
+            error!("error deserializing ValidatorHistory: {e:?}");
+            QueryResolverError::ValidatorHistoryError("Error parsing ValidatorHistory".to_string())
+        })?;
+
+        let all_steward_accounts = get_all_steward_accounts(
+            &self.rpc_client,
+            &self.cluster_config.steward_program_id,
+            &self.cluster_config.steward_config,
+        )
+        .await?;
+        let blacklist_validators: Vec<Pubkey> = self
+            .bam_delegation_blacklist_store
+            .find_active(Utc::now())
+            .await?
+            .iter()
+            .filter_map(|entry| Pubkey::from_str(entry.get_vote_account()).ok())
+            .collect();
+
+        let eligibility_checker = bam_eligibility_checker(
+            &self.rpc_client,
+            self.cluster_config.validator_history_program_id,
+            epoch,
+            &eligibility_rules,
+        )
+        .await?;
+        let statuses = eligibility_checker.evaluate(
+            &blacklist_validators,
+            &all_steward_accounts.config_account,
+            &validator_history,
+        );
+
+        res.vote_account = Some(vote_account.to_string());
+        res.eligible = statuses.iter().all(|status| status.passed);
+        res.eligible_from_epoch = eligibility_checker.eligible_from_epoch(&statuses);
+        res.criteria = statuses.into_iter().map(Into::into).collect();
+
+        Ok(res)
+    }
+
     pub async fn get_preferred_withdraw_validator_list(
         &self,
         min_stake_threshold: u64,
@@ -1154,9 +1820,12 @@ impl QueryResolver {
         let min_retained_balance: u64 = 1_000 * LAMPORTS_PER_SOL;
 
         // Get all steward accounts
-        let all_steward_accounts =
-            get_all_steward_accounts(&self.rpc_client, &jito_steward::ID, &self.steward_config)
-                .await?;
+        let all_steward_accounts = get_all_steward_accounts(
+            &self.rpc_client,
+            &self.cluster_config.steward_program_id,
+            &self.cluster_config.steward_config,
+        )
+        .await?;
         let steward_state = &all_steward_accounts.state_account.state;
         let validator_list = &all_steward_accounts.validator_list_account;
 
@@ -1216,12 +1885,240 @@ impl QueryResolver {
         Ok(preferred_withdraw_list)
     }
 
-    /// Retrieves the blacklist for bam delegation
+    /// Retrieves the blacklist for bam delegation, expired entries excluded
     pub async fn get_bam_delegation_blacklist(&self) -> Result<Vec<BamDelegationBlacklistEntry>> {
-        let entries = self.bam_delegation_blacklist_store.find().await?;
+        let entries = self
+            .bam_delegation_blacklist_store
+            .find_active(Utc::now())
+            .await?;
         Ok(entries)
     }
 
+    /// Blacklist a validator on behalf of `actor`, and record the change in the audit log
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// POST /admin/bam_delegation_blacklist
+    /// {"vote_account": "J1to1yufRnoWn81KYg1XkTWzmKjnYSnmE2VY8DGUJ9Qv", "reason": "sandwiching"}
+    /// ```
+    pub async fn add_bam_delegation_blacklist_entry(
+        &self,
+        actor: &Pubkey,
+        request: AddBamDelegationBlacklistRequest,
+    ) -> Result<BamDelegationBlacklistAuditEntry> {
+        let vote_account = parse_vote_account(&request.vote_account)?;
+
+        if self
+            .bam_delegation_blacklist_store
+            .find_by_vote_account(&vote_account)
+            .await?
+            .is_some()
+        {
+            return Err(QueryResolverError::InvalidRequest(format!(
+                "{vote_account} is already blacklisted"
+            )));
+        }
+
+        let added_epoch = match request.added_epoch {
+            Some(added_epoch) => added_epoch,
+            None => {
+                self.rpc_client
+                    .get_epoch_info()
+                    .await
+                    .map_err(|e| QueryResolverError::RpcError(e.to_string()))?
+                    .epoch
+            }
+        };
+
+        let mut entry = BamDelegationBlacklistEntry::new(&vote_account, added_epoch)
+            .with_reason(&request.reason)
+            .with_added_by(&actor.to_string());
+        entry.set_expires_at(request.expires_at);
+
+        // A concurrent add of the same vote account is rejected by the unique index
+        let insert = async {
+            self.bam_delegation_blacklist_store
+                .insert(entry.clone())
+                .await
+                .map_err(|e| {
+                    if is_duplicate_key_error(&e) {
+                        QueryResolverError::InvalidRequest(format!(
+                            "{vote_account} is already blacklisted"
+                        ))
+                    } else {
+                        e.into()
+                    }
+                })
+        };
+        self.apply_bam_delegation_blacklist_change(
+            &vote_account,
+            BamDelegationBlacklistAction::Add,
+            actor,
+            None,
+            Some(entry.clone()),
+            insert,
+        )
+        .await
+    }
+
+    /// Replace the reason and expiry of a blacklist entry on behalf of `actor`, and record the
+    /// change in the audit log
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// PUT /admin/bam_delegation_blacklist/J1to1yufRnoWn81KYg1XkTWzmKjnYSnmE2VY8DGUJ9Qv
+    /// {"reason": "sandwiching", "expires_at": 1767225600}
+    /// ```
+    pub async fn update_bam_delegation_blacklist_entry(
+        &self,
+        actor: &Pubkey,
+        vote_account: &str,
+        request: UpdateBamDelegationBlacklistRequest,
+    ) -> Result<BamDelegationBlacklistAuditEntry> {
+        let vote_account = parse_vote_account(vote_account)?;
+
+        let before = self
+            .bam_delegation_blacklist_store
+            .find_by_vote_account(&vote_account)
+            .await?
+            .ok_or_else(|| {
+                QueryResolverError::NotFound(format!("Blacklist entry of {vote_account}"))
+            })?;
+
+        let mut after = before.clone();
+        after.set_reason(Some(request.reason));
+        after.set_expires_at(request.expires_at);
+
+        let replace = async {
+            if self.bam_delegation_blacklist_store.replace(&after).await? {
+                Ok(())
+            } else {
+                Err(QueryResolverError::NotFound(format!(
+                    "Blacklist entry of {vote_account}"
+                )))
+            }
+        };
+        self.apply_bam_delegation_blacklist_change(
+            &vote_account,
+            BamDelegationBlacklistAction::Update,
+            actor,
+            Some(before.clone()),
+            Some(after.clone()),
+            replace,
+        )
+        .await
+    }
+
+    /// Remove a validator from the blacklist on behalf of `actor`, and record the change in the
+    /// audit log
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// DELETE /admin/bam_delegation_blacklist/J1to1yufRnoWn81KYg1XkTWzmKjnYSnmE2VY8DGUJ9Qv
+    /// ```
+    pub async fn remove_bam_delegation_blacklist_entry(
+        &self,
+        actor: &Pubkey,
+        vote_account: &str,
+    ) -> Result<BamDelegationBlacklistAuditEntry> {
+        let vote_account = parse_vote_account(vote_account)?;
+
+        let before = self
+            .bam_delegation_blacklist_store
+            .find_by_vote_account(&vote_account)
+            .await?
+            .ok_or_else(|| {
+                QueryResolverError::NotFound(format!("Blacklist entry of {vote_account}"))
+            })?;
+
+        let delete = async {
+            self.bam_delegation_blacklist_store
+                .delete(&vote_account)
+                .await?;
+            Ok(())
+        };
+        self.apply_bam_delegation_blacklist_change(
+            &vote_account,
+            BamDelegationBlacklistAction::Remove,
+            actor,
+            Some(before),
+            None,
+            delete,
+        )
+        .await
+    }
+
+    /// Record a change of the BAM delegation blacklist in the audit log, then `apply` it
+    ///
+    /// The audit entry is written first, so no change goes live unaudited. When `apply` fails, a
+    /// `failed` entry pointing at it is appended and the error of `apply` returned.
+    async fn apply_bam_delegation_blacklist_change(
+        &self,
+        vote_account: &str,
+        action: BamDelegationBlacklistAction,
+        actor: &Pubkey,
+        before: Option<BamDelegationBlacklistEntry>,
+        after: Option<BamDelegationBlacklistEntry>,
+        apply: impl Future<Output = Result<()>>,
+    ) -> Result<BamDelegationBlacklistAuditEntry> {
+        let audit_entry = BamDelegationBlacklistAuditEntry::new(
+            vote_account,
+            action,
+            &actor.to_string(),
+            before,
+            after,
+        );
+        if let Err(e) = self
+            .bam_delegation_blacklist_audit_store
+            .insert(&audit_entry)
+            .await
+        {
+            error!("Failed to record BAM delegation blacklist change {audit_entry:?}: {e}");
+            return Err(e.into());
+        }
+
+        if let Err(e) = apply.await {
+            let failed_entry = BamDelegationBlacklistAuditEntry::failed(&audit_entry);
+            if let Err(audit_error) = self
+                .bam_delegation_blacklist_audit_store
+                .insert(&failed_entry)
+                .await
+            {
+                error!(
+                    "Failed to record that BAM delegation blacklist change {audit_entry:?} failed: {audit_error}"
+                );
+            }
+            return Err(e);
+        }
+
+        Ok(audit_entry)
+    }
+
+    /// Changes made to the BAM delegation blacklist through the admin API, latest first
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// GET /bam_delegation_blacklist/audit_log?vote_account=J1to1yufRnoWn81KYg1XkTWzmKjnYSnmE2VY8DGUJ9Qv&limit=10
+    /// ```
+    pub async fn get_bam_delegation_blacklist_audit_log(
+        &self,
+        request: &BamDelegationBlacklistAuditLogRequest,
+    ) -> Result<BamDelegationBlacklistAuditLogResponse> {
+        let entries = self
+            .bam_delegation_blacklist_audit_store
+            .find(
+                request.vote_account.as_deref(),
+                request.limit.unwrap_or(DEFAULT_AUDIT_LOG_LIMIT),
+            )
+            .await?;
+
+        Ok(BamDelegationBlacklistAuditLogResponse { entries })
+    }
+
     /// BAM Boost merkle tree
     pub async fn get_bam_boost_merkle_tree(
         &self,
@@ -1294,8 +2191,11 @@ impl QueryResolver {
         match tree_node {
             Some(node) => {
                 let proof = node.proof.clone().unwrap_or_default();
-                let distributor_address =
-                    merkle_distributor_address(self.jito_bam_boost_program_id, JITOSOL_MINT, epoch);
+                let distributor_address = merkle_distributor_address(
+                    self.cluster_config.bam_boost_program_id,
+                    self.cluster_config.jitosol_mint,
+                    epoch,
+                );
                 Ok(BamBoostClaimResponse {
                     claimant: node.claimant.to_string(),
                     proof,
@@ -1303,7 +2203,7 @@ impl QueryResolver {
                     merkle_root: merkle_tree.merkle_root,
                     distributor_address: distributor_address.to_string(),
                     claim_status_address: claim_status_address(
-                        self.jito_bam_boost_program_id,
+                        self.cluster_config.bam_boost_program_id,
                         node.claimant,
                         distributor_address,
                     )
@@ -1356,3 +2256,74 @@ pub async fn get_daily_mev_rewards(dune_api_key: &str) -> Result<Vec<Row>> {
     let json: ApiResponse = body.json().await?;
     Ok(json.result.rows)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::future;
+
+    use super::*;
+
+    fn event(slot: u64, event_idx: u32) -> steward_events::StewardEvent {
+        steward_events::StewardEvent {
+            signature: "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d".to_string(),
+            instruction_idx: 0,
+            inner_instruction_idx: None,
+            event_idx,
+            event_type: "StateTransition".to_string(),
+            vote_account: None,
+            metadata: None,
+            tx_error: None,
+            signer: "signer".to_string(),
+            stake_pool: "pool".to_string(),
+            epoch: 800,
+            slot,
+            timestamp: None,
+            commitment: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_steward_events_advances_cursor() {
+        let start = StewardEventsCursor::after(&event(100, 0));
+        let mut polls = Vec::new();
+        let mut batches = vec![vec![], vec![event(101, 0), event(101, 1)]].into_iter();
+
+        let (events, cursor) = poll_steward_events(
+            |cursor| {
+                polls.push(cursor);
+                future::ready(mongodb::error::Result::Ok(batches.next().unwrap()))
+            },
+            Some(start.clone()),
+            Duration::ZERO,
+        )
+        .await
+        .unwrap();
+
+        // The empty poll is retried from the same cursor
+        assert_eq!(polls, vec![Some(start.clone()), Some(start)]);
+        assert_eq!(events.len(), 2);
+        assert_eq!(cursor, StewardEventsCursor::after(&event(101, 1)));
+    }
+
+    #[tokio::test]
+    async fn test_poll_steward_events_from_next_cursor() {
+        let mut polls = Vec::new();
+        let mut batches = vec![vec![event(100, 0)], vec![event(102, 0)]].into_iter();
+        let mut fetch = |cursor: Option<StewardEventsCursor>| {
+            polls.push(cursor);
+            future::ready(mongodb::error::Result::Ok(batches.next().unwrap()))
+        };
+
+        let (_, cursor) = poll_steward_events(&mut fetch, None, Duration::ZERO)
+            .await
+            .unwrap();
+        let (events, next_cursor) =
+            poll_steward_events(&mut fetch, Some(cursor.clone()), Duration::ZERO)
+                .await
+                .unwrap();
+
+        assert_eq!(polls, vec![None, Some(cursor)]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(next_cursor, StewardEventsCursor::after(&event(102, 0)));
+    }
+}
@@ -0,0 +1,94 @@
+use chrono::{serde::ts_seconds_option, DateTime, Utc};
+use kobe_core::db_models::bam_delegation_blacklist_audit::BamDelegationBlacklistAuditEntry;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ApiError;
+
+/// Audit log entries returned when the request sets no limit
+pub const DEFAULT_AUDIT_LOG_LIMIT: i64 = 100;
+
+/// Maximum number of audit log entries returned by a request
+pub const MAX_AUDIT_LOG_LIMIT: i64 = 1000;
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AddBamDelegationBlacklistRequest {
+    /// Vote account pubkey
+    pub vote_account: String,
+
+    /// Why the validator is blacklisted
+    pub reason: String,
+
+    /// Epoch the validator is blacklisted in, current epoch by default
+    pub added_epoch: Option<u64>,
+
+    /// Unix timestamp the entry stops applying at, never when unset
+    #[serde(default, with = "ts_seconds_option")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl AddBamDelegationBlacklistRequest {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        validate_entry(&self.reason, self.expires_at)
+    }
+}
+
+/// Replaces the reason and expiry of a blacklist entry
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateBamDelegationBlacklistRequest {
+    /// Why the validator is blacklisted
+    pub reason: String,
+
+    /// Unix timestamp the entry stops applying at, never when unset
+    #[serde(default, with = "ts_seconds_option")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl UpdateBamDelegationBlacklistRequest {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        validate_entry(&self.reason, self.expires_at)
+    }
+}
+
+fn validate_entry(reason: &str, expires_at: Option<DateTime<Utc>>) -> Result<(), ApiError> {
+    if reason.trim().is_empty() {
+        return Err(ApiError::validation_error("reason must not be empty"));
+    }
+    if matches!(expires_at, Some(expires_at) if expires_at <= Utc::now()) {
+        return Err(ApiError::validation_error(
+            "expires_at must be in the future",
+        ));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct BamDelegationBlacklistAuditLogRequest {
+    /// Vote account pubkey, every vote account when unset
+    pub vote_account: Option<String>,
+
+    /// Maximum number of entries, latest first
+    pub limit: Option<i64>,
+}
+
+impl BamDelegationBlacklistAuditLogRequest {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        if let Some(limit) = self.limit {
+            if !(1..=MAX_AUDIT_LOG_LIMIT).contains(&limit) {
+                return Err(ApiError::validation_error(format!(
+                    "limit must be between 1 and {MAX_AUDIT_LOG_LIMIT}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BamDelegationBlacklistAuditLogResponse {
+    /// Audit log entries, latest first
+    pub entries: Vec<BamDelegationBlacklistAuditEntry>,
+}
@@ -0,0 +1,13 @@
+use kobe_core::db_models::bam_delegation_plans::BamDelegationPlan;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BamDelegationPlanRequest {
+    /// Epoch of the plan, latest plan when unset
+    pub epoch: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BamDelegationPlanResponse {
+    pub bam_delegation_plan: Option<BamDelegationPlan>,
+}
@@ -0,0 +1,44 @@
+use kobe_core::bam_validator_eligibility::BamCriterionStatus;
+use serde::{Deserialize, Serialize};
+
+/// Status of an eligibility criterion, with a readable summary of it
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BamCriterionStatusResponse {
+    #[serde(flatten)]
+    pub status: BamCriterionStatus,
+
+    /// Summary of the status, e.g. "running BAM for 1 of 3 epochs"
+    pub summary: String,
+}
+
+impl From<BamCriterionStatus> for BamCriterionStatusResponse {
+    fn from(status: BamCriterionStatus) -> Self {
+        Self {
+            summary: status.to_string(),
+            status,
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct BamEligibilityResponse {
+    /// Vote account pubkey, `None` when the validator has no validator history
+    pub vote_account: Option<String>,
+
+    /// Next epoch, eligibility is checked for. The lookbacks end at the current epoch, whose data
+    /// is still partial
+    pub epoch: u64,
+
+    /// Version of the eligibility rules checked
+    pub eligibility_rules_version: String,
+
+    /// Whether the validator would be eligible for BAM delegation
+    pub eligible: bool,
+
+    /// Earliest epoch the validator can become eligible if it meets every criterion from now on,
+    /// `None` while blacklisted
+    pub eligible_from_epoch: Option<u64>,
+
+    /// Status of every eligibility criterion
+    pub criteria: Vec<BamCriterionStatusResponse>,
+}
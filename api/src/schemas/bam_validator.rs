@@ -1,4 +1,4 @@
-use kobe_core::db_models::bam_validators::BamValidator;
+use kobe_core::db_models::bam_validators::{BamIneligibilityReason, BamValidator};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -43,4 +43,10 @@ pub struct BamValidatorScoreResponse {
 
     /// BAM delegation score
     pub score: Option<u8>,
+
+    /// Whether the validator is eligible for BAM delegation
+    pub is_eligible: Option<bool>,
+
+    /// Every failed eligibility criterion
+    pub ineligibility_reasons: Vec<BamIneligibilityReason>,
 }
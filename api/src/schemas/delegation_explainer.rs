@@ -0,0 +1,907 @@
+use std::collections::HashMap;
+
+use kobe_core::{db_models::steward_events::StewardEvent, LAMPORTS_PER_SOL};
+use mongodb::bson::Document;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ApiError,
+    schemas::{validator_history::ValidatorHistoryEntryResponse, validator_score::number},
+};
+
+/// Epochs explained when the request sets no range
+pub const DEFAULT_EPOCHS: u64 = 10;
+
+/// Maximum number of epochs explained by a request
+pub const MAX_EPOCHS: u64 = 50;
+
+/// Steward events the explainer builds the timeline from, `DecreaseComponents` are read by the
+/// signature of the rebalances
+pub const EXPLAINER_EVENT_TYPES: [&str; 11] = [
+    "ScoreComponents",
+    "ScoreComponentsV2",
+    "ScoreComponentsV3",
+    "ScoreComponentsV4",
+    "InstantUnstakeComponents",
+    "InstantUnstakeComponentsV2",
+    "InstantUnstakeComponentsV3",
+    "RebalanceEvent",
+    "DirectedRebalanceEvent",
+    "AutoAddValidatorEvent",
+    "AutoRemoveValidatorEvent",
+];
+
+/// Score components of the ScoreComponents events and the check they stand for
+const SCORE_COMPONENTS: [(&str, &str); 10] = [
+    ("mev_commission_score", "MEV commission"),
+    ("commission_score", "commission"),
+    ("historical_commission_score", "historical commission"),
+    ("blacklisted_score", "blacklist"),
+    ("superminority_score", "superminority"),
+    ("delinquency_score", "delinquency"),
+    ("running_jito_score", "running Jito"),
+    (
+        "merkle_root_upload_authority_score",
+        "merkle root upload authority",
+    ),
+    ("priority_fee_commission_score", "priority fee commission"),
+    (
+        "priority_fee_merkle_root_upload_authority_score",
+        "priority fee merkle root upload authority",
+    ),
+];
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DelegationExplainerRequest {
+    /// First epoch explained, set along with `end_epoch`; the last [`DEFAULT_EPOCHS`] epochs by
+    /// default
+    pub start_epoch: Option<u64>,
+
+    /// Last epoch explained, set along with `start_epoch`; the current epoch by default
+    pub end_epoch: Option<u64>,
+}
+
+impl DelegationExplainerRequest {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        match (self.start_epoch, self.end_epoch) {
+            (None, None) => Ok(()),
+            (Some(start_epoch), Some(end_epoch)) => {
+                if start_epoch > end_epoch {
+                    return Err(ApiError::validation_error(
+                        "Invalid epoch range: start_epoch must not be after end_epoch",
+                    ));
+                }
+                if end_epoch - start_epoch >= MAX_EPOCHS {
+                    return Err(ApiError::validation_error(format!(
+                        "Invalid epoch range: at most {MAX_EPOCHS} epochs can be explained"
+                    )));
+                }
+                Ok(())
+            }
+            _ => Err(ApiError::validation_error(
+                "Invalid epoch range: start_epoch and end_epoch must be set together",
+            )),
+        }
+    }
+}
+
+/// Epochs explained when the request sets no range: the last [`DEFAULT_EPOCHS`] up to
+/// `current_epoch`
+pub fn default_epoch_range(current_epoch: u64) -> (u64, u64) {
+    (
+        current_epoch.saturating_sub(DEFAULT_EPOCHS - 1),
+        current_epoch,
+    )
+}
+
+impl std::fmt::Display for DelegationExplainerRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DelegationExplainerRequest {{ start_epoch: {:?}, end_epoch: {:?} }}",
+            self.start_epoch, self.end_epoch
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub epoch: u64,
+
+    /// Slot of the steward event, `None` for validator history entries
+    pub slot: Option<u64>,
+
+    pub signature: Option<String>,
+
+    /// Steward event type, or `ValidatorHistory`
+    pub source: String,
+
+    pub description: String,
+
+    /// Stake moved to (positive) or from (negative) the validator
+    pub lamports: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProbableCause {
+    /// Epoch the stake was moved in
+    pub epoch: u64,
+
+    /// Stake moved away from the validator for this cause
+    pub lamports: u64,
+
+    pub cause: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DelegationExplainerResponse {
+    pub vote_account: String,
+    pub start_epoch: u64,
+    pub end_epoch: u64,
+
+    /// Stake moved to the validator minus stake moved away by successful rebalances
+    pub net_lamports: i64,
+
+    /// Reasons stake was moved away from the validator, oldest first
+    pub probable_causes: Vec<ProbableCause>,
+
+    /// Steward events and validator history changes, oldest first
+    pub timeline: Vec<TimelineEntry>,
+}
+
+/// Value of a validator history field changing between two recorded epochs
+#[derive(Debug, Clone, Copy)]
+struct Change {
+    epoch: u64,
+    from: u64,
+    to: u64,
+}
+
+/// Decrease of a rebalance split by reason
+#[derive(Debug, Clone, Copy, Default)]
+struct Decrease {
+    scoring: u64,
+    instant_unstake: u64,
+    stake_deposit: u64,
+}
+
+impl Decrease {
+    /// Read the fields shared by `DecreaseComponents` and the decrease of `RebalanceEvent`
+    fn from_document(document: &Document) -> Self {
+        let lamports = |key| number(document, key).unwrap_or_default() as u64;
+        Self {
+            scoring: lamports("scoring_unstake_lamports"),
+            instant_unstake: lamports("instant_unstake_lamports"),
+            stake_deposit: lamports("stake_deposit_unstake_lamports"),
+        }
+    }
+
+    fn total(&self) -> u64 {
+        self.scoring + self.instant_unstake + self.stake_deposit
+    }
+}
+
+/// Timeline and probable causes of the stake moved to and from a validator
+pub struct DelegationExplainer<'a> {
+    /// Steward events of the validator, oldest first
+    events: &'a [StewardEvent],
+
+    /// `DecreaseComponents` by (signature, instruction_idx) of the rebalance they belong to
+    decrease_components: HashMap<(&'a str, u32), Decrease>,
+
+    /// Validator history, oldest first
+    history: &'a [ValidatorHistoryEntryResponse],
+
+    commission_changes: Vec<Change>,
+    mev_commission_changes: Vec<Change>,
+}
+
+impl<'a> DelegationExplainer<'a> {
+    pub fn new(
+        events: &'a [StewardEvent],
+        decrease_components: &'a [StewardEvent],
+        history: &'a [ValidatorHistoryEntryResponse],
+    ) -> Self {
+        let decrease_components = decrease_components
+            .iter()
+            .filter_map(|event| {
+                let decrease = Decrease::from_document(event.metadata.as_ref()?);
+                Some(((event.signature.as_str(), event.instruction_idx), decrease))
+            })
+            .collect();
+
+        Self {
+            events,
+            decrease_components,
+            history,
+            commission_changes: changes(history, |entry| {
+                (entry.commission != u8::MAX).then_some(entry.commission as u64)
+            }),
+            mev_commission_changes: changes(history, |entry| {
+                (entry.mev_commission != u16::MAX).then_some(entry.mev_commission as u64)
+            }),
+        }
+    }
+
+    pub fn explain(
+        &self,
+        vote_account: &str,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> DelegationExplainerResponse {
+        let mut timeline = self.history_timeline(start_epoch, end_epoch);
+        let mut causes: Vec<ProbableCause> = vec![];
+        let mut net_lamports = 0;
+
+        for event in self.events {
+            let metadata = event.metadata.clone().unwrap_or_default();
+            let (description, lamports) = match event.event_type.as_str() {
+                "RebalanceEvent" => {
+                    let increase =
+                        number(&metadata, "increase_lamports").unwrap_or_default() as u64;
+                    let decrease = self
+                        .decrease_components
+                        .get(&(event.signature.as_str(), event.instruction_idx))
+                        .copied()
+                        .or_else(|| {
+                            metadata
+                                .get_document("decrease_components")
+                                .ok()
+                                .map(Decrease::from_document)
+                        })
+                        .unwrap_or_default();
+                    if event.tx_error.is_none() {
+                        causes.extend(self.decrease_causes(event, &decrease));
+                    }
+                    rebalance_description(increase, &decrease)
+                }
+                "DirectedRebalanceEvent" => {
+                    let increase =
+                        number(&metadata, "increase_lamports").unwrap_or_default() as u64;
+                    let decrease =
+                        number(&metadata, "decrease_lamports").unwrap_or_default() as u64;
+                    if event.tx_error.is_none() && decrease > 0 {
+                        causes.push(ProbableCause {
+                            epoch: event.epoch,
+                            lamports: decrease,
+                            cause: "Directed stake was moved to other validators".to_string(),
+                        });
+                    }
+                    let lamports = increase as i64 - decrease as i64;
+                    (
+                        format!("Directed stake changed by {}", sol(lamports)),
+                        Some(lamports),
+                    )
+                }
+                "AutoAddValidatorEvent" => ("Added to the pool".to_string(), None),
+                "AutoRemoveValidatorEvent" => {
+                    let description = removal_description(&metadata);
+                    if event.tx_error.is_none() {
+                        causes.push(ProbableCause {
+                            epoch: event.epoch,
+                            lamports: 0,
+                            cause: description.clone(),
+                        });
+                    }
+                    (description, None)
+                }
+                event_type if event_type.starts_with("ScoreComponents") => {
+                    (score_description(&metadata), None)
+                }
+                event_type if event_type.starts_with("InstantUnstakeComponents") => {
+                    let reasons = instant_unstake_reasons(&metadata);
+                    let description = if reasons.is_empty() {
+                        "Passed the instant unstake checks".to_string()
+                    } else {
+                        format!("Flagged for instant unstake: {}", reasons.join(", "))
+                    };
+                    (description, None)
+                }
+                _ => continue,
+            };
+
+            let (description, lamports) = match &event.tx_error {
+                Some(tx_error) => (
+                    format!("{description} (transaction failed: {tx_error})"),
+                    None,
+                ),
+                None => {
+                    net_lamports += lamports.unwrap_or_default();
+                    (description, lamports)
+                }
+            };
+            timeline.push(TimelineEntry {
+                epoch: event.epoch,
+                slot: Some(event.slot),
+                signature: Some(event.signature.clone()),
+                source: event.event_type.clone(),
+                description,
+                lamports,
+            });
+        }
+
+        // History entries first within an epoch
+        timeline.sort_by_key(|entry| (entry.epoch, entry.slot));
+
+        DelegationExplainerResponse {
+            vote_account: vote_account.to_string(),
+            start_epoch,
+            end_epoch,
+            net_lamports,
+            probable_causes: merge_causes(causes),
+            timeline,
+        }
+    }
+
+    /// Commission, MEV commission, superminority and vote credit changes of the validator history
+    fn history_timeline(&self, start_epoch: u64, end_epoch: u64) -> Vec<TimelineEntry> {
+        let mut timeline = vec![];
+        let in_range = |epoch: u64| (start_epoch..=end_epoch).contains(&epoch);
+        let mut entry = |epoch: u64, description: String| {
+            timeline.push(TimelineEntry {
+                epoch,
+                slot: None,
+                signature: None,
+                source: "ValidatorHistory".to_string(),
+                description,
+                lamports: None,
+            })
+        };
+
+        for change in self.commission_changes.iter().filter(|c| in_range(c.epoch)) {
+            entry(
+                change.epoch,
+                format!("Commission changed from {}% to {}%", change.from, change.to),
+            );
+        }
+        for change in self
+            .mev_commission_changes
+            .iter()
+            .filter(|c| in_range(c.epoch))
+        {
+            entry(
+                change.epoch,
+                format!(
+                    "MEV commission changed from {} bps to {} bps",
+                    change.from, change.to
+                ),
+            );
+        }
+        for pair in self.history.windows(2) {
+            let (previous, current) = (&pair[0], &pair[1]);
+            let epoch = current.epoch as u64;
+            if !in_range(epoch) {
+                continue;
+            }
+            match (previous.is_superminority, current.is_superminority) {
+                (0, 1) => entry(epoch, "Entered the superminority".to_string()),
+                (1, 0) => entry(epoch, "Left the superminority".to_string()),
+                _ => {}
+            }
+            if current.epoch_credits == 0 {
+                entry(epoch, "Earned no vote credits".to_string());
+            }
+        }
+
+        timeline
+    }
+
+    /// Causes of the decrease of a successful rebalance
+    fn decrease_causes(&self, rebalance: &StewardEvent, decrease: &Decrease) -> Vec<ProbableCause> {
+        let mut causes = vec![];
+        let mut cause = |lamports: u64, description: String| {
+            if lamports > 0 {
+                causes.push(ProbableCause {
+                    epoch: rebalance.epoch,
+                    lamports,
+                    cause: description,
+                });
+            }
+        };
+
+        cause(
+            decrease.instant_unstake,
+            self.instant_unstake_cause(rebalance),
+        );
+        cause(decrease.scoring, self.scoring_cause(rebalance));
+        cause(
+            decrease.stake_deposit,
+            "Stake deposited above the validator target was moved to other validators".to_string(),
+        );
+
+        causes
+    }
+
+    /// Latest successful event of one of the `event_type` versions emitted up to `slot`
+    fn latest_event(&self, event_type: &str, slot: u64) -> Option<&StewardEvent> {
+        self.events.iter().rev().find(|event| {
+            event.slot <= slot
+                && event.tx_error.is_none()
+                && event.event_type.starts_with(event_type)
+        })
+    }
+
+    fn instant_unstake_cause(&self, rebalance: &StewardEvent) -> String {
+        let reasons = self
+            .latest_event("InstantUnstakeComponents", rebalance.slot)
+            .filter(|event| event.epoch == rebalance.epoch)
+            .and_then(|event| event.metadata.as_ref())
+            .map(|metadata| self.instant_unstake_causes(metadata, rebalance.epoch))
+            .unwrap_or_default();
+
+        if reasons.is_empty() {
+            format!("Instantly unstaked in epoch {}", rebalance.epoch)
+        } else {
+            reasons.join("; ")
+        }
+    }
+
+    fn instant_unstake_causes(&self, metadata: &Document, epoch: u64) -> Vec<String> {
+        let check = |key| metadata.get_bool(key).unwrap_or_default();
+        let mut causes = vec![];
+
+        if check("commission_check") {
+            causes.push(self.commission_cause(
+                &self.commission_changes,
+                "Commission",
+                "%",
+                epoch,
+                "an instant unstake",
+            ));
+        }
+        if check("mev_commission_check") {
+            causes.push(self.commission_cause(
+                &self.mev_commission_changes,
+                "MEV commission",
+                " bps",
+                epoch,
+                "an instant unstake",
+            ));
+        }
+        if check("delinquency_check") {
+            causes.push(format!(
+                "Vote credits below the delinquency threshold in epoch {epoch} triggered an instant unstake"
+            ));
+        }
+        if check("is_blacklisted") {
+            causes.push(
+                "The validator is blacklisted, which triggered an instant unstake".to_string(),
+            );
+        }
+        if check("is_bad_merkle_root_upload_authority") {
+            causes.push(
+                "Unexpected tip distribution merkle root upload authority triggered an instant unstake"
+                    .to_string(),
+            );
+        }
+        if check("is_bad_priority_fee_merkle_root_upload_authority") {
+            causes.push(
+                "Unexpected priority fee distribution merkle root upload authority triggered an instant unstake"
+                    .to_string(),
+            );
+        }
+
+        causes
+    }
+
+    fn scoring_cause(&self, rebalance: &StewardEvent) -> String {
+        let Some((score_event, metadata)) = self
+            .latest_event("ScoreComponents", rebalance.slot)
+            .and_then(|event| Some((event, event.metadata.as_ref()?)))
+        else {
+            return "Stake was moved to higher scoring validators".to_string();
+        };
+
+        let score = number(metadata, "score").unwrap_or_default();
+        if score > 0.0 {
+            return format!(
+                "Score of {score} in epoch {} ranked below the validators selected for delegation",
+                score_event.epoch
+            );
+        }
+
+        let failed = failed_components(metadata);
+        let mut cause = format!(
+            "Score dropped to zero in epoch {}, failing the {} check",
+            score_event.epoch,
+            failed.join(", ")
+        );
+        if failed.contains(&"commission") || failed.contains(&"historical commission") {
+            cause = self.commission_cause(
+                &self.commission_changes,
+                "Commission",
+                "%",
+                score_event.epoch,
+                &format!("a zero score in epoch {}", score_event.epoch),
+            );
+        } else if failed.contains(&"MEV commission") {
+            cause = self.commission_cause(
+                &self.mev_commission_changes,
+                "MEV commission",
+                " bps",
+                score_event.epoch,
+                &format!("a zero score in epoch {}", score_event.epoch),
+            );
+        }
+
+        cause
+    }
+
+    /// Attribute `consequence` to the latest increase of a commission up to `epoch`
+    fn commission_cause(
+        &self,
+        changes: &[Change],
+        name: &str,
+        unit: &str,
+        epoch: u64,
+        consequence: &str,
+    ) -> String {
+        match changes
+            .iter()
+            .rev()
+            .find(|change| change.epoch <= epoch && change.to > change.from)
+        {
+            Some(change) => format!(
+                "{name} increase from {}{unit} to {}{unit} in epoch {} triggered {consequence}",
+                change.from, change.to, change.epoch
+            ),
+            None => format!("{name} above the steward threshold triggered {consequence}"),
+        }
+    }
+}
+
+/// Changes of a validator history field, skipping the epochs it is unset in
+fn changes(
+    history: &[ValidatorHistoryEntryResponse],
+    value: impl Fn(&ValidatorHistoryEntryResponse) -> Option<u64>,
+) -> Vec<Change> {
+    let mut changes = vec![];
+    let mut previous: Option<u64> = None;
+    for entry in history {
+        let Some(current) = value(entry) else {
+            continue;
+        };
+        if let Some(from) = previous.filter(|&from| from != current) {
+            changes.push(Change {
+                epoch: entry.epoch as u64,
+                from,
+                to: current,
+            });
+        }
+        previous = Some(current);
+    }
+
+    changes
+}
+
+fn rebalance_description(increase: u64, decrease: &Decrease) -> (String, Option<i64>) {
+    let lamports = increase as i64 - decrease.total() as i64;
+    if decrease.total() == 0 {
+        return (
+            format!("Stake increased by {}", sol(lamports)),
+            Some(lamports),
+        );
+    }
+
+    (
+        format!(
+            "Stake decreased by {} (scoring {}, instant unstake {}, stake deposit {})",
+            sol(decrease.total() as i64),
+            sol(decrease.scoring as i64),
+            sol(decrease.instant_unstake as i64),
+            sol(decrease.stake_deposit as i64),
+        ),
+        Some(lamports),
+    )
+}
+
+fn removal_description(metadata: &Document) -> String {
+    let mut reasons = vec![];
+    if metadata.get_bool("vote_account_closed").unwrap_or_default() {
+        reasons.push("vote account closed");
+    }
+    if metadata
+        .get_bool("stake_account_deactivated")
+        .unwrap_or_default()
+    {
+        reasons.push("stake account deactivated");
+    }
+
+    if reasons.is_empty() {
+        "Removed from the pool".to_string()
+    } else {
+        format!("Removed from the pool: {}", reasons.join(", "))
+    }
+}
+
+fn score_description(metadata: &Document) -> String {
+    let score = number(metadata, "score").unwrap_or_default();
+    if score > 0.0 {
+        return format!("Scored {score}");
+    }
+
+    format!(
+        "Scored zero, failing the {} check",
+        failed_components(metadata).join(", ")
+    )
+}
+
+/// Checks of a ScoreComponents event the validator failed
+fn failed_components(metadata: &Document) -> Vec<&'static str> {
+    SCORE_COMPONENTS
+        .iter()
+        .filter(|(key, _)| number(metadata, key).is_some_and(|score| score == 0.0))
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+/// Checks of an InstantUnstakeComponents event that flagged the validator
+fn instant_unstake_reasons(metadata: &Document) -> Vec<&'static str> {
+    if !metadata.get_bool("instant_unstake").unwrap_or_default() {
+        return vec![];
+    }
+
+    [
+        ("delinquency_check", "delinquency"),
+        ("commission_check", "commission"),
+        ("mev_commission_check", "MEV commission"),
+        ("is_blacklisted", "blacklist"),
+        (
+            "is_bad_merkle_root_upload_authority",
+            "merkle root upload authority",
+        ),
+        (
+            "is_bad_priority_fee_merkle_root_upload_authority",
+            "priority fee merkle root upload authority",
+        ),
+    ]
+    .into_iter()
+    .filter(|(key, _)| metadata.get_bool(key).unwrap_or_default())
+    .map(|(_, name)| name)
+    .collect()
+}
+
+/// Sum the lamports of identical causes within an epoch
+fn merge_causes(causes: Vec<ProbableCause>) -> Vec<ProbableCause> {
+    let mut merged: Vec<ProbableCause> = vec![];
+    for cause in causes {
+        match merged
+            .iter_mut()
+            .find(|merged| merged.epoch == cause.epoch && merged.cause == cause.cause)
+        {
+            Some(merged) => merged.lamports += cause.lamports,
+            None => merged.push(cause),
+        }
+    }
+    merged.sort_by_key(|cause| cause.epoch);
+
+    merged
+}
+
+fn sol(lamports: i64) -> String {
+    format!("{:.2} SOL", lamports as f64 / LAMPORTS_PER_SOL as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    use super::*;
+
+    const LAMPORTS: u64 = 1_000 * LAMPORTS_PER_SOL;
+
+    fn request(start_epoch: Option<u64>, end_epoch: Option<u64>) -> DelegationExplainerRequest {
+        DelegationExplainerRequest {
+            start_epoch,
+            end_epoch,
+        }
+    }
+
+    fn event(event_type: &str, epoch: u64, slot: u64, metadata: Document) -> StewardEvent {
+        StewardEvent {
+            signature: format!("signature-{slot}"),
+            instruction_idx: 0,
+            inner_instruction_idx: None,
+            event_idx: 0,
+            event_type: event_type.to_string(),
+            vote_account: Some("vote".to_string()),
+            metadata: Some(metadata),
+            tx_error: None,
+            signer: "signer".to_string(),
+            stake_pool: "pool".to_string(),
+            epoch,
+            slot,
+            timestamp: None,
+            commitment: None,
+        }
+    }
+
+    fn history(epoch: u16, commission: u8) -> ValidatorHistoryEntryResponse {
+        ValidatorHistoryEntryResponse {
+            epoch,
+            commission,
+            mev_commission: 800,
+            epoch_credits: 400_000,
+            ..Default::default()
+        }
+    }
+
+    fn decrease(scoring: u64, instant_unstake: u64, stake_deposit: u64) -> Document {
+        doc! {
+            "scoring_unstake_lamports": scoring as i64,
+            "instant_unstake_lamports": instant_unstake as i64,
+            "stake_deposit_unstake_lamports": stake_deposit as i64,
+        }
+    }
+
+    #[test]
+    fn test_validate_epoch_range() {
+        assert!(request(None, None).validate().is_ok());
+        assert!(request(Some(800), Some(800)).validate().is_ok());
+        assert!(request(Some(800), Some(800 + MAX_EPOCHS - 1))
+            .validate()
+            .is_ok());
+
+        assert!(request(Some(801), Some(800)).validate().is_err());
+        assert!(request(Some(800), Some(800 + MAX_EPOCHS))
+            .validate()
+            .is_err());
+        assert!(request(Some(800), None).validate().is_err());
+        assert!(request(None, Some(800)).validate().is_err());
+    }
+
+    #[test]
+    fn test_default_epoch_range() {
+        assert_eq!(default_epoch_range(800), (800 - DEFAULT_EPOCHS + 1, 800));
+        assert_eq!(default_epoch_range(3), (0, 3));
+    }
+
+    #[test]
+    fn test_rebalance_decrease_attribution() {
+        let rebalance = event(
+            "RebalanceEvent",
+            801,
+            2_000,
+            doc! {"increase_lamports": 0_i64},
+        );
+        let decrease_components = vec![event(
+            "DecreaseComponents",
+            801,
+            2_000,
+            decrease(LAMPORTS, 2 * LAMPORTS, 3 * LAMPORTS),
+        )];
+        let events = vec![
+            event(
+                "ScoreComponentsV4",
+                801,
+                1_000,
+                doc! {"score": 10_i64, "commission_score": 1},
+            ),
+            rebalance,
+        ];
+
+        let response =
+            DelegationExplainer::new(&events, &decrease_components, &[]).explain("vote", 800, 801);
+
+        assert_eq!(response.net_lamports, -(6 * LAMPORTS as i64));
+        let causes: Vec<(u64, &str)> = response
+            .probable_causes
+            .iter()
+            .map(|cause| (cause.lamports, cause.cause.as_str()))
+            .collect();
+        assert_eq!(
+            causes,
+            vec![
+                (2 * LAMPORTS, "Instantly unstaked in epoch 801"),
+                (
+                    LAMPORTS,
+                    "Score of 10 in epoch 801 ranked below the validators selected for delegation"
+                ),
+                (
+                    3 * LAMPORTS,
+                    "Stake deposited above the validator target was moved to other validators"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_zero_score_attributed_to_commission_increase() {
+        let events = vec![
+            event(
+                "ScoreComponentsV4",
+                802,
+                1_000,
+                doc! {"score": 0_i64, "commission_score": 0, "mev_commission_score": 1},
+            ),
+            event(
+                "RebalanceEvent",
+                802,
+                2_000,
+                doc! {"increase_lamports": 0_i64, "decrease_components": decrease(LAMPORTS, 0, 0)},
+            ),
+        ];
+        let history = vec![history(800, 5), history(801, 10), history(802, 10)];
+
+        let response = DelegationExplainer::new(&events, &[], &history).explain("vote", 800, 802);
+
+        assert_eq!(response.probable_causes.len(), 1);
+        assert_eq!(
+            response.probable_causes[0].cause,
+            "Commission increase from 5% to 10% in epoch 801 triggered a zero score in epoch 802"
+        );
+        assert!(response
+            .timeline
+            .iter()
+            .any(|entry| entry.source == "ValidatorHistory"
+                && entry.description == "Commission changed from 5% to 10%"));
+    }
+
+    #[test]
+    fn test_instant_unstake_checks_attributed() {
+        let events = vec![
+            event(
+                "InstantUnstakeComponentsV3",
+                801,
+                1_000,
+                doc! {"instant_unstake": true, "delinquency_check": true},
+            ),
+            event(
+                "RebalanceEvent",
+                801,
+                2_000,
+                doc! {"increase_lamports": 0_i64, "decrease_components": decrease(0, LAMPORTS, 0)},
+            ),
+        ];
+
+        let response = DelegationExplainer::new(&events, &[], &[]).explain("vote", 801, 801);
+
+        assert_eq!(
+            response.probable_causes,
+            vec![ProbableCause {
+                epoch: 801,
+                lamports: LAMPORTS,
+                cause: "Vote credits below the delinquency threshold in epoch 801 triggered an instant unstake".to_string(),
+            }]
+        );
+        assert_eq!(
+            response.timeline[0].description,
+            "Flagged for instant unstake: delinquency"
+        );
+    }
+
+    #[test]
+    fn test_failed_transactions_move_no_stake() {
+        let mut rebalance = event(
+            "DirectedRebalanceEvent",
+            801,
+            2_000,
+            doc! {"increase_lamports": 0_i64, "decrease_lamports": LAMPORTS as i64},
+        );
+        rebalance.tx_error = Some("InstructionError".to_string());
+        let events = vec![rebalance];
+
+        let response = DelegationExplainer::new(&events, &[], &[]).explain("vote", 801, 801);
+
+        assert_eq!(response.net_lamports, 0);
+        assert!(response.probable_causes.is_empty());
+        assert_eq!(response.timeline[0].lamports, None);
+        assert!(response.timeline[0]
+            .description
+            .ends_with("(transaction failed: InstructionError)"));
+    }
+
+    #[test]
+    fn test_merge_causes_within_epoch() {
+        let cause = |epoch, lamports| ProbableCause {
+            epoch,
+            lamports,
+            cause: "cause".to_string(),
+        };
+
+        assert_eq!(
+            merge_causes(vec![cause(802, 1), cause(801, 2), cause(802, 3)]),
+            vec![cause(801, 2), cause(802, 4)]
+        );
+    }
+}
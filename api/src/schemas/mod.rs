@@ -1,10 +1,16 @@
 pub mod bam_boost_validator;
+pub mod bam_delegation_blacklist;
+pub mod bam_delegation_plan;
+pub mod bam_eligibility;
 pub mod bam_epoch_metrics;
 pub mod bam_validator;
+pub mod delegation_explainer;
 pub mod jitosol_ratio;
 pub mod mev_rewards;
 pub mod preferred_withdraw;
 pub mod stake_pool_stats;
 pub mod steward_events;
+pub mod steward_state;
 pub mod validator;
 pub mod validator_history;
+pub mod validator_score;
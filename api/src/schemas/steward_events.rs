@@ -1,6 +1,18 @@
-use kobe_core::db_models::steward_events::StewardEvent as StewardEventModel;
+use chrono::{DateTime, Utc};
+use kobe_core::{
+    db_models::steward_events::{
+        StewardEvent as StewardEventModel, StewardEventsCursor, StewardEventsFilter,
+        ValidatorRebalance,
+    },
+    SortOrder,
+};
 use serde::{Deserialize, Serialize};
 
+use crate::error::ApiError;
+
+/// Maximum number of steward events returned per page
+pub const MAX_STEWARD_EVENTS_LIMIT: u32 = 1000;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StewardEvent {
     pub signature: String,
@@ -35,23 +47,319 @@ impl From<StewardEventModel> for StewardEvent {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct StewardEventsResponse {
     pub events: Vec<StewardEvent>,
+
+    /// Number of events matching the filters across all pages, only counted on the first page
+    pub total_count: Option<u64>,
+
+    /// Cursor of the next page, `None` on the last page
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct StewardEventsRequest {
     pub event_type: Option<String>,
+
+    /// Comma separated event types, combined with `event_type`
+    pub event_types: Option<String>,
+
     pub vote_account: Option<String>,
     pub epoch: Option<u64>,
+
+    /// First slot, inclusive
+    pub start_slot: Option<u64>,
+
+    /// Last slot, inclusive
+    pub end_slot: Option<u64>,
+
+    /// Earliest block time (RFC 3339), inclusive
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// Latest block time (RFC 3339), inclusive
+    pub end_time: Option<DateTime<Utc>>,
+
+    /// Signer of the transaction
+    pub signer: Option<String>,
+
+    pub stake_pool: Option<String>,
+
+    /// Only events of failed transactions when `true`, of successful ones when `false`
+    pub has_error: Option<bool>,
+
+    /// "asc" or "desc" by slot, "desc" by default
+    pub sort_order: Option<String>,
+
+    /// `next_cursor` of the previous page, replaces `page`
+    pub cursor: Option<String>,
+
     pub page: Option<u32>,
     pub limit: Option<u32>,
 }
 
+impl StewardEventsRequest {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        if let Some(limit) = self.limit {
+            if limit == 0 || limit > MAX_STEWARD_EVENTS_LIMIT {
+                return Err(ApiError::validation_error(format!(
+                    "Invalid limit: must be between 1 and {MAX_STEWARD_EVENTS_LIMIT}"
+                )));
+            }
+        }
+        if self.page == Some(0) {
+            return Err(ApiError::validation_error("Invalid page: pages start at 1"));
+        }
+        if self.cursor.is_some() && self.page.is_some() {
+            return Err(ApiError::validation_error(
+                "Invalid pagination: cursor and page cannot be combined",
+            ));
+        }
+        if let Some(cursor) = &self.cursor {
+            cursor
+                .parse::<StewardEventsCursor>()
+                .map_err(ApiError::validation_error)?;
+        }
+        if let Some(sort_order) = &self.sort_order {
+            if sort_order != "asc" && sort_order != "desc" {
+                return Err(ApiError::validation_error(
+                    "Invalid sort_order: must be asc or desc",
+                ));
+            }
+        }
+        if let (Some(start_slot), Some(end_slot)) = (self.start_slot, self.end_slot) {
+            if start_slot > end_slot {
+                return Err(ApiError::validation_error(
+                    "Invalid slot range: start_slot must not be after end_slot",
+                ));
+            }
+        }
+        if let (Some(start_time), Some(end_time)) = (self.start_time, self.end_time) {
+            if start_time > end_time {
+                return Err(ApiError::validation_error(
+                    "Invalid time range: start_time must not be after end_time",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate a request of the event stream, which takes the filters but not the pagination
+    /// or ordering of a page
+    pub fn validate_stream(&self) -> Result<(), ApiError> {
+        let unsupported: Vec<&str> = [
+            ("cursor", self.cursor.is_some()),
+            ("page", self.page.is_some()),
+            ("limit", self.limit.is_some()),
+            ("sort_order", self.sort_order.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(param, set)| set.then_some(param))
+        .collect();
+        if !unsupported.is_empty() {
+            return Err(ApiError::validation_error(format!(
+                "Invalid stream request: {} not supported by the stream",
+                unsupported.join(", ")
+            )));
+        }
+
+        self.validate()
+    }
+
+    pub fn filter(&self) -> StewardEventsFilter {
+        let event_types = self
+            .event_type
+            .iter()
+            .chain(self.event_types.iter())
+            .flat_map(|event_types| event_types.split(','))
+            .map(str::trim)
+            .filter(|event_type| !event_type.is_empty())
+            .map(String::from)
+            .collect();
+
+        StewardEventsFilter {
+            event_types,
+            vote_account: self.vote_account.clone(),
+            epoch: self.epoch,
+            start_slot: self.start_slot,
+            end_slot: self.end_slot,
+            start_time: self.start_time.map(mongodb::bson::DateTime::from_chrono),
+            end_time: self.end_time.map(mongodb::bson::DateTime::from_chrono),
+            signer: self.signer.clone(),
+            stake_pool: self.stake_pool.clone(),
+            has_error: self.has_error,
+        }
+    }
+
+    pub fn sort_order(&self) -> SortOrder {
+        match self.sort_order.as_deref() {
+            Some("asc") => SortOrder::Asc,
+            _ => SortOrder::Desc,
+        }
+    }
+}
+
 impl std::fmt::Display for StewardEventsRequest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "StewardEventsRequest {{ event_type: {:?}, vote_account: {:?}, epoch: {:?}, page: {:?}, limit: {:?} }}",
-            self.event_type, self.vote_account, self.epoch, self.page, self.limit
+            "StewardEventsRequest {{ event_type: {:?}, event_types: {:?}, vote_account: {:?}, epoch: {:?}, start_slot: {:?}, end_slot: {:?}, start_time: {:?}, end_time: {:?}, signer: {:?}, stake_pool: {:?}, has_error: {:?}, sort_order: {:?}, cursor: {:?}, page: {:?}, limit: {:?} }}",
+            self.event_type,
+            self.event_types,
+            self.vote_account,
+            self.epoch,
+            self.start_slot,
+            self.end_slot,
+            self.start_time,
+            self.end_time,
+            self.signer,
+            self.stake_pool,
+            self.has_error,
+            self.sort_order,
+            self.cursor,
+            self.page,
+            self.limit
         )
     }
 }
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct StewardRebalanceSummaryRequest {
+    pub epoch: u64,
+}
+
+/// Decrease of a rebalance split by reason
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecreaseBreakdown {
+    pub scoring_unstake_lamports: u64,
+    pub instant_unstake_lamports: u64,
+    pub stake_deposit_unstake_lamports: u64,
+    pub directed_unstake_lamports: u64,
+}
+
+impl DecreaseBreakdown {
+    fn add(&mut self, other: &DecreaseBreakdown) {
+        self.scoring_unstake_lamports += other.scoring_unstake_lamports;
+        self.instant_unstake_lamports += other.instant_unstake_lamports;
+        self.stake_deposit_unstake_lamports += other.stake_deposit_unstake_lamports;
+        self.directed_unstake_lamports += other.directed_unstake_lamports;
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RebalanceSummary {
+    pub rebalances: u64,
+    pub increase_lamports: u64,
+    pub decrease_lamports: u64,
+
+    /// `increase_lamports - decrease_lamports`, negative when stake moved away
+    pub net_lamports: i64,
+
+    pub decrease_breakdown: DecreaseBreakdown,
+}
+
+impl RebalanceSummary {
+    fn add(&mut self, other: &RebalanceSummary) {
+        self.rebalances += other.rebalances;
+        self.increase_lamports += other.increase_lamports;
+        self.decrease_lamports += other.decrease_lamports;
+        self.net_lamports += other.net_lamports;
+        self.decrease_breakdown.add(&other.decrease_breakdown);
+    }
+}
+
+impl From<&ValidatorRebalance> for RebalanceSummary {
+    fn from(rebalance: &ValidatorRebalance) -> Self {
+        let decrease_lamports = rebalance.decrease_lamports();
+        RebalanceSummary {
+            rebalances: rebalance.rebalances,
+            increase_lamports: rebalance.increase_lamports,
+            decrease_lamports,
+            net_lamports: rebalance.increase_lamports as i64 - decrease_lamports as i64,
+            decrease_breakdown: DecreaseBreakdown {
+                scoring_unstake_lamports: rebalance.scoring_unstake_lamports,
+                instant_unstake_lamports: rebalance.instant_unstake_lamports,
+                stake_deposit_unstake_lamports: rebalance.stake_deposit_unstake_lamports,
+                directed_unstake_lamports: rebalance.directed_unstake_lamports,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorRebalanceSummary {
+    pub vote_account: String,
+
+    #[serde(flatten)]
+    pub summary: RebalanceSummary,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StewardRebalanceSummaryResponse {
+    pub epoch: u64,
+
+    /// Rebalances of successful transactions per validator, sorted by vote account
+    pub validators: Vec<ValidatorRebalanceSummary>,
+
+    /// Sum over all validators
+    pub totals: RebalanceSummary,
+
+    /// Number of failed transactions that emitted rebalance events, not counted in the lamports
+    pub failed_transactions: u64,
+}
+
+impl StewardRebalanceSummaryResponse {
+    pub fn new(epoch: u64, rebalances: &[ValidatorRebalance], failed_transactions: u64) -> Self {
+        let validators: Vec<ValidatorRebalanceSummary> = rebalances
+            .iter()
+            .map(|rebalance| ValidatorRebalanceSummary {
+                vote_account: rebalance.vote_account.clone(),
+                summary: rebalance.into(),
+            })
+            .collect();
+
+        let mut totals = RebalanceSummary::default();
+        for validator in validators.iter() {
+            totals.add(&validator.summary);
+        }
+
+        Self {
+            epoch,
+            validators,
+            totals,
+            failed_transactions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn request(params: serde_json::Value) -> StewardEventsRequest {
+        serde_json::from_value(params).unwrap()
+    }
+
+    #[test]
+    fn test_validate_stream_rejects_pagination() {
+        assert!(request(json!({"vote_account": "vote", "has_error": false}))
+            .validate_stream()
+            .is_ok());
+
+        for params in [
+            json!({"cursor": "cursor"}),
+            json!({"page": 2}),
+            json!({"limit": 10}),
+            json!({"sort_order": "asc"}),
+        ] {
+            assert!(request(params).validate_stream().is_err());
+        }
+    }
+
+    #[test]
+    fn test_validate_stream_validates_filters() {
+        assert!(request(json!({"start_slot": 10, "end_slot": 5}))
+            .validate_stream()
+            .is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use kobe_core::db_models::steward_state_snapshots::StewardStateSnapshot;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct StewardStateSnapshotsRequest {
+    /// Epoch of the snapshot, latest snapshot when unset
+    pub epoch: Option<u64>,
+}
+
+impl std::fmt::Display for StewardStateSnapshotsRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "StewardStateSnapshotsRequest {{ epoch: {:?} }}",
+            self.epoch
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StewardStateSnapshotsResponse {
+    /// Validators of the snapshot, ordered by rank
+    pub snapshots: Vec<StewardStateSnapshot>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ValidatorStewardStateRequest {
+    /// Restrict to the snapshots of an epoch
+    pub epoch: Option<u64>,
+
+    /// Maximum number of snapshots, 100 by default
+    pub limit: Option<u32>,
+}
+
+impl ValidatorStewardStateRequest {
+    pub const MAX_LIMIT: u32 = 1_000;
+}
+
+impl std::fmt::Display for ValidatorStewardStateRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ValidatorStewardStateRequest {{ epoch: {:?}, limit: {:?} }}",
+            self.epoch, self.limit
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidatorStewardStateResponse {
+    /// Rank, score and target delegation of the validator over time, newest first
+    pub snapshots: Vec<StewardStateSnapshot>,
+}
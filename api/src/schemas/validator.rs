@@ -1,3 +1,4 @@
+use kobe_core::db_models::validators::InflationRewardsStatus;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -45,6 +46,21 @@ pub struct ValidatorEntry {
     /// Active stake lamports delegated to this validator from the JitoSOL stake-pool
     #[serde(skip_serializing_if = "Option::is_none")]
     pub jito_sol_active_lamports: Option<u64>,
+
+    /// Inflation rewards estimated while the epoch was in progress
+    pub estimated_inflation_rewards_lamports: u64,
+
+    /// Whether the paid inflation rewards are recorded yet
+    pub inflation_rewards_status: Option<InflationRewardsStatus>,
+
+    /// Inflation rewards paid for the epoch, `None` until rewards are paid
+    pub paid_inflation_rewards_lamports: Option<u64>,
+
+    /// Inflation rewards (commission) paid to the vote account
+    pub vote_account_inflation_rewards_lamports: Option<u64>,
+
+    /// Inflation rewards paid to the target pool's (JitoSOL) stake accounts of this validator
+    pub target_pool_inflation_rewards_lamports: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
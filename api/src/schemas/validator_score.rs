@@ -0,0 +1,422 @@
+use kobe_core::db_models::{steward_events::StewardEvent, steward_parameters::StewardParameters};
+use mongodb::bson::{Bson, Document};
+use serde::{Deserialize, Serialize};
+
+/// Epoch recorded in score details when no epoch applies
+const EPOCH_DEFAULT: i64 = u16::MAX as i64;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ValidatorScoreRequest {
+    /// Epoch of the score, latest score when unset
+    pub epoch: Option<u64>,
+}
+
+impl std::fmt::Display for ValidatorScoreRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ValidatorScoreRequest {{ epoch: {:?} }}", self.epoch)
+    }
+}
+
+/// Binary score component and the check behind it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoreComponent {
+    /// Component score, the final score is zero as soon as one component is zero
+    pub score: f64,
+
+    /// Whether the validator passed the check
+    pub passed: bool,
+
+    /// Value compared to `threshold`, `None` when the event does not record it
+    pub value: Option<f64>,
+
+    /// Threshold of the steward parameters the score was computed with, `None` for checks
+    /// without one
+    pub threshold: Option<f64>,
+
+    /// Epoch the value was observed in
+    pub epoch: Option<u64>,
+
+    /// Condition to pass the check
+    pub condition: String,
+}
+
+impl ScoreComponent {
+    /// Component of a check recorded by every ScoreComponents version
+    fn new(metadata: &Document, score_key: &str, condition: String) -> Self {
+        let score = number(metadata, score_key).unwrap_or_default();
+        Self {
+            score,
+            passed: score > 0.0,
+            value: None,
+            threshold: None,
+            epoch: None,
+            condition,
+        }
+    }
+
+    /// Component of a check only recorded by later ScoreComponents versions, `None` when the
+    /// event predates it
+    fn recorded(metadata: &Document, score_key: &str, condition: String) -> Option<Self> {
+        metadata
+            .contains_key(score_key)
+            .then(|| Self::new(metadata, score_key, condition))
+    }
+
+    /// Compare the `value_key` of the event details to `threshold`
+    fn with_detail(
+        mut self,
+        details: Option<&Document>,
+        value_key: &str,
+        epoch_key: &str,
+        threshold: f64,
+    ) -> Self {
+        self.threshold = Some(threshold);
+        if let Some(details) = details {
+            self.value = number(details, value_key);
+            self.epoch = detail_epoch(details, epoch_key);
+        }
+        self
+    }
+}
+
+/// Score of a validator decoded from its latest ScoreComponents event
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorScore {
+    pub vote_account: String,
+    pub epoch: u64,
+    pub slot: u64,
+    pub signature: String,
+
+    /// Version of the event the score was decoded from
+    pub event_type: String,
+
+    /// Final score, zero when any component is zero
+    pub score: f64,
+
+    /// Score the validators are ranked by, only recorded by `ScoreComponentsV4`
+    pub raw_score: Option<f64>,
+
+    pub mev_commission: ScoreComponent,
+    pub commission: ScoreComponent,
+    pub historical_commission: ScoreComponent,
+    pub blacklist: ScoreComponent,
+    pub superminority: ScoreComponent,
+    pub delinquency: ScoreComponent,
+    pub running_jito: ScoreComponent,
+
+    /// Only recorded by `ScoreComponentsV4`
+    pub merkle_root_upload_authority: Option<ScoreComponent>,
+
+    /// Only recorded by `ScoreComponentsV4`
+    pub priority_fee_commission: Option<ScoreComponent>,
+
+    /// Only recorded by `ScoreComponentsV4`
+    pub priority_fee_merkle_root_upload_authority: Option<ScoreComponent>,
+
+    /// Slot the thresholds were recorded at, `None` when no parameters were recorded before the
+    /// score and the thresholds come from the current steward config
+    pub parameters_slot: Option<u64>,
+}
+
+impl ValidatorScore {
+    /// Decode a ScoreComponents event, thresholds come from `parameters`
+    ///
+    /// Only `ScoreComponentsV4` records the values compared to the thresholds. `parameters_slot`
+    /// is the slot `parameters` were recorded at.
+    pub fn from_event(
+        event: StewardEvent,
+        parameters: &StewardParameters,
+        parameters_slot: Option<u64>,
+    ) -> Option<Self> {
+        let metadata = event.metadata?;
+        let details = metadata.get_document("details").ok();
+
+        Some(Self {
+            vote_account: event.vote_account.unwrap_or_default(),
+            epoch: event.epoch,
+            slot: event.slot,
+            signature: event.signature,
+            event_type: event.event_type,
+            score: number(&metadata, "score").unwrap_or_default(),
+            raw_score: number(&metadata, "raw_score"),
+            mev_commission: ScoreComponent::new(
+                &metadata,
+                "mev_commission_score",
+                format!(
+                    "Max MEV commission (bps) over the last {} epochs <= threshold",
+                    parameters.mev_commission_range
+                ),
+            )
+            .with_detail(
+                details,
+                "max_mev_commission",
+                "max_mev_commission_epoch",
+                parameters.mev_commission_bps_threshold as f64,
+            ),
+            commission: ScoreComponent::new(
+                &metadata,
+                "commission_score",
+                format!(
+                    "Max commission (%) over the last {} epochs <= threshold",
+                    parameters.commission_range
+                ),
+            )
+            .with_detail(
+                details,
+                "max_commission",
+                "max_commission_epoch",
+                parameters.commission_threshold as f64,
+            ),
+            historical_commission: ScoreComponent::new(
+                &metadata,
+                "historical_commission_score",
+                "Max commission (%) over the validator history <= threshold".to_string(),
+            )
+            .with_detail(
+                details,
+                "max_historical_commission",
+                "max_historical_commission_epoch",
+                parameters.historical_commission_threshold as f64,
+            ),
+            blacklist: ScoreComponent::new(
+                &metadata,
+                "blacklisted_score",
+                "Not on the steward blacklist".to_string(),
+            ),
+            superminority: {
+                let mut component = ScoreComponent::new(
+                    &metadata,
+                    "superminority_score",
+                    "Not in the superminority".to_string(),
+                );
+                component.epoch = details.and_then(|d| detail_epoch(d, "superminority_epoch"));
+                component
+            },
+            delinquency: ScoreComponent::new(
+                &metadata,
+                "delinquency_score",
+                format!(
+                    "Vote credits ratio to the cluster in each of the last {} epochs >= threshold",
+                    parameters.epoch_credits_range
+                ),
+            )
+            .with_detail(
+                details,
+                "delinquency_ratio",
+                "delinquency_epoch",
+                parameters.scoring_delinquency_threshold_ratio,
+            ),
+            running_jito: ScoreComponent::new(
+                &metadata,
+                "running_jito_score",
+                format!(
+                    "MEV commission recorded in the last {} epochs",
+                    parameters.mev_commission_range
+                ),
+            ),
+            merkle_root_upload_authority: ScoreComponent::recorded(
+                &metadata,
+                "merkle_root_upload_authority_score",
+                "Tip distribution merkle root upload authority is an approved one".to_string(),
+            ),
+            priority_fee_commission: ScoreComponent::recorded(
+                &metadata,
+                "priority_fee_commission_score",
+                format!(
+                    "Average priority fee commission (bps) over the last {} epochs <= threshold",
+                    parameters.priority_fee_lookback_epochs
+                ),
+            )
+            .map(|component| {
+                component.with_detail(
+                    details,
+                    "avg_priority_fee_commission",
+                    "max_priority_fee_commission_epoch",
+                    parameters.priority_fee_max_commission_bps as f64,
+                )
+            }),
+            priority_fee_merkle_root_upload_authority: ScoreComponent::recorded(
+                &metadata,
+                "priority_fee_merkle_root_upload_authority_score",
+                "Priority fee distribution merkle root upload authority is an approved one"
+                    .to_string(),
+            ),
+            parameters_slot,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidatorScoreResponse {
+    pub score: Option<ValidatorScore>,
+}
+
+/// Numeric field stored as a double by `ScoreComponents` and as an integer by later versions
+pub(crate) fn number(document: &Document, key: &str) -> Option<f64> {
+    match document.get(key)? {
+        Bson::Double(value) => Some(*value),
+        Bson::Int32(value) => Some(*value as f64),
+        Bson::Int64(value) => Some(*value as f64),
+        _ => None,
+    }
+}
+
+fn detail_epoch(details: &Document, key: &str) -> Option<u64> {
+    let epoch = match details.get(key)? {
+        Bson::Int32(epoch) => *epoch as i64,
+        Bson::Int64(epoch) => *epoch,
+        _ => return None,
+    };
+    (epoch != EPOCH_DEFAULT).then_some(epoch as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    use super::*;
+
+    fn parameters() -> StewardParameters {
+        StewardParameters {
+            mev_commission_range: 10,
+            epoch_credits_range: 30,
+            commission_range: 30,
+            scoring_delinquency_threshold_ratio: 0.85,
+            mev_commission_bps_threshold: 1000,
+            commission_threshold: 5,
+            historical_commission_threshold: 50,
+            priority_fee_lookback_epochs: 10,
+            priority_fee_max_commission_bps: 5000,
+            ..Default::default()
+        }
+    }
+
+    fn score_event(event_type: &str, metadata: Document) -> StewardEvent {
+        StewardEvent {
+            signature: "signature".to_string(),
+            instruction_idx: 0,
+            inner_instruction_idx: None,
+            event_idx: 0,
+            event_type: event_type.to_string(),
+            vote_account: Some("vote".to_string()),
+            metadata: Some(metadata),
+            tx_error: None,
+            signer: "signer".to_string(),
+            stake_pool: "pool".to_string(),
+            epoch: 800,
+            slot: 345_600_000,
+            timestamp: None,
+            commitment: None,
+        }
+    }
+
+    #[test]
+    fn test_score_components_v4() {
+        let metadata = doc! {
+            "score": 0_i64,
+            "raw_score": 1_000_i64,
+            "mev_commission_score": 1,
+            "commission_score": 1,
+            "historical_commission_score": 1,
+            "blacklisted_score": 1,
+            "superminority_score": 1,
+            "delinquency_score": 1,
+            "running_jito_score": 1,
+            "merkle_root_upload_authority_score": 1,
+            "priority_fee_commission_score": 0,
+            "priority_fee_merkle_root_upload_authority_score": 1,
+            "details": {
+                "max_mev_commission": 800,
+                "max_mev_commission_epoch": 795,
+                "superminority_epoch": u16::MAX as i32,
+                "delinquency_ratio": 0.97,
+                "delinquency_epoch": 790,
+                "max_commission": 5,
+                "max_commission_epoch": 780,
+                "max_historical_commission": 10,
+                "max_historical_commission_epoch": 600,
+                "avg_priority_fee_commission": 7_500,
+                "max_priority_fee_commission_epoch": 798,
+            },
+        };
+
+        let score = ValidatorScore::from_event(
+            score_event("ScoreComponentsV4", metadata),
+            &parameters(),
+            Some(345_500_000),
+        )
+        .unwrap();
+
+        assert_eq!(score.score, 0.0);
+        assert_eq!(score.raw_score, Some(1_000.0));
+        assert_eq!(score.parameters_slot, Some(345_500_000));
+
+        assert!(score.mev_commission.passed);
+        assert_eq!(score.mev_commission.value, Some(800.0));
+        assert_eq!(score.mev_commission.threshold, Some(1000.0));
+        assert_eq!(score.mev_commission.epoch, Some(795));
+        assert_eq!(score.superminority.epoch, None);
+
+        let priority_fee_commission = score.priority_fee_commission.unwrap();
+        assert!(!priority_fee_commission.passed);
+        assert_eq!(priority_fee_commission.value, Some(7_500.0));
+        assert_eq!(priority_fee_commission.threshold, Some(5_000.0));
+        assert_eq!(priority_fee_commission.epoch, Some(798));
+
+        assert!(score.merkle_root_upload_authority.unwrap().passed);
+        assert!(
+            score
+                .priority_fee_merkle_root_upload_authority
+                .unwrap()
+                .passed
+        );
+    }
+
+    #[test]
+    fn test_score_components_v1() {
+        let metadata = doc! {
+            "score": 0.5,
+            "mev_commission_score": 1.0,
+            "commission_score": 0.0,
+            "historical_commission_score": 1.0,
+            "blacklisted_score": 1.0,
+            "superminority_score": 1.0,
+            "delinquency_score": 1.0,
+            "running_jito_score": 1.0,
+        };
+
+        let score = ValidatorScore::from_event(
+            score_event("ScoreComponents", metadata),
+            &parameters(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(score.score, 0.5);
+        assert_eq!(score.raw_score, None);
+        assert!(!score.commission.passed);
+        assert_eq!(score.commission.value, None);
+        assert_eq!(score.commission.threshold, Some(5.0));
+
+        // Checks added after the event version are left out rather than reported as failed
+        assert_eq!(score.merkle_root_upload_authority, None);
+        assert_eq!(score.priority_fee_commission, None);
+        assert_eq!(score.priority_fee_merkle_root_upload_authority, None);
+    }
+
+    #[test]
+    fn test_thresholds_from_parameters() {
+        let metadata = doc! {"score": 1_i64, "commission_score": 1};
+        let mut parameters = parameters();
+        parameters.commission_threshold = 10;
+
+        let score = ValidatorScore::from_event(
+            score_event("ScoreComponentsV4", metadata),
+            &parameters,
+            Some(1),
+        )
+        .unwrap();
+
+        assert_eq!(score.commission.threshold, Some(10.0));
+    }
+}
@@ -0,0 +1,711 @@
+//! Backtest of the BAM eligibility rules and allocation schedule.
+//!
+//! Replays [`BamValidatorEligibility`] and [`BamDelegationCriteria`] over past epochs, using the
+//! validator histories and the `bam_validators` and `bam_epoch_metrics` recorded by the writer,
+//! and diffs the results against what was recorded. Blacklists are read as of now.
+
+use std::{collections::HashMap, str::FromStr};
+
+use anyhow::anyhow;
+use clap::ValueEnum;
+use jito_steward::{constants::BASIS_POINTS_MAX, Config};
+use kobe_core::{
+    bam_eligibility_rules::BamEligibilityRules,
+    bam_validator_eligibility::BamValidatorEligibility,
+    db_models::{
+        bam_epoch_metrics::{BamAllocationReason, BamEpochMetrics},
+        bam_validators::{BamIneligibilityKind, BamIneligibilityReason, BamValidator},
+    },
+};
+use serde::Serialize;
+use solana_pubkey::Pubkey;
+use stakenet_sdk::utils::accounts::{get_all_steward_accounts, get_all_validator_history_accounts};
+use validator_history::ValidatorHistory;
+
+use crate::{bam_delegation_criteria::BamDelegationCriteria, BamWriterService};
+
+/// Output format of a backtest
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum BacktestFormat {
+    /// One row per epoch
+    Csv,
+
+    /// One object per epoch, with the validators whose eligibility changed and the targets
+    Json,
+
+    /// One row per target of an epoch
+    ValidatorCsv,
+}
+
+/// Validator whose eligibility differs from the recorded one
+#[derive(Clone, Debug, Serialize)]
+pub struct EligibilityChange {
+    pub vote_account: String,
+    pub active_stake: u64,
+    pub recorded_eligible: bool,
+    pub eligible: bool,
+
+    /// Failed criteria under the backtested rules
+    pub ineligibility_reasons: Vec<BamIneligibilityReason>,
+}
+
+/// Directed stake target of an eligible validator under the backtested rules
+#[derive(Clone, Debug, Serialize)]
+pub struct ValidatorTarget {
+    pub vote_account: String,
+    pub active_stake: u64,
+    pub score: Option<u8>,
+    pub target_lamports: u64,
+}
+
+/// Backtested epoch next to what was recorded
+#[derive(Clone, Debug, Serialize)]
+pub struct EpochBacktest {
+    pub epoch: u64,
+
+    /// BAM validators tracked in the epoch
+    pub bam_validator_count: u64,
+
+    pub eligible_count: u64,
+    pub recorded_eligible_count: u64,
+
+    /// Stake of the BAM validators over the total stake (BPS)
+    pub bam_stakeweight_bps: u64,
+
+    pub allocation_bps: u64,
+    pub recorded_allocation_bps: u64,
+    pub allocation_reason: BamAllocationReason,
+
+    /// Stakeweight threshold (BPS) of the tier that applied, `None` when the floor applied
+    pub allocation_tier_bps: Option<u64>,
+
+    pub available_delegation: u64,
+    pub recorded_available_delegation: u64,
+
+    /// Average target of the eligible validators under the distribution of the schedule
+    pub delegation_per_validator: u64,
+    pub recorded_delegation_per_validator: u64,
+
+    pub eligibility_changes: Vec<EligibilityChange>,
+
+    /// Targets of the eligible validators under the distribution of the schedule
+    pub targets: Vec<ValidatorTarget>,
+}
+
+impl EpochBacktest {
+    const VALIDATOR_CSV_HEADER: &'static str =
+        "epoch,vote_account,active_stake,score,target_lamports";
+
+    const CSV_HEADER: &'static str = "epoch,bam_validator_count,eligible_count,recorded_eligible_count,bam_stakeweight_bps,allocation_bps,recorded_allocation_bps,allocation_reason,allocation_tier_bps,available_delegation,recorded_available_delegation,delegation_per_validator,recorded_delegation_per_validator,newly_eligible,newly_ineligible";
+
+    fn csv_row(&self) -> String {
+        let newly_eligible = self
+            .eligibility_changes
+            .iter()
+            .filter(|change| change.eligible)
+            .count();
+        let newly_ineligible = self.eligibility_changes.len() - newly_eligible;
+
+        format!(
+            "{},{},{},{},{},{},{},{:?},{},{},{},{},{},{},{}",
+            self.epoch,
+            self.bam_validator_count,
+            self.eligible_count,
+            self.recorded_eligible_count,
+            self.bam_stakeweight_bps,
+            self.allocation_bps,
+            self.recorded_allocation_bps,
+            self.allocation_reason,
+            self.allocation_tier_bps
+                .map(|bps| bps.to_string())
+                .unwrap_or_default(),
+            self.available_delegation,
+            self.recorded_available_delegation,
+            self.delegation_per_validator,
+            self.recorded_delegation_per_validator,
+            newly_eligible,
+            newly_ineligible,
+        )
+    }
+
+    fn validator_csv_rows(&self) -> impl Iterator<Item = String> + '_ {
+        self.targets.iter().map(|target| {
+            format!(
+                "{},{},{},{},{}",
+                self.epoch,
+                target.vote_account,
+                target.active_stake,
+                target
+                    .score
+                    .map(|score| score.to_string())
+                    .unwrap_or_default(),
+                target.target_lamports,
+            )
+        })
+    }
+}
+
+/// Render backtested epochs in `format`
+pub fn render_backtest(epochs: &[EpochBacktest], format: BacktestFormat) -> anyhow::Result<String> {
+    match format {
+        BacktestFormat::Csv => {
+            let mut csv = String::from(EpochBacktest::CSV_HEADER);
+            for epoch in epochs {
+                csv.push('\n');
+                csv.push_str(&epoch.csv_row());
+            }
+            Ok(csv)
+        }
+        BacktestFormat::Json => Ok(serde_json::to_string_pretty(epochs)?),
+        BacktestFormat::ValidatorCsv => {
+            let mut csv = String::from(EpochBacktest::VALIDATOR_CSV_HEADER);
+            for row in epochs.iter().flat_map(EpochBacktest::validator_csv_rows) {
+                csv.push('\n');
+                csv.push_str(&row);
+            }
+            Ok(csv)
+        }
+    }
+}
+
+fn per_validator(available_delegation: u64, eligible_count: u64) -> u64 {
+    available_delegation
+        .checked_div(eligible_count)
+        .unwrap_or(0)
+}
+
+/// Eligibility of a validator under the backtested rules: its score, or the failed criteria
+type BacktestEligibility = Result<u8, Vec<BamIneligibilityReason>>;
+
+/// Check a recorded BAM validator against the backtested rules
+///
+/// Validators without a validator history account are ineligible for insufficient history.
+fn validator_eligibility(
+    eligibility_checker: &BamValidatorEligibility,
+    blacklist_validators: &[Pubkey],
+    steward_config: &Config,
+    validator_history: Option<&ValidatorHistory>,
+) -> BacktestEligibility {
+    let Some(validator_history) = validator_history else {
+        return Err(vec![BamIneligibilityReason::new(
+            BamIneligibilityKind::InsufficientHistory,
+        )]);
+    };
+
+    eligibility_checker
+        .check_eligibility(blacklist_validators, steward_config, validator_history)
+        .map(|()| eligibility_checker.score(validator_history))
+}
+
+/// Replay an epoch from its recorded metrics and validators
+///
+/// `previous_epoch_metrics` confirm the allocation tier. Returns the backtested epoch and its
+/// backtested metrics, which confirm the tiers of the following epochs.
+fn replay_epoch(
+    criteria: &BamDelegationCriteria,
+    recorded_metrics: &BamEpochMetrics,
+    bam_validators: &[BamValidator],
+    previous_epoch_metrics: &[BamEpochMetrics],
+    eligibility: impl Fn(&BamValidator) -> BacktestEligibility,
+) -> (EpochBacktest, BamEpochMetrics) {
+    let epoch = recorded_metrics.get_epoch();
+
+    let mut eligible_validators = Vec::new();
+    let mut eligibility_changes = Vec::new();
+    for bam_validator in bam_validators {
+        let result = eligibility(bam_validator);
+
+        let eligible = result.is_ok();
+        if let Ok(score) = result {
+            let mut eligible_validator = bam_validator.clone();
+            eligible_validator.set_score(score);
+            eligible_validators.push(eligible_validator);
+        }
+        if eligible != bam_validator.is_eligible() {
+            eligibility_changes.push(EligibilityChange {
+                vote_account: bam_validator.get_vote_account(),
+                active_stake: bam_validator.get_active_stake(),
+                recorded_eligible: bam_validator.is_eligible(),
+                eligible,
+                ineligibility_reasons: result.err().unwrap_or_default(),
+            });
+        }
+    }
+
+    let eligible_count = eligible_validators.len() as u64;
+    let bam_stake = bam_validators.iter().map(|v| v.get_active_stake()).sum();
+    let mut metrics = BamEpochMetrics::new(
+        epoch,
+        bam_stake,
+        recorded_metrics.get_total_stake(),
+        recorded_metrics.get_jitosol_stake(),
+        eligible_count,
+    );
+
+    let allocation_tier = criteria.calculate_current_allocation(&metrics, previous_epoch_metrics);
+    let available_delegation = criteria.calculate_available_delegation(
+        allocation_tier.allocation_bps,
+        recorded_metrics.get_jitosol_stake(),
+    );
+    let targets: Vec<ValidatorTarget> = criteria
+        .distribute(epoch, available_delegation, &eligible_validators)
+        .into_iter()
+        .map(|(vote_account, target_lamports)| {
+            let validator = eligible_validators
+                .iter()
+                .find(|v| v.get_vote_account() == vote_account);
+            ValidatorTarget {
+                active_stake: validator.map_or(0, |v| v.get_active_stake()),
+                score: validator.and_then(|v| v.get_score()),
+                vote_account,
+                target_lamports,
+            }
+        })
+        .collect();
+    let delegated: u64 = targets.iter().map(|target| target.target_lamports).sum();
+
+    let bam_stakeweight_bps = (bam_stake as u128 * BASIS_POINTS_MAX as u128)
+        .checked_div(recorded_metrics.get_total_stake() as u128)
+        .unwrap_or(0) as u64;
+
+    let recorded_eligible_count = bam_validators.iter().filter(|v| v.is_eligible()).count() as u64;
+    let epoch_backtest = EpochBacktest {
+        epoch,
+        bam_validator_count: bam_validators.len() as u64,
+        eligible_count,
+        recorded_eligible_count,
+        bam_stakeweight_bps,
+        allocation_bps: allocation_tier.allocation_bps,
+        recorded_allocation_bps: recorded_metrics.get_allocation_bps(),
+        allocation_reason: allocation_tier.reason,
+        allocation_tier_bps: allocation_tier.stakeweight_threshold_bps,
+        available_delegation,
+        recorded_available_delegation: recorded_metrics.get_available_bam_delegation_stake(),
+        delegation_per_validator: per_validator(delegated, eligible_count),
+        recorded_delegation_per_validator: per_validator(
+            recorded_metrics.get_available_bam_delegation_stake(),
+            recorded_eligible_count,
+        ),
+        eligibility_changes,
+        targets,
+    };
+
+    metrics.set_allocation_tier(allocation_tier);
+    metrics.set_available_bam_delegation_stake(available_delegation);
+    metrics.set_distribution(criteria.rules_for(epoch).distribution.clone());
+
+    (epoch_backtest, metrics)
+}
+
+impl BamWriterService {
+    /// Replay `rules` and the allocation schedule over `from_epoch..=to_epoch`
+    ///
+    /// Epochs without recorded `bam_epoch_metrics` are skipped. The validators and their stake
+    /// are the ones recorded for each epoch.
+    pub async fn backtest(
+        &self,
+        from_epoch: u64,
+        to_epoch: u64,
+        rules: &BamEligibilityRules,
+    ) -> anyhow::Result<Vec<EpochBacktest>> {
+        if from_epoch > to_epoch {
+            return Err(anyhow!("from_epoch must not be after to_epoch"));
+        }
+
+        let blacklist_validators = self.get_blacklist_validators().await?;
+        let steward_all_accounts = get_all_steward_accounts(
+            &self.rpc_client,
+            &self.steward_program_id,
+            &self.steward_config,
+        )
+        .await?;
+        let validator_histories =
+            get_all_validator_history_accounts(&self.rpc_client, self.validator_history_program_id)
+                .await?;
+        let validator_history_by_vote_account: HashMap<_, _> = validator_histories
+            .iter()
+            .map(|validator_history| (validator_history.vote_account, validator_history))
+            .collect();
+
+        // Backtested metrics of the epochs replayed so far, confirming later tiers
+        let mut backtested_metrics: HashMap<u64, BamEpochMetrics> = HashMap::new();
+        let mut epochs = Vec::new();
+
+        for epoch in from_epoch..=to_epoch {
+            let Some(recorded_metrics) = self.bam_epoch_metrics_store.find_by_epoch(epoch).await?
+            else {
+                log::warn!("No BAM epoch metrics recorded for epoch {epoch}, skipping");
+                continue;
+            };
+            let bam_validators = self.bam_validators_store.find(epoch).await?;
+
+            let confirmation_epochs = self
+                .bam_delegation_criteria
+                .rules_for(epoch)
+                .confirmation_epochs;
+            let mut previous_epoch_metrics = Vec::new();
+            for prev_epoch in epoch.saturating_sub(confirmation_epochs - 1)..epoch {
+                let previous = match backtested_metrics.get(&prev_epoch) {
+                    Some(previous) => Some(previous.clone()),
+                    None => {
+                        self.bam_epoch_metrics_store
+                            .find_by_epoch(prev_epoch)
+                            .await?
+                    }
+                };
+                previous_epoch_metrics.extend(previous);
+            }
+
+            let eligibility_checker =
+                BamValidatorEligibility::new(epoch, &validator_histories, rules);
+            let (epoch_backtest, metrics) = replay_epoch(
+                &self.bam_delegation_criteria,
+                &recorded_metrics,
+                &bam_validators,
+                &previous_epoch_metrics,
+                |bam_validator| {
+                    let validator_history = Pubkey::from_str(&bam_validator.get_vote_account())
+                        .ok()
+                        .and_then(|pubkey| validator_history_by_vote_account.get(&pubkey))
+                        .copied();
+                    validator_eligibility(
+                        &eligibility_checker,
+                        &blacklist_validators,
+                        &steward_all_accounts.config_account,
+                        validator_history,
+                    )
+                },
+            );
+
+            epochs.push(epoch_backtest);
+            backtested_metrics.insert(epoch, metrics);
+        }
+
+        Ok(epochs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jito_steward::{utils::U8Bool, LargeBitMask, Parameters};
+    use validator_history::{CircBuf, ValidatorHistoryEntry};
+
+    use super::*;
+
+    fn epoch_backtest() -> EpochBacktest {
+        EpochBacktest {
+            epoch: 800,
+            bam_validator_count: 3,
+            eligible_count: 2,
+            recorded_eligible_count: 1,
+            bam_stakeweight_bps: 2_100,
+            allocation_bps: 5_000,
+            recorded_allocation_bps: 5_000,
+            allocation_reason: BamAllocationReason::TierConfirmed,
+            allocation_tier_bps: Some(2_000),
+            available_delegation: 1_000,
+            recorded_available_delegation: 1_000,
+            delegation_per_validator: 500,
+            recorded_delegation_per_validator: 1_000,
+            eligibility_changes: vec![EligibilityChange {
+                vote_account: "vote".to_string(),
+                active_stake: 10,
+                recorded_eligible: false,
+                eligible: true,
+                ineligibility_reasons: vec![],
+            }],
+            targets: vec![
+                ValidatorTarget {
+                    vote_account: "vote".to_string(),
+                    active_stake: 10,
+                    score: Some(80),
+                    target_lamports: 600,
+                },
+                ValidatorTarget {
+                    vote_account: "other".to_string(),
+                    active_stake: 20,
+                    score: None,
+                    target_lamports: 400,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let csv = render_backtest(&[epoch_backtest()], BacktestFormat::Csv).unwrap();
+        let mut lines = csv.lines();
+
+        let header = lines.next().unwrap();
+        let row = lines.next().unwrap();
+        assert_eq!(header.split(',').count(), row.split(',').count());
+        assert_eq!(
+            row,
+            "800,3,2,1,2100,5000,5000,TierConfirmed,2000,1000,1000,500,1000,1,0"
+        );
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn test_render_json() {
+        let mut epoch = epoch_backtest();
+        epoch.eligibility_changes[0].eligible = false;
+        epoch.eligibility_changes[0].recorded_eligible = true;
+        epoch.eligibility_changes[0].ineligibility_reasons = vec![BamIneligibilityReason::new(
+            BamIneligibilityKind::OffChainBlacklist,
+        )];
+
+        let json = render_backtest(&[epoch], BacktestFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value[0]["allocation_reason"], "tier_confirmed");
+        assert_eq!(
+            value[0]["eligibility_changes"][0]["ineligibility_reasons"][0]["kind"],
+            "off_chain_blacklist"
+        );
+        assert_eq!(value[0]["targets"][0]["vote_account"], "vote");
+        assert_eq!(value[0]["targets"][0]["target_lamports"], 600);
+    }
+
+    #[test]
+    fn test_render_validator_csv() {
+        let csv = render_backtest(&[epoch_backtest()], BacktestFormat::ValidatorCsv).unwrap();
+
+        assert_eq!(
+            csv.lines().collect::<Vec<_>>(),
+            [
+                "epoch,vote_account,active_stake,score,target_lamports",
+                "800,vote,10,80,600",
+                "800,other,20,,400",
+            ]
+        );
+    }
+
+    fn create_steward_config() -> Config {
+        Config {
+            stake_pool: Pubkey::new_unique(),
+            validator_list: Pubkey::new_unique(),
+            admin: Pubkey::new_unique(),
+            parameters_authority: Pubkey::new_unique(),
+            blacklist_authority: Pubkey::new_unique(),
+            validator_history_blacklist: LargeBitMask::default(),
+            parameters: Parameters::default(),
+            paused: U8Bool::from(false),
+            _padding_0: [0; 7],
+            priority_fee_parameters_authority: Pubkey::new_unique(),
+            directed_stake_meta_upload_authority: Pubkey::new_unique(),
+            directed_stake_whitelist_authority: Pubkey::new_unique(),
+            directed_stake_ticket_override_authority: Pubkey::new_unique(),
+            _padding: [0; 888],
+        }
+    }
+
+    // Validator history of a BAM client with no commission over `epochs`
+    fn create_validator_history(epochs: std::ops::Range<u16>) -> ValidatorHistory {
+        let mut history = ValidatorHistory {
+            struct_version: 1,
+            vote_account: Pubkey::new_unique(),
+            index: 0,
+            bump: 0,
+            _padding0: [0; 7],
+            last_ip_timestamp: 0,
+            last_version_timestamp: 0,
+            validator_age: 0,
+            validator_age_last_updated_epoch: 0,
+            _padding1: [0; 226],
+            history: CircBuf::default(),
+        };
+
+        for epoch in epochs {
+            history.history.arr[epoch as usize % history.history.arr.len()] =
+                ValidatorHistoryEntry {
+                    epoch,
+                    commission: 0,
+                    mev_commission: 1000,
+                    is_superminority: 0,
+                    epoch_credits: 10000,
+                    client_type: 6,
+                    ..Default::default()
+                };
+        }
+        history
+    }
+
+    fn criteria() -> BamDelegationCriteria {
+        BamDelegationCriteria::load(None).unwrap()
+    }
+
+    // Recorded metrics at 25% BAM stakeweight
+    fn recorded_metrics(epoch: u64) -> BamEpochMetrics {
+        BamEpochMetrics::new(epoch, 100_000_000, 400_000_000, 200_000_000, 2)
+    }
+
+    fn bam_validators(epoch: u64) -> Vec<BamValidator> {
+        vec![
+            BamValidator::new(60_000_000, epoch, "identity-a", true, "vote-a"),
+            BamValidator::new(40_000_000, epoch, "identity-b", true, "vote-b"),
+        ]
+    }
+
+    #[test]
+    fn test_validator_eligibility_without_history() {
+        let rules = BamEligibilityRules::load(None).unwrap();
+        let checker = BamValidatorEligibility::new(30, &[], &rules);
+
+        assert_eq!(
+            validator_eligibility(&checker, &[], &create_steward_config(), None),
+            Err(vec![BamIneligibilityReason::new(
+                BamIneligibilityKind::InsufficientHistory
+            )])
+        );
+    }
+
+    #[test]
+    fn test_validator_eligibility_scores_eligible_validator() {
+        let rules = BamEligibilityRules::load(None).unwrap();
+        let validator_history = create_validator_history(0..30);
+        let checker = BamValidatorEligibility::new(30, &[validator_history.clone()], &rules);
+
+        assert_eq!(
+            validator_eligibility(
+                &checker,
+                &[],
+                &create_steward_config(),
+                Some(&validator_history)
+            ),
+            Ok(checker.score(&validator_history))
+        );
+
+        // Blacklisted validators are ineligible however clean their history
+        assert!(validator_eligibility(
+            &checker,
+            &[validator_history.vote_account],
+            &create_steward_config(),
+            Some(&validator_history)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_replay_epoch_diffs_eligibility() {
+        let mut validators = bam_validators(100);
+        validators.push(BamValidator::new(
+            10_000_000,
+            100,
+            "identity-c",
+            false,
+            "vote-c",
+        ));
+
+        let (epoch_backtest, metrics) = replay_epoch(
+            &criteria(),
+            &recorded_metrics(100),
+            &validators,
+            &[],
+            |bam_validator| match bam_validator.get_vote_account().as_str() {
+                "vote-a" => Err(vec![BamIneligibilityReason::new(
+                    BamIneligibilityKind::OffChainBlacklist,
+                )]),
+                _ => Ok(1),
+            },
+        );
+
+        assert_eq!(epoch_backtest.bam_validator_count, 3);
+        assert_eq!(epoch_backtest.eligible_count, 2);
+        assert_eq!(epoch_backtest.recorded_eligible_count, 2);
+        assert_eq!(metrics.get_bam_stake(), 110_000_000);
+
+        let changes: Vec<_> = epoch_backtest
+            .eligibility_changes
+            .iter()
+            .map(|change| {
+                (
+                    change.vote_account.as_str(),
+                    change.recorded_eligible,
+                    change.eligible,
+                )
+            })
+            .collect();
+        assert_eq!(changes, [("vote-a", true, false), ("vote-c", false, true)]);
+        assert_eq!(
+            epoch_backtest.eligibility_changes[0].ineligibility_reasons,
+            vec![BamIneligibilityReason::new(
+                BamIneligibilityKind::OffChainBlacklist
+            )]
+        );
+        assert!(epoch_backtest.eligibility_changes[1]
+            .ineligibility_reasons
+            .is_empty());
+    }
+
+    #[test]
+    fn test_replay_epochs_confirm_tier_from_backtested_metrics() {
+        let criteria = criteria();
+
+        // No metrics before the first epoch, the floor applies
+        let (first, first_metrics) = replay_epoch(
+            &criteria,
+            &recorded_metrics(100),
+            &bam_validators(100),
+            &[],
+            |_| Ok(1),
+        );
+        assert_eq!(
+            first.allocation_reason,
+            BamAllocationReason::InsufficientHistory
+        );
+        assert_eq!(first.allocation_bps, 3_000);
+        assert_eq!(first.allocation_tier_bps, None);
+        assert_eq!(first.bam_stakeweight_bps, 2_500);
+
+        // The backtested metrics of the first epoch confirm the 25% tier
+        let (second, _) = replay_epoch(
+            &criteria,
+            &recorded_metrics(101),
+            &bam_validators(101),
+            &[first_metrics],
+            |_| Ok(1),
+        );
+        assert_eq!(second.allocation_reason, BamAllocationReason::TierConfirmed);
+        assert_eq!(second.allocation_bps, 7_500);
+        assert_eq!(second.allocation_tier_bps, Some(2_500));
+        assert_eq!(second.available_delegation, 150_000_000);
+        assert_eq!(second.delegation_per_validator, 75_000_000);
+    }
+
+    #[test]
+    fn test_replay_epoch_targets_only_eligible_validators() {
+        let criteria = criteria();
+        let (_, previous) = replay_epoch(
+            &criteria,
+            &recorded_metrics(100),
+            &bam_validators(100),
+            &[],
+            |_| Ok(1),
+        );
+
+        // The stakeweight counts every BAM validator, the targets only the eligible ones
+        let (epoch_backtest, _) = replay_epoch(
+            &criteria,
+            &recorded_metrics(101),
+            &bam_validators(101),
+            &[previous],
+            |bam_validator| match bam_validator.get_vote_account().as_str() {
+                "vote-a" => Ok(1),
+                _ => Err(vec![BamIneligibilityReason::new(
+                    BamIneligibilityKind::NotBamClient,
+                )]),
+            },
+        );
+        assert_eq!(epoch_backtest.allocation_bps, 7_500);
+        assert_eq!(epoch_backtest.eligible_count, 1);
+        assert_eq!(epoch_backtest.delegation_per_validator, 150_000_000);
+
+        assert_eq!(epoch_backtest.targets.len(), 1);
+        let target = &epoch_backtest.targets[0];
+        assert_eq!(target.vote_account, "vote-a");
+        assert_eq!(target.active_stake, 60_000_000);
+        assert_eq!(target.score, Some(1));
+        assert_eq!(target.target_lamports, 150_000_000);
+    }
+}
@@ -1,28 +1,175 @@
+//! Allocation of JitoSOL to BAM validators (JIP-28).
+//!
+//! The allocation tiers, the number of epochs confirming a tier, the allocation floor and the
+//! distribution between the eligible validators are read from a schedule of governance-versioned
+//! rules, each taking effect at an epoch. The default schedule ([`DEFAULT_ALLOCATION_SCHEDULE`])
+//! is embedded in the binary.
+
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, bail};
 use jito_steward::constants::BASIS_POINTS_MAX;
-use kobe_core::db_models::bam_epoch_metrics::BamEpochMetrics;
+use kobe_core::db_models::{
+    bam_epoch_metrics::{
+        BamAllocationReason, BamAllocationTier, BamDistribution, BamDistributionStrategy,
+        BamEpochMetrics,
+    },
+    bam_validators::BamValidator,
+};
+use serde::{Deserialize, Serialize};
+
+/// JIP-28 schedule shipped with the BAM writer
+pub const DEFAULT_ALLOCATION_SCHEDULE: &str =
+    include_str!("../config/bam_allocation_schedule.toml");
+
+/// Allocation given once the BAM stakeweight reaches a threshold
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AllocationTierRule {
+    /// BAM stakeweight threshold (in BPS)
+    pub stakeweight_bps: u64,
+
+    /// Share of JitoSOL allocated (in BPS)
+    pub allocation_bps: u64,
+}
+
+/// Allocation rules adopted by governance
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AllocationRules {
+    /// Version of the rules
+    pub version: String,
+
+    /// First epoch the rules apply to
+    pub effective_epoch: u64,
+
+    /// Consecutive epochs, the current one included, the stakeweight must meet a tier threshold
+    pub confirmation_epochs: u64,
+
+    /// Lowest allocation (in BPS)
+    pub floor_bps: u64,
+
+    /// Tiers sorted by stakeweight threshold
+    pub tiers: Vec<AllocationTierRule>,
+
+    /// Distribution between the eligible validators, an equal split by default
+    #[serde(default)]
+    pub distribution: BamDistribution,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AllocationSchedule {
+    schedule: Vec<AllocationRules>,
+}
 
 /// Criteria for BAM delegation amount based on JIP-28 specification
-pub(crate) struct BamDelegationCriteria {
-    /// JIP-28 tier breakpoints
-    ///
-    /// 0: stakeweight_threshold (in BPS)
-    /// 1: allocation_pct (in BPS)
-    tiers: Vec<(u64, u64)>,
+pub struct BamDelegationCriteria {
+    /// Allocation rules sorted by effective epoch, the first taking effect at epoch 0
+    schedule: Vec<AllocationRules>,
 }
 
 impl BamDelegationCriteria {
-    /// Create a new calculator with JIP-28 default tiers
-    pub fn new() -> Self {
-        Self {
-            tiers: vec![
-                (0, 3_000),      // 0% -> 30%
-                (2_000, 5_000),  // 20% -> 50%
-                (2_500, 7_500),  // 25% -> 75%
-                (3_000, 10_000), // 30% -> 100%
-            ],
+    /// Load the schedule from `path`, or the embedded [`DEFAULT_ALLOCATION_SCHEDULE`] when not
+    /// given
+    pub fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+        match path {
+            Some(path) => {
+                let contents = fs::read_to_string(path)
+                    .map_err(|e| anyhow!("Failed to read {}: {e}", path.display()))?;
+                Self::from_toml_str(&contents)
+            }
+            None => Self::from_toml_str(DEFAULT_ALLOCATION_SCHEDULE),
         }
     }
 
+    /// Parse and validate the schedule of a TOML document
+    pub fn from_toml_str(contents: &str) -> anyhow::Result<Self> {
+        let AllocationSchedule { schedule } = toml::from_str(contents)
+            .map_err(|e| anyhow!("Invalid BAM allocation schedule: {e}"))?;
+
+        match schedule.first() {
+            None => bail!("Invalid BAM allocation schedule: no rules"),
+            Some(rules) if rules.effective_epoch != 0 => {
+                bail!(
+                    "Invalid BAM allocation schedule: the first rules must take effect at epoch 0"
+                )
+            }
+            Some(_) => {}
+        }
+        if schedule
+            .windows(2)
+            .any(|pair| pair[0].effective_epoch >= pair[1].effective_epoch)
+        {
+            bail!("Invalid BAM allocation schedule: effective epochs must be strictly increasing");
+        }
+
+        let max_bps = BASIS_POINTS_MAX as u64;
+        for rules in &schedule {
+            let version = &rules.version;
+            if version.trim().is_empty() {
+                bail!("Invalid BAM allocation schedule: version must not be empty");
+            }
+            if rules.confirmation_epochs == 0 {
+                bail!("Invalid BAM allocation rules {version}: confirmation_epochs must be at least 1");
+            }
+            if rules.floor_bps > max_bps {
+                bail!(
+                    "Invalid BAM allocation rules {version}: floor_bps must be at most {max_bps}"
+                );
+            }
+            if rules.tiers.is_empty() {
+                bail!("Invalid BAM allocation rules {version}: no tiers");
+            }
+            if rules
+                .tiers
+                .windows(2)
+                .any(|pair| pair[0].stakeweight_bps >= pair[1].stakeweight_bps)
+            {
+                bail!(
+                    "Invalid BAM allocation rules {version}: tier thresholds must be strictly increasing"
+                );
+            }
+            if rules
+                .tiers
+                .iter()
+                .any(|tier| tier.stakeweight_bps > max_bps || tier.allocation_bps > max_bps)
+            {
+                bail!(
+                    "Invalid BAM allocation rules {version}: tier thresholds and allocations must be at most {max_bps}"
+                );
+            }
+
+            let distribution = &rules.distribution;
+            match (distribution.strategy, distribution.stake_cap_bps) {
+                (BamDistributionStrategy::StakeCapped, None | Some(0)) => bail!(
+                    "Invalid BAM allocation rules {version}: stake_capped distribution needs a positive stake_cap_bps"
+                ),
+                (BamDistributionStrategy::StakeCapped, _) | (_, None) => {}
+                (_, Some(_)) => bail!(
+                    "Invalid BAM allocation rules {version}: stake_cap_bps only applies to stake_capped distribution"
+                ),
+            }
+            if matches!(distribution.max_validator_share_bps, Some(share_bps) if share_bps == 0 || share_bps > max_bps)
+            {
+                bail!(
+                    "Invalid BAM allocation rules {version}: max_validator_share_bps must be between 1 and {max_bps}"
+                );
+            }
+        }
+
+        Ok(Self { schedule })
+    }
+
+    /// Rules in effect at `epoch`
+    pub fn rules_for(&self, epoch: u64) -> &AllocationRules {
+        self.schedule
+            .iter()
+            .rev()
+            .find(|rules| rules.effective_epoch <= epoch)
+            .unwrap_or(&self.schedule[0])
+    }
+
     /// Calculate BAM stakeweight (percentage of network stake running BAM)
     fn calculate_bam_stakeweight(&self, bam_sol_stake: u64, total_sol_stake: u64) -> Option<u64> {
         if total_sol_stake == 0 {
@@ -36,37 +183,68 @@ impl BamDelegationCriteria {
         Some(stakeweight_bps as u64)
     }
 
-    /// Calculate current tier level with two-epoch validation
+    /// Calculate the current allocation tier with N-epoch confirmation
+    ///
+    /// `previous_epoch_metrics` must hold the metrics of the `confirmation_epochs - 1` epochs
+    /// before the current one, otherwise the floor applies.
     pub fn calculate_current_allocation(
         &self,
         current_epoch_metrics: &BamEpochMetrics,
-        previous_epoch_metrics: Option<&BamEpochMetrics>,
-    ) -> u64 {
-        let current_stakeweight_bps = self
-            .calculate_bam_stakeweight(
-                current_epoch_metrics.get_bam_stake(),
-                current_epoch_metrics.get_total_stake(),
-            )
-            .unwrap_or(0);
-
-        // If no previous epoch, return initial 20% (2000 BPS)
-        let Some(prev_metric) = previous_epoch_metrics else {
-            return 3_000;
+        previous_epoch_metrics: &[BamEpochMetrics],
+    ) -> BamAllocationTier {
+        let epoch = current_epoch_metrics.get_epoch();
+        let rules = self.rules_for(epoch);
+
+        let floor = |confirmed_stakeweight_bps, reason| BamAllocationTier {
+            rules_version: rules.version.clone(),
+            stakeweight_threshold_bps: None,
+            allocation_bps: rules.floor_bps,
+            confirmation_epochs: rules.confirmation_epochs,
+            confirmed_stakeweight_bps,
+            reason,
         };
 
-        let previous_stakeweight_bps = self
-            .calculate_bam_stakeweight(prev_metric.get_bam_stake(), prev_metric.get_total_stake())
-            .unwrap_or(0);
+        if epoch + 1 < rules.confirmation_epochs {
+            return floor(None, BamAllocationReason::InsufficientHistory);
+        }
+
+        // Lowest stakeweight over the confirmation epochs, all of them must have metrics
+        let mut confirmed_stakeweight_bps = u64::MAX;
+        for confirmation_epoch in epoch.saturating_sub(rules.confirmation_epochs - 1)..=epoch {
+            let metrics = if confirmation_epoch == epoch {
+                Some(current_epoch_metrics)
+            } else {
+                previous_epoch_metrics
+                    .iter()
+                    .find(|metrics| metrics.get_epoch() == confirmation_epoch)
+            };
+            let Some(metrics) = metrics else {
+                return floor(None, BamAllocationReason::InsufficientHistory);
+            };
+
+            let stakeweight_bps = self
+                .calculate_bam_stakeweight(metrics.get_bam_stake(), metrics.get_total_stake())
+                .unwrap_or(0);
+            confirmed_stakeweight_bps = confirmed_stakeweight_bps.min(stakeweight_bps);
+        }
 
-        // Find highest tier where BOTH epochs meet threshold
-        self.tiers
+        // Find highest tier met in every confirmation epoch
+        match rules
+            .tiers
             .iter()
             .rev()
-            .find(|(threshold, _)| {
-                current_stakeweight_bps >= *threshold && previous_stakeweight_bps >= *threshold
-            })
-            .map(|(_, allocation)| *allocation)
-            .unwrap_or(3_000)
+            .find(|tier| confirmed_stakeweight_bps >= tier.stakeweight_bps)
+        {
+            Some(tier) if tier.allocation_bps >= rules.floor_bps => BamAllocationTier {
+                rules_version: rules.version.clone(),
+                stakeweight_threshold_bps: Some(tier.stakeweight_bps),
+                allocation_bps: tier.allocation_bps,
+                confirmation_epochs: rules.confirmation_epochs,
+                confirmed_stakeweight_bps: Some(confirmed_stakeweight_bps),
+                reason: BamAllocationReason::TierConfirmed,
+            },
+            _ => floor(Some(confirmed_stakeweight_bps), BamAllocationReason::Floor),
+        }
     }
 
     /// Calculate available delegation amount in lamports
@@ -79,15 +257,99 @@ impl BamDelegationCriteria {
             .saturating_mul(allocation_bps as u128)
             .saturating_div(BASIS_POINTS_MAX as u128) as u64
     }
+
+    /// Split `available_delegation` between the eligible validators with the distribution of
+    /// the rules in effect at `epoch`
+    ///
+    /// Returns the target lamports of each validator, in the order of `eligible_validators`.
+    /// Targets reaching their cap are fixed at it and the rest is split again between the
+    /// uncapped validators, the dust of integer division stays undelegated.
+    pub fn distribute(
+        &self,
+        epoch: u64,
+        available_delegation: u64,
+        eligible_validators: &[BamValidator],
+    ) -> Vec<(String, u64)> {
+        let distribution = &self.rules_for(epoch).distribution;
+        let share = |amount: u64, bps: u64| {
+            ((amount as u128 * bps as u128) / BASIS_POINTS_MAX as u128) as u64
+        };
+
+        let weights: Vec<u128> = eligible_validators
+            .iter()
+            .map(|validator| match distribution.strategy {
+                BamDistributionStrategy::Equal | BamDistributionStrategy::StakeCapped => 1,
+                BamDistributionStrategy::ScoreWeighted => {
+                    validator.get_score().unwrap_or(0) as u128
+                }
+            })
+            .collect();
+        let caps: Vec<u64> = eligible_validators
+            .iter()
+            .map(|validator| {
+                let stake_cap = match distribution.strategy {
+                    BamDistributionStrategy::StakeCapped => distribution
+                        .stake_cap_bps
+                        .map(|bps| share(validator.get_active_stake(), bps)),
+                    _ => None,
+                };
+                let share_cap = distribution
+                    .max_validator_share_bps
+                    .map(|bps| share(available_delegation, bps));
+
+                stake_cap
+                    .into_iter()
+                    .chain(share_cap)
+                    .min()
+                    .unwrap_or(u64::MAX)
+            })
+            .collect();
+
+        let mut targets = vec![0; eligible_validators.len()];
+        let mut remaining = available_delegation;
+        let mut uncapped: Vec<usize> = (0..eligible_validators.len())
+            .filter(|&i| weights[i] > 0 && caps[i] > 0)
+            .collect();
+
+        while !uncapped.is_empty() && remaining > 0 {
+            let total_weight: u128 = uncapped.iter().map(|&i| weights[i]).sum();
+            let target = |i: usize| (remaining as u128 * weights[i] / total_weight) as u64;
+
+            let (capped, rest): (Vec<usize>, Vec<usize>) =
+                uncapped.iter().partition(|&&i| target(i) >= caps[i]);
+            if capped.is_empty() {
+                for &i in &rest {
+                    targets[i] = target(i);
+                }
+                break;
+            }
+
+            for &i in &capped {
+                targets[i] = caps[i];
+                remaining -= caps[i];
+            }
+            uncapped = rest;
+        }
+
+        eligible_validators
+            .iter()
+            .zip(targets)
+            .map(|(validator, target)| (validator.get_vote_account(), target))
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn criteria() -> BamDelegationCriteria {
+        BamDelegationCriteria::load(None).unwrap()
+    }
+
     #[test]
     fn test_stakeweight_calculation() {
-        let criteria = BamDelegationCriteria::new();
+        let criteria = criteria();
 
         // 25% = 2500 BPS
         assert_eq!(
@@ -104,7 +366,7 @@ mod tests {
 
     #[test]
     fn test_two_epoch_validation_initial_epoch() {
-        let criteria = BamDelegationCriteria::new();
+        let criteria = criteria();
 
         // First epoch ever - no previous data
         let current = BamEpochMetrics::new(
@@ -116,12 +378,17 @@ mod tests {
         );
 
         // Should only get initial 30% regardless of current stakeweight
-        assert_eq!(criteria.calculate_current_allocation(&current, None), 3000);
+        assert_eq!(
+            criteria
+                .calculate_current_allocation(&current, &[])
+                .allocation_bps,
+            3000
+        );
     }
 
     #[test]
     fn test_two_epoch_validation_tier_advancement() {
-        let criteria = BamDelegationCriteria::new();
+        let criteria = criteria();
 
         let previous = BamEpochMetrics::new(
             99,
@@ -141,14 +408,16 @@ mod tests {
 
         // Both epochs at 25% -> should advance to 75% allocation
         assert_eq!(
-            criteria.calculate_current_allocation(&current, Some(&previous)),
+            criteria
+                .calculate_current_allocation(&current, &[previous.clone()])
+                .allocation_bps,
             7500
         );
     }
 
     #[test]
     fn test_two_epoch_validation_insufficient_previous_epoch() {
-        let criteria = BamDelegationCriteria::new();
+        let criteria = criteria();
 
         let previous = BamEpochMetrics::new(
             99,
@@ -169,14 +438,16 @@ mod tests {
         // Current is 25% but previous was only 20%
         // Should stay at 50% tier (20% threshold met in both)
         assert_eq!(
-            criteria.calculate_current_allocation(&current, Some(&previous)),
+            criteria
+                .calculate_current_allocation(&current, &[previous.clone()])
+                .allocation_bps,
             5000
         );
     }
 
     #[test]
     fn test_two_epoch_validation_insufficient_current_epoch() {
-        let criteria = BamDelegationCriteria::new();
+        let criteria = criteria();
 
         let previous = BamEpochMetrics::new(
             99,
@@ -197,14 +468,16 @@ mod tests {
         // Previous was 25% but current dropped to 20%
         // Should fall back to 50% tier (20% threshold met in both)
         assert_eq!(
-            criteria.calculate_current_allocation(&current, Some(&previous)),
+            criteria
+                .calculate_current_allocation(&current, &[previous.clone()])
+                .allocation_bps,
             5000
         );
     }
 
     #[test]
     fn test_two_epoch_validation_volatility_protection() {
-        let criteria = BamDelegationCriteria::new();
+        let criteria = criteria();
 
         // Epoch N-1: 24% (just below 25% threshold)
         let previous = BamEpochMetrics::new(99, 96_000_000, 400_000_000, 200_000_000, 10);
@@ -215,14 +488,16 @@ mod tests {
         // Even though current is above 25%, previous wasn't
         // Should stay at 50% (20% tier) not jump to 75% (25% tier)
         assert_eq!(
-            criteria.calculate_current_allocation(&current, Some(&previous)),
+            criteria
+                .calculate_current_allocation(&current, &[previous.clone()])
+                .allocation_bps,
             5000
         );
     }
 
     #[test]
     fn test_two_epoch_validation_highest_tier() {
-        let criteria = BamDelegationCriteria::new();
+        let criteria = criteria();
 
         let previous = BamEpochMetrics::new(
             99,
@@ -242,14 +517,16 @@ mod tests {
 
         // Both epochs above 30% threshold -> 100% allocation
         assert_eq!(
-            criteria.calculate_current_allocation(&current, Some(&previous)),
+            criteria
+                .calculate_current_allocation(&current, &[previous.clone()])
+                .allocation_bps,
             10_000
         );
     }
 
     #[test]
     fn test_two_epoch_validation_multiple_tier_jump_prevented() {
-        let criteria = BamDelegationCriteria::new();
+        let criteria = criteria();
 
         // Previous: 15% stakeweight
         let previous = BamEpochMetrics::new(99, 60_000_000, 400_000_000, 200_000_000, 10);
@@ -260,14 +537,16 @@ mod tests {
         // Can't skip tiers - previous only qualified for initial 30%
         // Should get 30% allocation, not 100%
         assert_eq!(
-            criteria.calculate_current_allocation(&current, Some(&previous)),
+            criteria
+                .calculate_current_allocation(&current, &[previous.clone()])
+                .allocation_bps,
             3000
         );
     }
 
     #[test]
     fn test_two_epoch_validation_gradual_progression() {
-        let criteria = BamDelegationCriteria::new();
+        let criteria = criteria();
 
         // Simulate progression through tiers
 
@@ -275,27 +554,33 @@ mod tests {
         let epoch_1 = BamEpochMetrics::new(100, 80_000_000, 400_000_000, 200_000_000, 10);
         let epoch_2 = BamEpochMetrics::new(101, 80_000_000, 400_000_000, 200_000_000, 10);
         assert_eq!(
-            criteria.calculate_current_allocation(&epoch_2, Some(&epoch_1)),
+            criteria
+                .calculate_current_allocation(&epoch_2, &[epoch_1.clone()])
+                .allocation_bps,
             5000
         );
 
         // Epoch 3: Both at 25% -> 75% allocation
         let epoch_3 = BamEpochMetrics::new(102, 100_000_000, 400_000_000, 200_000_000, 10);
         assert_eq!(
-            criteria.calculate_current_allocation(&epoch_3, Some(&epoch_2)),
+            criteria
+                .calculate_current_allocation(&epoch_3, &[epoch_2.clone()])
+                .allocation_bps,
             5000
         );
 
         let epoch_4 = BamEpochMetrics::new(103, 100_000_000, 400_000_000, 200_000_000, 10);
         assert_eq!(
-            criteria.calculate_current_allocation(&epoch_4, Some(&epoch_3)),
+            criteria
+                .calculate_current_allocation(&epoch_4, &[epoch_3.clone()])
+                .allocation_bps,
             7500
         );
     }
 
     #[test]
     fn test_two_epoch_validation_edge_case_zero_stake() {
-        let criteria = BamDelegationCriteria::new();
+        let criteria = criteria();
 
         let previous = BamEpochMetrics::new(
             99,
@@ -315,8 +600,214 @@ mod tests {
 
         // Previous had 0%, so can only get initial 30%
         assert_eq!(
-            criteria.calculate_current_allocation(&current, Some(&previous)),
+            criteria
+                .calculate_current_allocation(&current, &[previous.clone()])
+                .allocation_bps,
             3000
         );
     }
+
+    const SCHEDULE: &str = r#"
+[[schedule]]
+version = "jip-28"
+effective_epoch = 0
+confirmation_epochs = 2
+floor_bps = 3000
+tiers = [
+    { stakeweight_bps = 0, allocation_bps = 3000 },
+    { stakeweight_bps = 2000, allocation_bps = 5000 },
+]
+
+[[schedule]]
+version = "jip-99"
+effective_epoch = 200
+confirmation_epochs = 3
+floor_bps = 4000
+tiers = [
+    { stakeweight_bps = 1000, allocation_bps = 2000 },
+    { stakeweight_bps = 2000, allocation_bps = 6000 },
+]
+
+[schedule.distribution]
+strategy = "score_weighted"
+max_validator_share_bps = 4000
+"#;
+
+    #[test]
+    fn test_schedule_rules_by_epoch() {
+        let criteria = BamDelegationCriteria::from_toml_str(SCHEDULE).unwrap();
+
+        assert_eq!(criteria.rules_for(0).version, "jip-28");
+        assert_eq!(criteria.rules_for(199).version, "jip-28");
+        assert_eq!(criteria.rules_for(200).version, "jip-99");
+        assert_eq!(criteria.rules_for(1_000).version, "jip-99");
+    }
+
+    #[test]
+    fn test_confirmation_epochs_from_schedule() {
+        let criteria = BamDelegationCriteria::from_toml_str(SCHEDULE).unwrap();
+
+        // 25% stakeweight in each epoch
+        let epoch_200 = BamEpochMetrics::new(200, 100_000_000, 400_000_000, 200_000_000, 10);
+        let epoch_201 = BamEpochMetrics::new(201, 100_000_000, 400_000_000, 200_000_000, 10);
+        let epoch_202 = BamEpochMetrics::new(202, 100_000_000, 400_000_000, 200_000_000, 10);
+
+        // Only two of the three confirmation epochs
+        let tier = criteria.calculate_current_allocation(&epoch_202, &[epoch_201.clone()]);
+        assert_eq!(
+            tier,
+            BamAllocationTier {
+                rules_version: "jip-99".to_string(),
+                stakeweight_threshold_bps: None,
+                allocation_bps: 4000,
+                confirmation_epochs: 3,
+                confirmed_stakeweight_bps: None,
+                reason: BamAllocationReason::InsufficientHistory,
+            }
+        );
+
+        let tier = criteria.calculate_current_allocation(&epoch_202, &[epoch_201, epoch_200]);
+        assert_eq!(
+            tier,
+            BamAllocationTier {
+                rules_version: "jip-99".to_string(),
+                stakeweight_threshold_bps: Some(2000),
+                allocation_bps: 6000,
+                confirmation_epochs: 3,
+                confirmed_stakeweight_bps: Some(2500),
+                reason: BamAllocationReason::TierConfirmed,
+            }
+        );
+    }
+
+    #[test]
+    fn test_floor_above_tier_allocation() {
+        let criteria = BamDelegationCriteria::from_toml_str(SCHEDULE).unwrap();
+
+        // 15% stakeweight meets the 10% tier, whose 20% allocation is below the 40% floor
+        let epoch_200 = BamEpochMetrics::new(200, 60_000_000, 400_000_000, 200_000_000, 10);
+        let epoch_201 = BamEpochMetrics::new(201, 60_000_000, 400_000_000, 200_000_000, 10);
+        let epoch_202 = BamEpochMetrics::new(202, 60_000_000, 400_000_000, 200_000_000, 10);
+
+        let tier = criteria.calculate_current_allocation(&epoch_202, &[epoch_201, epoch_200]);
+        assert_eq!(tier.allocation_bps, 4000);
+        assert_eq!(tier.stakeweight_threshold_bps, None);
+        assert_eq!(tier.confirmed_stakeweight_bps, Some(1500));
+        assert_eq!(tier.reason, BamAllocationReason::Floor);
+    }
+
+    #[test]
+    fn test_invalid_schedule_rejected() {
+        for (from, to) in [
+            ("effective_epoch = 0", "effective_epoch = 1"),
+            ("effective_epoch = 200", "effective_epoch = 0"),
+            ("confirmation_epochs = 2", "confirmation_epochs = 0"),
+            ("floor_bps = 3000", "floor_bps = 10001"),
+            (
+                "stakeweight_bps = 2000, allocation_bps = 5000",
+                "stakeweight_bps = 0, allocation_bps = 5000",
+            ),
+            ("version = \"jip-99\"", "version = \"\""),
+            (
+                "max_validator_share_bps = 4000",
+                "max_validator_share_bps = 0",
+            ),
+            ("max_validator_share_bps = 4000", "stake_cap_bps = 5000"),
+            ("\"score_weighted\"", "\"stake_capped\""),
+            ("\"score_weighted\"", "\"largest_first\""),
+        ] {
+            let contents = SCHEDULE.replacen(from, to, 1);
+            assert!(
+                BamDelegationCriteria::from_toml_str(&contents).is_err(),
+                "{to} should be rejected"
+            );
+        }
+    }
+
+    fn bam_validator(vote_account: &str, active_stake: u64, score: u8) -> BamValidator {
+        let mut validator = BamValidator::new(active_stake, 200, "identity", true, vote_account);
+        validator.set_score(score);
+        validator
+    }
+
+    fn targets(distribution: &[(String, u64)]) -> Vec<u64> {
+        distribution.iter().map(|(_, target)| *target).collect()
+    }
+
+    #[test]
+    fn test_distribution_from_schedule() {
+        let criteria = BamDelegationCriteria::from_toml_str(SCHEDULE).unwrap();
+
+        assert_eq!(
+            criteria.rules_for(0).distribution,
+            BamDistribution::default()
+        );
+        assert_eq!(
+            criteria.rules_for(200).distribution,
+            BamDistribution {
+                strategy: BamDistributionStrategy::ScoreWeighted,
+                stake_cap_bps: None,
+                max_validator_share_bps: Some(4000),
+            }
+        );
+    }
+
+    #[test]
+    fn test_equal_distribution() {
+        let criteria = criteria();
+        let validators = [
+            bam_validator("a", 100, 90),
+            bam_validator("b", 1_000, 50),
+            bam_validator("c", 10_000, 10),
+        ];
+
+        let distribution = criteria.distribute(100, 1_000, &validators);
+        assert_eq!(distribution[0], ("a".to_string(), 333));
+        assert_eq!(targets(&distribution), vec![333, 333, 333]);
+        assert!(criteria.distribute(100, 1_000, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_stake_capped_distribution() {
+        let criteria = BamDelegationCriteria::from_toml_str(&SCHEDULE.replacen(
+            "strategy = \"score_weighted\"",
+            "strategy = \"stake_capped\"\nstake_cap_bps = 20000",
+            1,
+        ))
+        .unwrap();
+        let validators = [
+            bam_validator("a", 100, 90),
+            bam_validator("b", 1_000, 50),
+            bam_validator("c", 10_000, 10),
+        ];
+
+        // a is capped at twice its stake, b and c split the rest up to 40% of the allocation each
+        assert_eq!(
+            targets(&criteria.distribute(200, 1_000, &validators)),
+            vec![200, 400, 400]
+        );
+
+        // Nobody can take more than twice its stake, the rest stays undelegated
+        assert_eq!(
+            targets(&criteria.distribute(200, 100_000, &validators[..2])),
+            vec![200, 2_000]
+        );
+    }
+
+    #[test]
+    fn test_score_weighted_distribution() {
+        let criteria = BamDelegationCriteria::from_toml_str(SCHEDULE).unwrap();
+        let validators = [
+            bam_validator("a", 100, 90),
+            bam_validator("b", 1_000, 15),
+            bam_validator("c", 10_000, 10),
+            bam_validator("d", 10_000, 0),
+        ];
+
+        // a is capped at 40% of the allocation, b and c split the rest 3 to 2
+        assert_eq!(
+            targets(&criteria.distribute(200, 2_000, &validators)),
+            vec![800, 720, 480, 0]
+        );
+    }
 }
@@ -0,0 +1,245 @@
+//! Per-validator BAM delegation plan.
+//!
+//! Every run records the directed stake target of each eligible validator next to the BAM share
+//! of the directed stake the steward directed stake meta currently holds for it. Validators that
+//! lost eligibility are kept in the plan with a zero target, so their directed stake is removed.
+
+use std::collections::{BTreeSet, HashMap};
+
+use kobe_core::db_models::{
+    bam_delegation_plans::{BamDelegationPlan, BamDelegationTarget},
+    bam_epoch_metrics::BamDistribution,
+};
+use serde::{Deserialize, Serialize};
+
+/// Directed stake target in the format the directed stake meta uploader consumes
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectedStakeTargetUpload {
+    pub vote_pubkey: String,
+    pub total_target_lamports: u64,
+}
+
+/// BAM share of the directed stake per vote account
+///
+/// The directed stake meta does not say which source directed a validator's stake, so the BAM
+/// share is capped at the most `earlier_plans` targeted or recorded for the validator; the rest is
+/// directed by other sources.
+pub fn bam_directed_lamports(
+    earlier_plans: &[&BamDelegationPlan],
+    directed_stake_lamports: &HashMap<String, u64>,
+) -> HashMap<String, u64> {
+    let mut bam_lamports: HashMap<String, u64> = HashMap::new();
+    for target in earlier_plans.iter().flat_map(|plan| plan.targets.iter()) {
+        let directed = directed_stake_lamports
+            .get(&target.vote_account)
+            .copied()
+            .unwrap_or(0);
+        let share = directed.min(target.target_lamports.max(target.current_lamports));
+        let entry = bam_lamports.entry(target.vote_account.clone()).or_default();
+        *entry = (*entry).max(share);
+    }
+
+    bam_lamports.retain(|_, lamports| *lamports > 0);
+    bam_lamports
+}
+
+/// Build the delegation plan of an epoch
+///
+/// `eligible_targets` are the target lamports of the eligible validators. `earlier_plans` are the
+/// plans that may have directed stake already: the previous epoch's and an earlier run of this
+/// epoch's. The validators they targeted, and every validator still holding BAM-directed stake,
+/// get a zero target unless eligible. `directed_stake_lamports` is the directed stake per vote
+/// account from all sources.
+pub fn build_delegation_plan(
+    epoch: u64,
+    available_bam_delegation_stake: u64,
+    distribution: Option<BamDistribution>,
+    eligible_targets: &[(String, u64)],
+    earlier_plans: &[&BamDelegationPlan],
+    directed_stake_lamports: &HashMap<String, u64>,
+) -> BamDelegationPlan {
+    let bam_lamports = bam_directed_lamports(earlier_plans, directed_stake_lamports);
+    let current = |vote_account: &str| bam_lamports.get(vote_account).copied().unwrap_or(0);
+
+    let mut targets: Vec<BamDelegationTarget> = eligible_targets
+        .iter()
+        .map(|(vote_account, target_lamports)| {
+            BamDelegationTarget::new(vote_account, true, *target_lamports, current(vote_account))
+        })
+        .collect();
+
+    let previously_targeted = earlier_plans
+        .iter()
+        .flat_map(|plan| plan.targets.iter())
+        .filter(|target| target.target_lamports > 0)
+        .map(|target| target.vote_account.as_str());
+    let still_staked = bam_lamports.keys().map(String::as_str);
+    let lost_eligibility: BTreeSet<&str> = previously_targeted
+        .chain(still_staked)
+        .filter(|vote_account| {
+            !eligible_targets
+                .iter()
+                .any(|(eligible, _)| eligible == vote_account)
+        })
+        .collect();
+
+    targets.extend(lost_eligibility.into_iter().map(|vote_account| {
+        BamDelegationTarget::new(vote_account, false, 0, current(vote_account))
+    }));
+
+    BamDelegationPlan::new(epoch, available_bam_delegation_stake, distribution, targets)
+}
+
+/// Render the targets of `plan` in the format the directed stake meta uploader consumes
+pub fn render_directed_stake_targets(plan: &BamDelegationPlan) -> anyhow::Result<String> {
+    let targets: Vec<DirectedStakeTargetUpload> = plan
+        .targets
+        .iter()
+        .map(|target| DirectedStakeTargetUpload {
+            vote_pubkey: target.vote_account.clone(),
+            total_target_lamports: target.target_lamports,
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&targets)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Directed stake from all sources, b and d also hold non-BAM directed stake
+    fn directed_stake_lamports() -> HashMap<String, u64> {
+        HashMap::from([
+            ("a".to_string(), 400),
+            ("b".to_string(), 700),
+            ("c".to_string(), 300),
+            ("d".to_string(), 200),
+            ("h".to_string(), 250),
+        ])
+    }
+
+    fn previous_plan() -> BamDelegationPlan {
+        BamDelegationPlan::new(
+            799,
+            1_500,
+            None,
+            vec![
+                BamDelegationTarget::new("a", true, 500, 400),
+                BamDelegationTarget::new("b", true, 600, 600),
+                BamDelegationTarget::new("c", true, 500, 500),
+                BamDelegationTarget::new("e", false, 0, 0),
+                BamDelegationTarget::new("h", false, 0, 250),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_bam_directed_lamports() {
+        let previous_plan = previous_plan();
+
+        assert_eq!(
+            bam_directed_lamports(&[&previous_plan], &directed_stake_lamports()),
+            HashMap::from([
+                ("a".to_string(), 400),
+                ("b".to_string(), 600),
+                ("c".to_string(), 300),
+                ("h".to_string(), 250),
+            ])
+        );
+        assert!(bam_directed_lamports(&[], &directed_stake_lamports()).is_empty());
+    }
+
+    #[test]
+    fn test_targets_and_deltas() {
+        let previous_plan = previous_plan();
+        let plan = build_delegation_plan(
+            800,
+            1_000,
+            None,
+            &[("a".to_string(), 500), ("b".to_string(), 500)],
+            &[&previous_plan],
+            &directed_stake_lamports(),
+        );
+
+        assert_eq!(
+            plan.targets[0],
+            BamDelegationTarget::new("a", true, 500, 400)
+        );
+        assert_eq!(plan.targets[0].delta_lamports, 100);
+        // Only the 600 lamports the previous plan directed to b are BAM's
+        assert_eq!(
+            plan.targets[1],
+            BamDelegationTarget::new("b", true, 500, 600)
+        );
+        assert_eq!(plan.targets[1].delta_lamports, -100);
+        assert_eq!(plan.total_target_lamports(), 1_000);
+    }
+
+    #[test]
+    fn test_lost_eligibility_gets_zero_target() {
+        let previous_plan = previous_plan();
+        let earlier_run = BamDelegationPlan::new(
+            800,
+            1_000,
+            None,
+            vec![
+                BamDelegationTarget::new("a", true, 700, 400),
+                BamDelegationTarget::new("g", true, 300, 0),
+            ],
+        );
+
+        let plan = build_delegation_plan(
+            800,
+            1_000,
+            None,
+            &[("a".to_string(), 1_000)],
+            &[&previous_plan, &earlier_run],
+            &directed_stake_lamports(),
+        );
+
+        // b and c were targeted by the previous plan, g by the earlier run of the epoch, h still
+        // holds BAM-directed stake; d only holds non-BAM directed stake, e holds none
+        assert_eq!(
+            plan.targets,
+            vec![
+                BamDelegationTarget::new("a", true, 1_000, 400),
+                BamDelegationTarget::new("b", false, 0, 600),
+                BamDelegationTarget::new("c", false, 0, 300),
+                BamDelegationTarget::new("g", false, 0, 0),
+                BamDelegationTarget::new("h", false, 0, 250),
+            ]
+        );
+        assert_eq!(plan.targets[2].delta_lamports, -300);
+    }
+
+    #[test]
+    fn test_render_directed_stake_targets() {
+        let plan = BamDelegationPlan::new(
+            800,
+            1_000,
+            None,
+            vec![
+                BamDelegationTarget::new("a", true, 1_000, 400),
+                BamDelegationTarget::new("c", false, 0, 300),
+            ],
+        );
+
+        let json = render_directed_stake_targets(&plan).unwrap();
+        let targets: Vec<DirectedStakeTargetUpload> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            targets,
+            vec![
+                DirectedStakeTargetUpload {
+                    vote_pubkey: "a".to_string(),
+                    total_target_lamports: 1_000,
+                },
+                DirectedStakeTargetUpload {
+                    vote_pubkey: "c".to_string(),
+                    total_target_lamports: 0,
+                },
+            ]
+        );
+    }
+}
@@ -1,12 +1,21 @@
 use std::{collections::HashMap, str::FromStr, sync::Arc};
 
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use bam_api_client::{client::BamApiClient, types::ValidatorsResponse};
+use chrono::Utc;
 use clap::ValueEnum;
-use kobe_client::{client::KobeClient, client_builder::KobeApiClientBuilder};
-use kobe_core::db_models::{
-    bam_epoch_metrics::{BamEpochMetrics, BamEpochMetricsStore},
-    bam_validators::{BamValidator, BamValidatorStore},
+use kobe_core::{
+    bam_eligibility_rules::BamEligibilityRules,
+    bam_validator_eligibility::BamValidatorEligibility,
+    db_models::{
+        bam_delegation_blacklist::{BamDelegationBlacklistEntry, BamDelegationBlacklistStore},
+        bam_delegation_plans::{BamDelegationPlan, BamDelegationPlanStore},
+        bam_epoch_metrics::{
+            BamAllocationReason, BamAllocationTier, BamEpochMetrics, BamEpochMetricsStore,
+        },
+        bam_validators::{BamValidator, BamValidatorStore},
+        bam_writer_snapshots::{BamWriterSnapshot, BamWriterSnapshotStore},
+    },
 };
 use mongodb::Collection;
 use solana_client::nonblocking::rpc_client::RpcClient;
@@ -15,31 +24,37 @@ use solana_pubkey::Pubkey;
 use stakenet_sdk::{
     models::cluster::Cluster,
     utils::accounts::{
-        get_all_steward_accounts, get_all_validator_history_accounts, get_stake_pool_account,
+        get_all_steward_accounts, get_all_validator_history_accounts, get_directed_stake_meta,
+        get_stake_pool_account,
     },
 };
 
 use crate::{
-    bam_delegation_criteria::BamDelegationCriteria,
-    bam_validator_eligibility::{BamValidatorEligibility, IneligibilityReason},
+    bam_delegation_criteria::BamDelegationCriteria, bam_delegation_plan::build_delegation_plan,
+    run_schedule::ScheduledRun,
 };
 
-mod bam_delegation_criteria;
-mod bam_validator_eligibility;
+pub mod backtest;
+pub mod bam_delegation_criteria;
+pub mod bam_delegation_plan;
+pub mod run_schedule;
 
 pub struct BamWriterService {
     /// Cluster name (mainnet-beta, testnet)
     cluster: Cluster,
 
-    /// Kobe api client
-    kobe_api_client: KobeClient,
-
     /// Stake pool address
     stake_pool: Pubkey,
 
     /// Steward config
     steward_config: Pubkey,
 
+    /// Steward program ID
+    steward_program_id: Pubkey,
+
+    /// Validator history program ID
+    validator_history_program_id: Pubkey,
+
     /// RPC Client
     rpc_client: Arc<RpcClient>,
 
@@ -52,9 +67,21 @@ pub struct BamWriterService {
     /// Bam epoch metrics store
     bam_epoch_metrics_store: BamEpochMetricsStore,
 
+    /// BAM delegation plan store
+    bam_delegation_plan_store: BamDelegationPlanStore,
+
+    /// BAM delegation blacklist store
+    bam_delegation_blacklist_store: BamDelegationBlacklistStore,
+
+    /// BAM writer snapshot store
+    bam_writer_snapshot_store: BamWriterSnapshotStore,
+
     /// BAM Delegation Criteria
     bam_delegation_criteria: BamDelegationCriteria,
 
+    /// BAM eligibility rules
+    eligibility_rules: BamEligibilityRules,
+
     /// Override eligible validators with a hardcoded list (vote account pubkeys)
     override_eligible_validators: Option<Vec<Pubkey>>,
 
@@ -71,19 +98,18 @@ impl BamWriterService {
         mongo_db_name: &str,
         stake_pool: Pubkey,
         steward_config: Pubkey,
+        steward_program_id: Pubkey,
+        validator_history_program_id: Pubkey,
         rpc_client: Arc<RpcClient>,
         bam_api_base_url: &str,
-        kobe_api_base_url: &str,
+        eligibility_rules: BamEligibilityRules,
+        bam_delegation_criteria: BamDelegationCriteria,
         override_eligible_validators: Option<Vec<Pubkey>>,
         override_delegation_lamports: Option<u64>,
     ) -> anyhow::Result<Self> {
         let cluster = Cluster::from_str(cluster, false)
             .map_err(|e| anyhow!("Failed to read cluster: {e}"))?;
 
-        let kobe_api_client = KobeApiClientBuilder::new()
-            .base_url(kobe_api_base_url)
-            .build();
-
         // Connect to MongoDB
         let client = mongodb::Client::with_uri_str(mongo_connection_uri).await?;
         let db = client.database(mongo_db_name);
@@ -96,7 +122,24 @@ impl BamWriterService {
             db.collection(BamEpochMetricsStore::COLLECTION);
         let bam_epoch_metrics_store = BamEpochMetricsStore::new(bam_epoch_metrics_collection);
 
-        let bam_delegation_criteria = BamDelegationCriteria::new();
+        let bam_delegation_plan_collection: Collection<BamDelegationPlan> =
+            db.collection(BamDelegationPlanStore::COLLECTION);
+        let bam_delegation_plan_store = BamDelegationPlanStore::new(bam_delegation_plan_collection);
+
+        let bam_delegation_blacklist_collection: Collection<BamDelegationBlacklistEntry> =
+            db.collection(BamDelegationBlacklistStore::COLLECTION);
+        let bam_delegation_blacklist_store =
+            BamDelegationBlacklistStore::new(bam_delegation_blacklist_collection);
+
+        let bam_writer_snapshot_collection: Collection<BamWriterSnapshot> =
+            db.collection(BamWriterSnapshotStore::COLLECTION);
+        let bam_writer_snapshot_store = BamWriterSnapshotStore::new(bam_writer_snapshot_collection);
+
+        log::info!(
+            "Using BAM eligibility rules {} ({})",
+            eligibility_rules.version,
+            eligibility_rules.hash()
+        );
 
         if let Some(ref overrides) = override_eligible_validators {
             log::info!(
@@ -116,14 +159,19 @@ impl BamWriterService {
 
         Ok(Self {
             cluster,
-            kobe_api_client,
             stake_pool,
             steward_config,
+            steward_program_id,
+            validator_history_program_id,
             rpc_client,
             bam_api_base_url: bam_api_base_url.to_string(),
             bam_validators_store,
             bam_epoch_metrics_store,
+            bam_delegation_plan_store,
+            bam_delegation_blacklist_store,
+            bam_writer_snapshot_store,
             bam_delegation_criteria,
+            eligibility_rules,
             override_eligible_validators,
             override_delegation_lamports,
         })
@@ -172,10 +220,64 @@ impl BamWriterService {
         }
     }
 
-    /// Run [`BamWriterService`]
-    pub async fn run(&self) -> anyhow::Result<()> {
+    /// Vote accounts on the BAM delegation blacklist, expired entries excluded
+    async fn get_blacklist_validators(&self) -> anyhow::Result<Vec<Pubkey>> {
+        let bam_delegation_blacklist = self
+            .bam_delegation_blacklist_store
+            .find_active(Utc::now())
+            .await?;
+
+        Ok(bam_delegation_blacklist
+            .iter()
+            .filter_map(|entry| Pubkey::from_str(entry.get_vote_account()).ok())
+            .collect())
+    }
+
+    /// Directed stake per vote account from all sources, as held by the steward directed stake
+    /// meta
+    async fn get_directed_stake_lamports(&self) -> anyhow::Result<HashMap<String, u64>> {
+        let directed_stake_meta = get_directed_stake_meta(
+            self.rpc_client.clone(),
+            &self.steward_config,
+            &self.steward_program_id,
+        )
+        .await?;
+
+        Ok(directed_stake_meta
+            .targets
+            .iter()
+            .map(|target| (target.vote_pubkey.to_string(), target.total_staked_lamports))
+            .collect())
+    }
+
+    /// Delegation plan of `epoch`, the latest one when unset
+    pub async fn get_delegation_plan(
+        &self,
+        epoch: Option<u64>,
+    ) -> anyhow::Result<Option<BamDelegationPlan>> {
+        Ok(self.bam_delegation_plan_store.find_by_epoch(epoch).await?)
+    }
+
+    /// Thresholds in BPS that already ran in `epoch`
+    pub async fn get_completed_thresholds(&self, epoch: u64) -> anyhow::Result<Vec<u64>> {
+        Ok(self
+            .bam_writer_snapshot_store
+            .find_threshold_bps(epoch)
+            .await?)
+    }
+
+    /// Run [`BamWriterService`] for a scheduled run of `epoch`, and record its snapshot
+    ///
+    /// Records are keyed by epoch, and the snapshot by epoch and threshold, so repeating a run
+    /// replaces what it wrote.
+    pub async fn run(&self, epoch: u64, scheduled_run: &ScheduledRun) -> anyhow::Result<()> {
         let epoch_info = self.rpc_client.get_epoch_info().await?;
-        let epoch = epoch_info.epoch;
+        if epoch_info.epoch != epoch {
+            bail!(
+                "Epoch {epoch} ended before its run, now in epoch {}",
+                epoch_info.epoch
+            );
+        }
 
         let jitosol_pool = get_stake_pool_account(&self.rpc_client, &self.stake_pool).await?;
         let jitosol_stake = jitosol_pool.total_lamports;
@@ -248,27 +350,23 @@ impl BamWriterService {
                 }
             }
 
-            let bam_delegation_blacklist =
-                self.kobe_api_client.get_bam_delegation_blacklist().await?;
-            let blacklist_validators: Vec<Pubkey> = bam_delegation_blacklist
-                .into_iter()
-                .filter_map(|entry| Pubkey::from_str(&entry.vote_account).ok())
-                .collect();
+            let blacklist_validators = self.get_blacklist_validators().await?;
 
             let steward_all_accounts = get_all_steward_accounts(
                 &self.rpc_client.clone(),
-                &jito_steward::id(),
+                &self.steward_program_id,
                 &self.steward_config,
             )
             .await?;
 
             let validator_histories = get_all_validator_history_accounts(
                 &self.rpc_client.clone(),
-                validator_history::id(),
+                self.validator_history_program_id,
             )
             .await?;
 
-            let eligibility_checker = BamValidatorEligibility::new(epoch, &validator_histories);
+            let eligibility_checker =
+                BamValidatorEligibility::new(epoch, &validator_histories, &self.eligibility_rules);
             let mut validators: Vec<BamValidator> = Vec::new();
 
             for validator_history in validator_histories.iter() {
@@ -289,7 +387,7 @@ impl BamWriterService {
                     ) {
                         Ok(()) => {
                             bam_validator.set_is_eligible(true);
-                            bam_validator.set_score(1);
+                            bam_validator.set_score(eligibility_checker.score(validator_history));
                             datapoint_info!(
                                 "bam-eligible-validators",
                                 ("epoch", epoch, i64),
@@ -298,45 +396,8 @@ impl BamWriterService {
                                 "cluster" => self.cluster.to_string(),
                             );
                         }
-                        Err(reason) => {
-                            let reason_string = match reason {
-                                IneligibilityReason::NotBamClient => "NotBamClient".to_string(),
-                                IneligibilityReason::NonZeroCommission { epoch, commission } => {
-                                    format!("NonZeroCommission: {} in epoch {}", commission, epoch)
-                                }
-                                IneligibilityReason::HighMevCommission {
-                                    epoch,
-                                    mev_commission,
-                                } => {
-                                    format!(
-                                        "HighMevCommission: {} in epoch {}",
-                                        mev_commission, epoch
-                                    )
-                                }
-                                IneligibilityReason::InSuperminority { epoch } => {
-                                    format!("InSuperminority in epoch {}", epoch)
-                                }
-                                IneligibilityReason::LowVoteCredits {
-                                    epoch,
-                                    credits,
-                                    min_required,
-                                } => {
-                                    format!(
-                                        "LowVoteCredits: {} credits (required: {}) in epoch {}",
-                                        credits, min_required, epoch
-                                    )
-                                }
-                                IneligibilityReason::InsufficientHistory => {
-                                    "InsufficientHistory: Less than 3 epochs".to_string()
-                                }
-                                IneligibilityReason::OnChainBlacklist => {
-                                    format!("Blacklist on-chain in epoch {epoch}")
-                                }
-                                IneligibilityReason::OffChainBlacklist => {
-                                    format!("Blacklist off-chain in epoch {epoch}")
-                                }
-                            };
-                            bam_validator.set_ineligibility_reason(Some(reason_string));
+                        Err(reasons) => {
+                            bam_validator.set_ineligibility_reasons(reasons);
                             bam_validator.set_score(0);
                         }
                     }
@@ -350,6 +411,7 @@ impl BamWriterService {
         self.bam_validators_store
             .upsert(&bam_validators, epoch)
             .await?;
+        let snapshot_validators = bam_validators.clone();
 
         let total_stake = vote_accounts
             .current
@@ -386,67 +448,178 @@ impl BamWriterService {
             jitosol_stake,
             eligible_bam_validators.len() as u64,
         );
+        current_epoch_metrics.set_eligibility_rules(&self.eligibility_rules);
+        current_epoch_metrics.set_snapshot(
+            scheduled_run.snapshot,
+            scheduled_run.threshold_bps,
+            epoch_info.absolute_slot,
+            scheduled_run.is_final,
+        );
 
-        let (allocation_percentage, available_delegation) = if let Some(override_delegation) =
-            self.override_delegation_lamports
-        {
-            // Override mode: use hardcoded delegation amount
-            log::info!(
-                "Using override delegation: {} lamports ({:.2} SOL)",
-                override_delegation,
-                override_delegation as f64 / 1_000_000_000.0
-            );
-
-            (
-                0u64,
-                override_delegation * eligible_bam_validators.len() as u64,
-            )
-        } else {
-            // Normal mode: calculate allocation based on criteria
-            let previous_epoch_metrics = if let Some(prev_epoch) = epoch.checked_sub(1) {
-                self.bam_epoch_metrics_store
-                    .find_by_epoch(prev_epoch)
-                    .await?
+        let (allocation_tier, available_delegation) =
+            if let Some(override_delegation) = self.override_delegation_lamports {
+                // Override mode: use hardcoded delegation amount
+                log::info!(
+                    "Using override delegation: {} lamports ({:.2} SOL)",
+                    override_delegation,
+                    override_delegation as f64 / 1_000_000_000.0
+                );
+
+                let rules = self.bam_delegation_criteria.rules_for(epoch);
+                let allocation_tier = BamAllocationTier {
+                    rules_version: rules.version.clone(),
+                    stakeweight_threshold_bps: None,
+                    allocation_bps: 0,
+                    confirmation_epochs: rules.confirmation_epochs,
+                    confirmed_stakeweight_bps: None,
+                    reason: BamAllocationReason::Override,
+                };
+
+                (
+                    allocation_tier,
+                    override_delegation * eligible_bam_validators.len() as u64,
+                )
             } else {
-                None
-            };
+                // Normal mode: calculate allocation based on criteria
+                let confirmation_epochs = self
+                    .bam_delegation_criteria
+                    .rules_for(epoch)
+                    .confirmation_epochs;
+                let mut previous_epoch_metrics = Vec::new();
+                for prev_epoch in epoch.saturating_sub(confirmation_epochs - 1)..epoch {
+                    if let Some(metrics) = self
+                        .bam_epoch_metrics_store
+                        .find_by_epoch(prev_epoch)
+                        .await?
+                    {
+                        previous_epoch_metrics.push(metrics);
+                    }
+                }
 
-            let allocation_percentage = self.bam_delegation_criteria.calculate_current_allocation(
-                &current_epoch_metrics,
-                previous_epoch_metrics.as_ref(),
-            );
+                let allocation_tier = self
+                    .bam_delegation_criteria
+                    .calculate_current_allocation(&current_epoch_metrics, &previous_epoch_metrics);
 
-            let available_delegation = self
-                .bam_delegation_criteria
-                .calculate_available_delegation(allocation_percentage, jitosol_stake);
+                let available_delegation = self
+                    .bam_delegation_criteria
+                    .calculate_available_delegation(allocation_tier.allocation_bps, jitosol_stake);
 
-            (allocation_percentage, available_delegation)
-        };
+                (allocation_tier, available_delegation)
+            };
 
-        current_epoch_metrics.set_allocation_bps(allocation_percentage);
+        log::info!(
+            "Allocation for epoch {epoch}: {} BPS ({:?}, rules {})",
+            allocation_tier.allocation_bps,
+            allocation_tier.reason,
+            allocation_tier.rules_version
+        );
+        let allocation_percentage = allocation_tier.allocation_bps;
+
+        current_epoch_metrics.set_allocation_tier(allocation_tier);
         current_epoch_metrics.set_available_bam_delegation_stake(available_delegation);
 
-        let delegation_per_validator = if eligible_bam_validators.is_empty() {
-            0
-        } else {
-            self.override_delegation_lamports
-                .unwrap_or(available_delegation / eligible_bam_validators.len() as u64)
+        let (distribution, eligible_targets) = match self.override_delegation_lamports {
+            Some(override_delegation) => (
+                None,
+                eligible_bam_validators
+                    .iter()
+                    .map(|bv| (bv.get_vote_account(), override_delegation))
+                    .collect::<Vec<_>>(),
+            ),
+            None => {
+                let distribution = self
+                    .bam_delegation_criteria
+                    .rules_for(epoch)
+                    .distribution
+                    .clone();
+                current_epoch_metrics.set_distribution(distribution.clone());
+
+                (
+                    Some(distribution),
+                    self.bam_delegation_criteria.distribute(
+                        epoch,
+                        available_delegation,
+                        &eligible_bam_validators,
+                    ),
+                )
+            }
         };
+        let delegated: u64 = eligible_targets.iter().map(|(_, target)| target).sum();
+        let max_delegation_per_validator = eligible_targets
+            .iter()
+            .map(|(_, target)| *target)
+            .max()
+            .unwrap_or(0);
+        let delegation_per_validator = delegated
+            .checked_div(eligible_targets.len() as u64)
+            .unwrap_or(0);
 
         datapoint_info!(
             "bam-writer-run",
             ("epoch", epoch, i64),
             ("slot_index", epoch_info.slot_index, i64),
+            ("snapshot", scheduled_run.snapshot as i64, i64),
+            ("final", scheduled_run.is_final, bool),
             ("allocation_bps", allocation_percentage, i64),
             ("available_delegation", available_delegation, i64),
+            ("delegated", delegated, i64),
             ("delegation_per_validator", delegation_per_validator, i64),
+            ("max_delegation_per_validator", max_delegation_per_validator, i64),
+            (
+                "distribution_strategy",
+                distribution
+                    .as_ref()
+                    .map(|d| format!("{:?}", d.strategy))
+                    .unwrap_or_else(|| "Override".to_string()),
+                String
+            ),
             "cluster" => self.cluster.to_string(),
         );
 
         self.bam_epoch_metrics_store
-            .upsert(current_epoch_metrics)
+            .upsert(current_epoch_metrics.clone())
             .await?;
 
+        // An earlier run of this epoch may have directed stake the previous plan did not
+        let previous_plan = self.bam_delegation_plan_store.find_previous(epoch).await?;
+        let earlier_run_plan = self
+            .bam_delegation_plan_store
+            .find_by_epoch(Some(epoch))
+            .await?;
+        let earlier_plans: Vec<&BamDelegationPlan> = previous_plan
+            .iter()
+            .chain(earlier_run_plan.iter())
+            .collect();
+        let directed_stake_lamports = self.get_directed_stake_lamports().await?;
+
+        let delegation_plan = build_delegation_plan(
+            epoch,
+            available_delegation,
+            distribution,
+            &eligible_targets,
+            &earlier_plans,
+            &directed_stake_lamports,
+        );
+        log::info!(
+            "Delegation plan for epoch {epoch}: {} targets, {} lamports",
+            delegation_plan.targets.len(),
+            delegation_plan.total_target_lamports()
+        );
+        self.bam_delegation_plan_store
+            .upsert(&delegation_plan)
+            .await?;
+
+        // Written last, the snapshot marks the threshold as done
+        let snapshot = BamWriterSnapshot::new(current_epoch_metrics, snapshot_validators)
+            .ok_or_else(|| anyhow!("Epoch metrics of epoch {epoch} are missing the run"))?;
+        self.bam_writer_snapshot_store.upsert(&snapshot).await?;
+        log::info!(
+            "Recorded snapshot {} of epoch {epoch} at slot {}{}",
+            snapshot.snapshot,
+            snapshot.slot,
+            if snapshot.is_final { " (final)" } else { "" }
+        );
+
         Ok(())
     }
 }
@@ -1,7 +1,18 @@
-use std::{collections::HashSet, sync::Arc, time::Duration};
+use std::{collections::HashSet, path::PathBuf, sync::Arc, time::Duration};
 
+use anyhow::anyhow;
 use clap::{Parser, Subcommand};
-use kobe_bam_writer_service::BamWriterService;
+use kobe_bam_writer_service::{
+    backtest::{render_backtest, BacktestFormat},
+    bam_delegation_criteria::BamDelegationCriteria,
+    bam_delegation_plan::render_directed_stake_targets,
+    run_schedule::RunSchedule,
+    BamWriterService,
+};
+use kobe_core::{
+    bam_eligibility_rules::BamEligibilityRules, cluster_config::ClusterConfig,
+    validators_app::Cluster,
+};
 use log::{error, info};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_commitment_config::CommitmentConfig;
@@ -30,31 +41,28 @@ struct Args {
     #[clap(long, env)]
     bam_api_base_url: String,
 
-    /// Kobe api base url
+    /// Solana cluster e.g. testnet, mainnet, devnet, localnet
+    #[clap(long, env, default_value = "mainnet")]
+    solana_cluster: String,
+
+    /// Cluster config file (TOML), defaults to the profiles embedded in kobe-core
+    #[clap(long, env)]
+    cluster_config: Option<PathBuf>,
+
+    /// Stake pool address, overrides the cluster config
     #[clap(long, env)]
-    kobe_api_base_url: String,
-
-    /// Stake pool address
-    #[clap(
-        long,
-        env,
-        default_value = "Jito4APyf642JPZPx3hGc6WWJ8zPKtRbRs4P815Awbb"
-    )]
-    stake_pool: Pubkey,
-
-    /// Steward config account address
-    #[clap(
-        long,
-        env,
-        default_value = "jitoVjT9jRUyeXHzvCwzPgHj7yWNRhLcUoXtes4wtjv"
-    )]
-    steward_config: Pubkey,
+    stake_pool: Option<Pubkey>,
+
+    /// Steward config account address, overrides the cluster config
+    #[clap(long, env)]
+    steward_config: Option<Pubkey>,
 
     /// Cluster name for metrics
     #[clap(long, env, default_value = "mainnet")]
     cluster_name: String,
 
-    /// Epoch progress thresholds to trigger (0.0-1.0, default 50%, 75%, 90%)
+    /// Epoch progress thresholds to trigger (0.0-1.0, default 50%, 75%, 90%), the run at the
+    /// highest threshold is the final one of the epoch
     #[clap(long, env, value_delimiter = ',', default_value = "0.5,0.75,0.9")]
     epoch_progress_thresholds: Vec<f64>,
 
@@ -62,6 +70,14 @@ struct Args {
     #[clap(long, env, default_value = "60")]
     poll_interval_secs: u64,
 
+    /// BAM eligibility rules file (TOML), defaults to the JIP-28 rules embedded in kobe-core
+    #[clap(long, env)]
+    eligibility_rules: Option<PathBuf>,
+
+    /// BAM allocation schedule file (TOML), defaults to the JIP-28 schedule embedded in the binary
+    #[clap(long, env)]
+    allocation_schedule: Option<PathBuf>,
+
     /// Override eligible validators with a hardcoded list of vote account pubkeys.
     /// When provided, skips BAM API queries and eligibility calculations.
     /// Comma-separated list of vote account pubkeys.
@@ -78,6 +94,33 @@ struct Args {
 enum Commands {
     /// Run bam writer service
     Run,
+
+    /// Replay eligibility rules and the allocation schedule over past epochs and diff the
+    /// results against what was recorded
+    Backtest {
+        /// First epoch replayed
+        #[clap(long)]
+        from_epoch: u64,
+
+        /// Last epoch replayed
+        #[clap(long)]
+        to_epoch: u64,
+
+        /// Eligibility rules file (TOML) to replay, defaults to --eligibility-rules
+        #[clap(long)]
+        rules: Option<PathBuf>,
+
+        /// Output format
+        #[clap(long, value_enum, default_value = "csv")]
+        format: BacktestFormat,
+    },
+
+    /// Print the delegation plan of an epoch as directed stake meta uploader targets
+    DelegationPlan {
+        /// Epoch of the plan, defaults to the latest plan
+        #[clap(long)]
+        epoch: Option<u64>,
+    },
 }
 
 #[tokio::main]
@@ -85,6 +128,12 @@ async fn main() -> anyhow::Result<()> {
     env_logger::init();
     let args = Args::parse();
 
+    let cluster = Cluster::get_cluster(&args.solana_cluster)?;
+    let cluster_config = ClusterConfig::load(args.cluster_config.as_deref(), cluster)?;
+
+    let eligibility_rules = BamEligibilityRules::load(args.eligibility_rules.as_deref())?;
+    let bam_delegation_criteria = BamDelegationCriteria::load(args.allocation_schedule.as_deref())?;
+
     let rpc_client = RpcClient::new_with_timeout_and_commitment(
         args.rpc_url.to_string(),
         Duration::from_secs(20),
@@ -94,25 +143,68 @@ async fn main() -> anyhow::Result<()> {
 
     let poll_interval = Duration::from_secs(args.poll_interval_secs);
 
+    let backtest_rules = match &args.command {
+        Commands::Backtest {
+            rules: Some(rules), ..
+        } => Some(BamEligibilityRules::load(Some(rules))?),
+        _ => None,
+    };
+
     let bam_writer_service = BamWriterService::new(
         &args.cluster_name,
         &args.mongo_connection_uri,
         &args.mongo_db_name,
-        args.stake_pool,
-        args.steward_config,
+        args.stake_pool.unwrap_or(cluster_config.stake_pool_address),
+        args.steward_config.unwrap_or(cluster_config.steward_config),
+        cluster_config.steward_program_id,
+        cluster_config.validator_history_program_id,
         rpc_client.clone(),
         &args.bam_api_base_url,
-        &args.kobe_api_base_url,
+        eligibility_rules.clone(),
+        bam_delegation_criteria,
         args.override_eligible_validators,
         args.override_delegation_lamports,
     )
     .await?;
 
     match args.command {
+        Commands::Backtest {
+            from_epoch,
+            to_epoch,
+            format,
+            ..
+        } => {
+            let rules = backtest_rules.unwrap_or(eligibility_rules);
+            info!(
+                "Backtesting epochs {from_epoch} to {to_epoch} with BAM eligibility rules {} ({})",
+                rules.version,
+                rules.hash()
+            );
+
+            let epochs = bam_writer_service
+                .backtest(from_epoch, to_epoch, &rules)
+                .await?;
+            println!("{}", render_backtest(&epochs, format)?);
+        }
+        Commands::DelegationPlan { epoch } => {
+            let plan = bam_writer_service
+                .get_delegation_plan(epoch)
+                .await?
+                .ok_or_else(|| anyhow!("No BAM delegation plan found"))?;
+            info!(
+                "Delegation plan for epoch {}: {} targets, {} lamports",
+                plan.epoch,
+                plan.targets.len(),
+                plan.total_target_lamports()
+            );
+
+            println!("{}", render_directed_stake_targets(&plan)?);
+        }
         Commands::Run => {
             info!("Running BAM writer service");
+            let run_schedule = RunSchedule::new(&args.epoch_progress_thresholds)?;
             let mut last_processed_epoch: Option<u64> = None;
-            let mut thresholds_hit = HashSet::new();
+            let mut completed_thresholds: HashSet<u64> = HashSet::new();
 
             loop {
                 let epoch_info = rpc_client.get_epoch_info().await?;
@@ -120,43 +212,62 @@ async fn main() -> anyhow::Result<()> {
                 let progress = epoch_info.slot_index as f64 / epoch_info.slots_in_epoch as f64;
 
                 if last_processed_epoch != Some(current_epoch) {
-                    thresholds_hit.clear();
+                    // Threshold progress is kept in Mongo, so a restart resumes the epoch
+                    completed_thresholds = bam_writer_service
+                        .get_completed_thresholds(current_epoch)
+                        .await?
+                        .into_iter()
+                        .collect();
+                    if !completed_thresholds.is_empty() {
+                        info!(
+                            "Resuming epoch {current_epoch}, {} threshold(s) already processed",
+                            completed_thresholds.len()
+                        );
+                    }
                     last_processed_epoch = Some(current_epoch);
                 }
 
-                for (idx, &threshold) in args.epoch_progress_thresholds.iter().enumerate() {
-                    if progress >= threshold && !thresholds_hit.contains(&idx) {
-                        let threshold_pct = threshold * 100.0;
-
-                        info!("Reached {threshold_pct:.0}% threshold for epoch {current_epoch}");
-
-                        match bam_writer_service.run().await {
-                            Ok(()) => {
-                                info!(
-                                    "Successfully processed at {threshold_pct:.0}% of epoch {current_epoch}"
-                                );
-                                thresholds_hit.insert(idx);
-
-                                datapoint_info!(
-                                    "bam-writer-service-stats",
-                                    ("epoch", current_epoch, i64),
-                                    ("threshold_pct", threshold_pct as i64, i64),
-                                    ("success", 1, i64),
-                                    "cluster" => args.cluster_name,
-                                );
-                            }
-                            Err(e) => {
-                                error!(
-                                    "Error processing at {threshold_pct:.0}% of epoch {current_epoch}: {e}"
-                                );
-
-                                datapoint_info!(
-                                    "bam-writer-service-stats",
-                                    ("epoch", current_epoch, i64),
-                                    ("success", 0, i64),
-                                    "cluster" => args.cluster_name,
-                                );
-                            }
+                // Thresholds passed while the service was down are covered by the latest one
+                let pending = run_schedule.pending(progress, &completed_thresholds);
+                if let Some((scheduled_run, skipped)) = pending.split_last() {
+                    let threshold_pct = scheduled_run.threshold_bps as f64 / 100.0;
+
+                    info!("Reached {threshold_pct:.0}% threshold for epoch {current_epoch}");
+                    for skipped_run in skipped {
+                        info!(
+                            "Skipping {:.0}% threshold of epoch {current_epoch}, covered by the {threshold_pct:.0}% run",
+                            skipped_run.threshold_bps as f64 / 100.0
+                        );
+                    }
+
+                    match bam_writer_service.run(current_epoch, scheduled_run).await {
+                        Ok(()) => {
+                            info!(
+                                "Successfully processed at {threshold_pct:.0}% of epoch {current_epoch}"
+                            );
+                            completed_thresholds.insert(scheduled_run.threshold_bps);
+
+                            datapoint_info!(
+                                "bam-writer-service-stats",
+                                ("epoch", current_epoch, i64),
+                                ("threshold_pct", threshold_pct as i64, i64),
+                                ("snapshot", scheduled_run.snapshot as i64, i64),
+                                ("final", scheduled_run.is_final, bool),
+                                ("success", 1, i64),
+                                "cluster" => args.cluster_name,
+                            );
+                        }
+                        Err(e) => {
+                            error!(
+                                "Error processing at {threshold_pct:.0}% of epoch {current_epoch}: {e}"
+                            );
+
+                            datapoint_info!(
+                                "bam-writer-service-stats",
+                                ("epoch", current_epoch, i64),
+                                ("success", 0, i64),
+                                "cluster" => args.cluster_name,
+                            );
                         }
                     }
                 }
@@ -0,0 +1,158 @@
+//! Runs of the BAM writer within an epoch.
+//!
+//! The writer runs once per epoch progress threshold. Runs are numbered in threshold order and
+//! the run at the highest threshold is the final one of the epoch. Thresholds are identified in
+//! BPS, so the threshold progress read back from Mongo compares exactly.
+//!
+//! A run covers every threshold up to its own: thresholds passed while the writer was down are
+//! not run once a later threshold ran, and only the latest of them runs on restart.
+
+use std::collections::HashSet;
+
+use anyhow::anyhow;
+
+/// Scheduled run of the BAM writer within an epoch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScheduledRun {
+    /// Number of the run within the epoch, starting at 1
+    pub snapshot: u32,
+
+    /// Epoch progress threshold in BPS
+    pub threshold_bps: u64,
+
+    /// Whether this is the last run of the epoch
+    pub is_final: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct RunSchedule {
+    /// Runs sorted by threshold
+    runs: Vec<ScheduledRun>,
+}
+
+impl RunSchedule {
+    /// Build the [`RunSchedule`] of epoch progress thresholds (0.0-1.0]
+    pub fn new(thresholds: &[f64]) -> anyhow::Result<Self> {
+        if thresholds.is_empty() {
+            return Err(anyhow!("At least one epoch progress threshold is required"));
+        }
+
+        let mut thresholds_bps = thresholds
+            .iter()
+            .map(|&threshold| {
+                if threshold > 0.0 && threshold <= 1.0 {
+                    Ok((threshold * 10_000.0).round() as u64)
+                } else {
+                    Err(anyhow!(
+                        "Epoch progress threshold {threshold} must be in (0.0, 1.0]"
+                    ))
+                }
+            })
+            .collect::<anyhow::Result<Vec<u64>>>()?;
+        thresholds_bps.sort_unstable();
+        thresholds_bps.dedup();
+
+        let run_count = thresholds_bps.len();
+        let runs = thresholds_bps
+            .into_iter()
+            .enumerate()
+            .map(|(idx, threshold_bps)| ScheduledRun {
+                snapshot: idx as u32 + 1,
+                threshold_bps,
+                is_final: idx + 1 == run_count,
+            })
+            .collect();
+
+        Ok(Self { runs })
+    }
+
+    /// Runs reached at `progress` (0.0-1.0) and not covered by `completed_thresholds_bps`, in
+    /// threshold order
+    ///
+    /// Only the last one needs to run, the earlier ones are covered by it.
+    pub fn pending(
+        &self,
+        progress: f64,
+        completed_thresholds_bps: &HashSet<u64>,
+    ) -> Vec<ScheduledRun> {
+        let progress_bps = (progress * 10_000.0).floor() as u64;
+        let covered_bps = completed_thresholds_bps.iter().max().copied();
+
+        self.runs
+            .iter()
+            .filter(|run| run.threshold_bps <= progress_bps)
+            .filter(|run| covered_bps.is_none_or(|covered_bps| run.threshold_bps > covered_bps))
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runs_numbered_in_threshold_order() {
+        let schedule = RunSchedule::new(&[0.9, 0.5, 0.75, 0.5]).unwrap();
+
+        assert_eq!(
+            schedule.runs,
+            vec![
+                ScheduledRun {
+                    snapshot: 1,
+                    threshold_bps: 5_000,
+                    is_final: false,
+                },
+                ScheduledRun {
+                    snapshot: 2,
+                    threshold_bps: 7_500,
+                    is_final: false,
+                },
+                ScheduledRun {
+                    snapshot: 3,
+                    threshold_bps: 9_000,
+                    is_final: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_thresholds() {
+        assert!(RunSchedule::new(&[]).is_err());
+        assert!(RunSchedule::new(&[0.0]).is_err());
+        assert!(RunSchedule::new(&[0.5, 1.5]).is_err());
+    }
+
+    #[test]
+    fn test_pending_skips_completed_thresholds() {
+        let schedule = RunSchedule::new(&[0.5, 0.75, 0.9]).unwrap();
+
+        assert!(schedule.pending(0.4, &HashSet::new()).is_empty());
+
+        // Restarted at 80% after the 50% run
+        let pending = schedule.pending(0.8, &HashSet::from([5_000]));
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].threshold_bps, 7_500);
+
+        let pending = schedule.pending(1.0, &HashSet::from([5_000, 7_500]));
+        assert_eq!(pending.len(), 1);
+        assert!(pending[0].is_final);
+    }
+
+    #[test]
+    fn test_pending_skips_covered_thresholds() {
+        let schedule = RunSchedule::new(&[0.5, 0.75, 0.9]).unwrap();
+
+        // Restarted at 95% without any run, only the final run is needed
+        let pending = schedule.pending(0.95, &HashSet::new());
+        assert_eq!(pending.len(), 3);
+        assert!(pending.last().unwrap().is_final);
+
+        // The 75% run covers the skipped 50% threshold
+        assert!(schedule.pending(0.8, &HashSet::from([7_500])).is_empty());
+        let pending = schedule.pending(0.95, &HashSet::from([7_500]));
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].threshold_bps, 9_000);
+    }
+}
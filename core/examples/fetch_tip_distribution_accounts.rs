@@ -1,5 +1,6 @@
 use clap::Parser;
 use kobe_core::{
+    cluster_config::ClusterConfig,
     fetcher::fetch_tip_distribution_accounts,
     validators_app::{Cluster, ValidatorsAppResponseEntry},
 };
@@ -13,10 +14,14 @@ struct Args {
     #[arg(long)]
     rpc_url: String,
 
-    /// Solana cluster (e.g. mainnet, testnet, devnet)
+    /// Solana cluster (e.g. mainnet, testnet, devnet, localnet)
     #[arg(long)]
     cluster: String,
 
+    /// Cluster config file, defaults to the embedded profiles
+    #[arg(long)]
+    cluster_config: Option<std::path::PathBuf>,
+
     /// Epoch number
     #[arg(long)]
     epoch: u64,
@@ -31,14 +36,16 @@ async fn main() {
     let args = Args::parse();
 
     let cluster = Cluster::get_cluster(&args.cluster).unwrap();
+    let cluster_config = ClusterConfig::load(args.cluster_config.as_deref(), cluster).unwrap();
     let rpc_client = RpcClient::new(args.rpc_url);
 
     let mut entry = ValidatorsAppResponseEntry::default();
     entry.vote_account = args.vote_account;
 
-    let accounts = fetch_tip_distribution_accounts(&[entry], &rpc_client, &cluster, args.epoch)
-        .await
-        .unwrap();
+    let accounts =
+        fetch_tip_distribution_accounts(&[entry], &rpc_client, &cluster_config, args.epoch)
+            .await
+            .unwrap();
 
     println!("Accounts: {}", accounts.len());
 }
@@ -0,0 +1,230 @@
+//! Versioned BAM eligibility rules.
+//!
+//! The lookback windows and thresholds of [`BamValidatorEligibility`] are read from a TOML file so
+//! governance changes don't need a code change. The default file ([`DEFAULT_ELIGIBILITY_RULES`])
+//! is embedded in kobe-core.
+//!
+//! [`BamValidatorEligibility`]: crate::bam_validator_eligibility::BamValidatorEligibility
+
+use std::{fs, path::Path};
+
+use jito_steward::constants::BASIS_POINTS_MAX;
+use serde::{Deserialize, Serialize};
+use solana_program::hash::hash;
+
+use crate::error::KobeCoreError;
+
+/// JIP-28 rules shipped with kobe-core
+pub const DEFAULT_ELIGIBILITY_RULES: &str = include_str!("../config/bam_eligibility_rules.toml");
+
+/// Lookback windows and thresholds of the BAM eligibility criteria
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct BamEligibilityRules {
+    /// Version of the rules, bumped with every governance change
+    pub version: String,
+
+    /// Epochs over which the inflation commission is checked
+    pub commission_lookback_epochs: u16,
+
+    /// Epochs over which the MEV commission is checked
+    pub mev_commission_lookback_epochs: u16,
+
+    /// Epochs over which the validator must have run the BAM client
+    pub running_bam_lookback_epochs: u16,
+
+    /// Epochs over which the validator must have been outside the superminority
+    pub superminority_lookback_epochs: u16,
+
+    /// Epochs over which the vote credits are checked
+    pub vote_credits_lookback_epochs: u16,
+
+    /// Epochs of the commission lookback with recorded history required to be eligible
+    pub min_history_epochs: u16,
+
+    /// Highest inflation commission (%) allowed
+    pub max_commission: u8,
+
+    /// Highest MEV commission (bps) allowed
+    pub max_mev_commission_bps: u16,
+
+    /// Lowest share of the cluster's maximum vote credits required in each epoch
+    pub min_vote_credits_ratio: f64,
+
+    /// SHA-256 hash of the rules file, not serialized with the rules
+    #[serde(skip)]
+    hash: String,
+}
+
+impl BamEligibilityRules {
+    /// Load the rules from `path`, or the embedded [`DEFAULT_ELIGIBILITY_RULES`] when not given
+    pub fn load(path: Option<&Path>) -> Result<Self, KobeCoreError> {
+        match path {
+            Some(path) => {
+                let contents = fs::read_to_string(path).map_err(|e| {
+                    KobeCoreError::InvalidBamEligibilityRules(format!("{}: {e}", path.display()))
+                })?;
+                Self::from_toml_str(&contents)
+            }
+            None => Self::from_toml_str(DEFAULT_ELIGIBILITY_RULES),
+        }
+    }
+
+    /// Parse and validate the rules of a TOML document
+    pub fn from_toml_str(contents: &str) -> Result<Self, KobeCoreError> {
+        let mut rules: Self = toml::from_str(contents)
+            .map_err(|e| KobeCoreError::InvalidBamEligibilityRules(e.to_string()))?;
+        rules.validate()?;
+        rules.hash = hash(contents.as_bytes()).to_string();
+
+        Ok(rules)
+    }
+
+    /// SHA-256 hash of the rules file, base58 encoded
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    fn validate(&self) -> Result<(), KobeCoreError> {
+        if self.version.trim().is_empty() {
+            return Err(KobeCoreError::InvalidBamEligibilityRules(
+                "version must not be empty".to_string(),
+            ));
+        }
+
+        for (name, lookback) in [
+            (
+                "commission_lookback_epochs",
+                self.commission_lookback_epochs,
+            ),
+            (
+                "mev_commission_lookback_epochs",
+                self.mev_commission_lookback_epochs,
+            ),
+            (
+                "running_bam_lookback_epochs",
+                self.running_bam_lookback_epochs,
+            ),
+            (
+                "superminority_lookback_epochs",
+                self.superminority_lookback_epochs,
+            ),
+            (
+                "vote_credits_lookback_epochs",
+                self.vote_credits_lookback_epochs,
+            ),
+        ] {
+            if lookback == 0 {
+                return Err(KobeCoreError::InvalidBamEligibilityRules(format!(
+                    "{name} must be at least 1"
+                )));
+            }
+        }
+
+        if self.min_history_epochs > self.commission_lookback_epochs {
+            return Err(KobeCoreError::InvalidBamEligibilityRules(
+                "min_history_epochs must not exceed commission_lookback_epochs".to_string(),
+            ));
+        }
+        if self.max_commission > 100 {
+            return Err(KobeCoreError::InvalidBamEligibilityRules(
+                "max_commission must be at most 100".to_string(),
+            ));
+        }
+        if self.max_mev_commission_bps as u64 > BASIS_POINTS_MAX as u64 {
+            return Err(KobeCoreError::InvalidBamEligibilityRules(format!(
+                "max_mev_commission_bps must be at most {BASIS_POINTS_MAX}"
+            )));
+        }
+        if !(self.min_vote_credits_ratio > 0.0 && self.min_vote_credits_ratio <= 1.0) {
+            return Err(KobeCoreError::InvalidBamEligibilityRules(
+                "min_vote_credits_ratio must be in (0, 1]".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_rules_match_jip_28() {
+        let rules = BamEligibilityRules::load(None).unwrap();
+
+        assert_eq!(rules.version, "jip-28");
+        assert_eq!(rules.commission_lookback_epochs, 30);
+        assert_eq!(rules.mev_commission_lookback_epochs, 10);
+        assert_eq!(rules.running_bam_lookback_epochs, 3);
+        assert_eq!(rules.superminority_lookback_epochs, 3);
+        assert_eq!(rules.vote_credits_lookback_epochs, 3);
+        assert_eq!(rules.min_history_epochs, 3);
+        assert_eq!(rules.max_commission, 0);
+        assert_eq!(rules.max_mev_commission_bps, 1000);
+        assert_eq!(rules.min_vote_credits_ratio, 0.97);
+        assert!(!rules.hash().is_empty());
+    }
+
+    #[test]
+    fn test_hash_changes_with_rules() {
+        let rules = BamEligibilityRules::from_toml_str(DEFAULT_ELIGIBILITY_RULES).unwrap();
+        let contents = DEFAULT_ELIGIBILITY_RULES.replace(
+            "max_mev_commission_bps = 1000",
+            "max_mev_commission_bps = 800",
+        );
+        let changed = BamEligibilityRules::from_toml_str(&contents).unwrap();
+
+        assert_eq!(changed.max_mev_commission_bps, 800);
+        assert_ne!(rules.hash(), changed.hash());
+    }
+
+    #[test]
+    fn test_rules_round_trip_through_bson() {
+        let rules = BamEligibilityRules::load(None).unwrap();
+
+        let document = mongodb::bson::to_document(&rules).unwrap();
+        assert!(!document.contains_key("hash"));
+
+        let stored: BamEligibilityRules = mongodb::bson::from_document(document).unwrap();
+        assert_eq!(
+            stored,
+            BamEligibilityRules {
+                hash: String::new(),
+                ..rules
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        for (from, to) in [
+            ("version = \"jip-28\"", "version = \"\""),
+            (
+                "running_bam_lookback_epochs = 3",
+                "running_bam_lookback_epochs = 0",
+            ),
+            ("min_history_epochs = 3", "min_history_epochs = 31"),
+            ("max_commission = 0", "max_commission = 101"),
+            (
+                "max_mev_commission_bps = 1000",
+                "max_mev_commission_bps = 10001",
+            ),
+            (
+                "min_vote_credits_ratio = 0.97",
+                "min_vote_credits_ratio = 1.5",
+            ),
+            (
+                "min_vote_credits_ratio = 0.97",
+                "min_vote_credits_ratio = 0.97\nunknown_rule = 1",
+            ),
+        ] {
+            let contents = DEFAULT_ELIGIBILITY_RULES.replace(from, to);
+            assert!(
+                BamEligibilityRules::from_toml_str(&contents).is_err(),
+                "{to} should be rejected"
+            );
+        }
+    }
+}
@@ -0,0 +1,1049 @@
+//! Validator eligibility validation for BAM delegation (JIP-28)
+
+use std::collections::HashMap;
+
+use jito_steward::{constants::BASIS_POINTS_MAX, Config};
+use serde::{Deserialize, Serialize};
+use solana_pubkey::Pubkey;
+use validator_history::ValidatorHistory;
+
+use crate::{
+    bam_eligibility_rules::BamEligibilityRules,
+    client_type::ClientType,
+    db_models::bam_validators::{BamIneligibilityKind, BamIneligibilityReason},
+};
+
+/// BAM eligibility criterion
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BamEligibilityCriterion {
+    History,
+    BamClient,
+    Commission,
+    MevCommission,
+    Superminority,
+    VoteCredits,
+    OnChainBlacklist,
+    OffChainBlacklist,
+}
+
+/// Status of an eligibility criterion for a validator
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BamCriterionStatus {
+    pub criterion: BamEligibilityCriterion,
+    pub passed: bool,
+
+    /// Epochs of the lookback meeting the criterion, epochs of history for `History`, `None` for
+    /// blacklists
+    pub epochs_met: Option<u64>,
+
+    /// Epochs of the lookback, minimum epochs of history for `History`, `None` for blacklists
+    pub epochs_required: Option<u64>,
+
+    /// Why the criterion failed, `None` when it passed
+    pub reason: Option<BamIneligibilityReason>,
+
+    /// Earliest epoch the criterion can pass if the validator meets it from now on, `None` when
+    /// it passed or for blacklists
+    pub eligible_from_epoch: Option<u64>,
+}
+
+impl BamCriterionStatus {
+    /// Status of a criterion checked over each epoch of a lookback, failing when any epoch with
+    /// data fails
+    fn from_epochs(
+        criterion: BamEligibilityCriterion,
+        lookback_epochs: u16,
+        epochs_met: usize,
+        reason: Option<BamIneligibilityReason>,
+    ) -> Self {
+        let eligible_from_epoch = reason
+            .as_ref()
+            .and_then(|reason| reason.epoch)
+            .map(|epoch| epoch + lookback_epochs as u64 + 1);
+
+        Self {
+            criterion,
+            passed: reason.is_none(),
+            epochs_met: Some(epochs_met as u64),
+            epochs_required: Some(lookback_epochs as u64),
+            reason,
+            eligible_from_epoch,
+        }
+    }
+
+    fn from_blacklist(
+        criterion: BamEligibilityCriterion,
+        kind: BamIneligibilityKind,
+        listed: bool,
+    ) -> Self {
+        Self {
+            criterion,
+            passed: !listed,
+            epochs_met: None,
+            epochs_required: None,
+            reason: listed.then(|| BamIneligibilityReason::new(kind)),
+            eligible_from_epoch: None,
+        }
+    }
+}
+
+impl std::fmt::Display for BamCriterionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let check = match self.criterion {
+            BamEligibilityCriterion::History => "history",
+            BamEligibilityCriterion::BamClient => "running BAM",
+            BamEligibilityCriterion::Commission => "commission at or under the maximum",
+            BamEligibilityCriterion::MevCommission => "MEV commission at or under the maximum",
+            BamEligibilityCriterion::Superminority => "outside the superminority",
+            BamEligibilityCriterion::VoteCredits => "vote credits at or over the minimum",
+            BamEligibilityCriterion::OnChainBlacklist => {
+                return match self.passed {
+                    true => write!(f, "not on the on-chain blacklist"),
+                    false => write!(f, "on the on-chain blacklist"),
+                };
+            }
+            BamEligibilityCriterion::OffChainBlacklist => {
+                return match self.passed {
+                    true => write!(f, "not on the BAM delegation blacklist"),
+                    false => write!(f, "on the BAM delegation blacklist"),
+                };
+            }
+        };
+
+        write!(
+            f,
+            "{check} for {} of {} epochs",
+            self.epochs_met.unwrap_or_default(),
+            self.epochs_required.unwrap_or_default()
+        )
+    }
+}
+
+/// Validates validator eligibility for BAM delegation according to JIP-28 criteria
+#[derive(Debug)]
+pub struct BamValidatorEligibility {
+    /// Epoch eligibility is checked for, the lookback windows end the epoch before
+    current_epoch: u64,
+
+    /// Start epoch for lookback window
+    validator_commission_start_epoch: u16,
+
+    /// End epoch for lookback window
+    validator_commission_end_epoch: u16,
+
+    /// MEV Commission start epoch
+    mev_commission_start_epoch: u16,
+
+    /// MEV Commission end epoch
+    mev_commission_end_epoch: u16,
+
+    /// Running bam start epoch
+    running_bam_start_epoch: u16,
+
+    /// Running bam end epoch
+    running_bam_end_epoch: u16,
+
+    /// Superminority start epoch
+    superminority_start_epoch: u16,
+
+    /// Superminority end epoch
+    superminority_end_epoch: u16,
+
+    /// Epoch credits start epoch
+    epoch_credits_start_epoch: u16,
+
+    /// Epoch credits end epoch
+    epoch_credits_end_epoch: u16,
+
+    /// Chain maximum vote credits per epoch
+    chain_max_credits: HashMap<u16, u32>,
+
+    /// Epochs of history required in the commission lookback
+    min_history_epochs: usize,
+
+    /// Highest inflation commission (%) allowed
+    max_commission: u8,
+
+    /// Highest MEV commission (bps) allowed
+    max_mev_commission_bps: u16,
+
+    /// Lowest share of the chain maximum vote credits required
+    min_vote_credits_ratio: f64,
+}
+
+impl BamValidatorEligibility {
+    /// Create a new eligibility checker
+    ///
+    /// # BAM Eligibility Components
+    ///
+    /// | Component | Description |
+    /// |-----------|-------------|
+    /// | `bam_blacklist_component` | Is the validator on the BAM blacklist (includes on-chain blacklist)? Binary component. |
+    /// | `validator_commission_component` | Has the validator kept its inflation commission at or under `max_commission` the last `commission_lookback_epochs` epochs? Binary component. |
+    /// | `mev_commission_component` | Has the validator kept its MEV commission at or under `max_mev_commission_bps` the last `mev_commission_lookback_epochs` epochs? Binary component. |
+    /// | `running_bam_component` | Has the validator been running the BAM client for the last `running_bam_lookback_epochs` epochs? Binary component. |
+    /// | `superminority_component` | Has the validator been outside of the superminority for the last `superminority_lookback_epochs` epochs? Binary component. |
+    /// | `voting_rate_component` | Has the validator earned at least `min_vote_credits_ratio` of the chain maximum vote credits the last `vote_credits_lookback_epochs` epochs? Binary component. |
+    ///
+    /// Windows and thresholds come from `rules`, JIP-28 values by default.
+    pub fn new(
+        current_epoch: u64,
+        all_validator_histories: &[ValidatorHistory],
+        rules: &BamEligibilityRules,
+    ) -> Self {
+        let end_epoch = current_epoch.saturating_sub(1) as u16;
+        let start_epoch =
+            |lookback_epochs: u16| current_epoch.saturating_sub(lookback_epochs as u64) as u16;
+
+        // Validator Commission
+        let validator_commission_start_epoch = start_epoch(rules.commission_lookback_epochs);
+        let validator_commission_end_epoch = end_epoch;
+
+        // MEV Commission
+        let mev_commission_start_epoch = start_epoch(rules.mev_commission_lookback_epochs);
+        let mev_commission_end_epoch = end_epoch;
+
+        // Running bam
+        let running_bam_start_epoch = start_epoch(rules.running_bam_lookback_epochs);
+        let running_bam_end_epoch = end_epoch;
+
+        // Superminority
+        let superminority_start_epoch = start_epoch(rules.superminority_lookback_epochs);
+        let superminority_end_epoch = end_epoch;
+
+        // Epoch credits
+        let epoch_credits_start_epoch = start_epoch(rules.vote_credits_lookback_epochs);
+        let epoch_credits_end_epoch = end_epoch;
+
+        // Calculate chain maximum vote credits for each epoch
+        let chain_max_credits = Self::calculate_chain_max_credits(
+            all_validator_histories,
+            epoch_credits_start_epoch,
+            epoch_credits_end_epoch,
+        );
+
+        Self {
+            current_epoch,
+            validator_commission_start_epoch,
+            validator_commission_end_epoch,
+            mev_commission_start_epoch,
+            mev_commission_end_epoch,
+            running_bam_start_epoch,
+            running_bam_end_epoch,
+            superminority_start_epoch,
+            superminority_end_epoch,
+            epoch_credits_start_epoch,
+            epoch_credits_end_epoch,
+            chain_max_credits,
+            min_history_epochs: rules.min_history_epochs as usize,
+            max_commission: rules.max_commission,
+            max_mev_commission_bps: rules.max_mev_commission_bps,
+            min_vote_credits_ratio: rules.min_vote_credits_ratio,
+        }
+    }
+
+    /// Calculate the maximum vote credits earned by any validator in each epoch
+    fn calculate_chain_max_credits(
+        all_histories: &[ValidatorHistory],
+        start_epoch: u16,
+        end_epoch: u16,
+    ) -> HashMap<u16, u32> {
+        let mut max_credits = HashMap::new();
+
+        for epoch in start_epoch..=end_epoch {
+            let max = all_histories
+                .iter()
+                .filter_map(|vh| {
+                    vh.history
+                        .epoch_credits_range(epoch, epoch)
+                        .into_iter()
+                        .flatten()
+                        .next()
+                })
+                .max()
+                .unwrap_or(0);
+
+            max_credits.insert(epoch, max);
+        }
+
+        max_credits
+    }
+
+    /// Check if a validator is eligible for BAM delegation
+    ///
+    /// Every criterion is evaluated. Returns `Ok(())` if eligible, or `Err` with a reason per
+    /// failed criterion, recording the latest epoch it failed in.
+    pub fn check_eligibility(
+        &self,
+        blacklist_validators: &[Pubkey],
+        steward_config: &Config,
+        validator_history: &ValidatorHistory,
+    ) -> Result<(), Vec<BamIneligibilityReason>> {
+        let reasons: Vec<BamIneligibilityReason> = self
+            .evaluate(blacklist_validators, steward_config, validator_history)
+            .into_iter()
+            .filter_map(|status| status.reason)
+            .collect();
+
+        if reasons.is_empty() {
+            Ok(())
+        } else {
+            Err(reasons)
+        }
+    }
+
+    /// Earliest epoch a validator with `statuses` can become eligible if it meets every
+    /// criterion from now on, `None` while blacklisted
+    pub fn eligible_from_epoch(&self, statuses: &[BamCriterionStatus]) -> Option<u64> {
+        statuses.iter().filter(|status| !status.passed).try_fold(
+            self.current_epoch,
+            |eligible_from_epoch, status| {
+                Some(eligible_from_epoch.max(status.eligible_from_epoch?))
+            },
+        )
+    }
+
+    /// Delegation score of a validator, from 1 to 100
+    ///
+    /// Product of its average share of the chain maximum vote credits over the vote credits
+    /// lookback, its uptime (share of the commission lookback epochs it earned vote credits in)
+    /// and one minus its average MEV commission over the MEV commission lookback.
+    pub fn score(&self, validator_history: &ValidatorHistory) -> u8 {
+        let vote_credit_ratios: Vec<f64> =
+            (self.epoch_credits_start_epoch..=self.epoch_credits_end_epoch)
+                .zip(validator_history.history.epoch_credits_range(
+                    self.epoch_credits_start_epoch,
+                    self.epoch_credits_end_epoch,
+                ))
+                .filter_map(|(epoch, credits)| {
+                    let max_credits = *self.chain_max_credits.get(&epoch)?;
+                    let credits = credits?;
+                    (max_credits > 0).then(|| (credits as f64 / max_credits as f64).min(1.))
+                })
+                .collect();
+        let vote_credit_ratio = mean(&vote_credit_ratios).unwrap_or(0.);
+
+        let uptime_epochs = (self.validator_commission_end_epoch
+            - self.validator_commission_start_epoch
+            + 1) as f64;
+        let uptime = validator_history
+            .history
+            .epoch_credits_range(
+                self.validator_commission_start_epoch,
+                self.validator_commission_end_epoch,
+            )
+            .iter()
+            .filter(|credits| matches!(credits, Some(credits) if *credits > 0))
+            .count() as f64
+            / uptime_epochs;
+
+        let mev_commissions: Vec<f64> = validator_history
+            .history
+            .mev_commission_range(
+                self.mev_commission_start_epoch,
+                self.mev_commission_end_epoch,
+            )
+            .into_iter()
+            .flatten()
+            .map(|mev_commission| mev_commission as f64 / BASIS_POINTS_MAX as f64)
+            .collect();
+        let mev_commission = mean(&mev_commissions).unwrap_or(0.).min(1.);
+
+        (100. * vote_credit_ratio * uptime * (1. - mev_commission))
+            .round()
+            .clamp(1., 100.) as u8
+    }
+
+    /// Evaluate every eligibility criterion for a validator
+    pub fn evaluate(
+        &self,
+        blacklist_validators: &[Pubkey],
+        steward_config: &Config,
+        validator_history: &ValidatorHistory,
+    ) -> Vec<BamCriterionStatus> {
+        let client_types = validator_history
+            .history
+            .client_type_range(self.running_bam_start_epoch, self.running_bam_end_epoch);
+        let commissions = validator_history.history.commission_range(
+            self.validator_commission_start_epoch,
+            self.validator_commission_end_epoch,
+        );
+        let mev_commissions = validator_history.history.mev_commission_range(
+            self.mev_commission_start_epoch,
+            self.mev_commission_end_epoch,
+        );
+        let superminority = validator_history
+            .history
+            .superminority_range(self.superminority_start_epoch, self.superminority_end_epoch);
+        let epoch_credits = validator_history
+            .history
+            .epoch_credits_range(self.epoch_credits_start_epoch, self.epoch_credits_end_epoch);
+
+        let mut statuses = Vec::new();
+
+        // Count how many epochs have data
+        let epochs_with_data = commissions.iter().filter(|c| c.is_some()).count();
+
+        // Must have history for `min_history_epochs` epochs (continuous operation requirement)
+        let missing_epochs = self.min_history_epochs.saturating_sub(epochs_with_data);
+        statuses.push(BamCriterionStatus {
+            criterion: BamEligibilityCriterion::History,
+            passed: missing_epochs == 0,
+            epochs_met: Some(epochs_with_data as u64),
+            epochs_required: Some(self.min_history_epochs as u64),
+            reason: (missing_epochs > 0).then(|| {
+                BamIneligibilityReason::new(BamIneligibilityKind::InsufficientHistory)
+                    .with_values(epochs_with_data as u64, self.min_history_epochs as u64)
+            }),
+            eligible_from_epoch: (missing_epochs > 0)
+                .then_some(self.current_epoch + missing_epochs as u64),
+        });
+
+        // BAM clients
+        let client_types: Vec<(u16, u8)> = (self.running_bam_start_epoch
+            ..=self.running_bam_end_epoch)
+            .zip(client_types)
+            .filter_map(|(epoch, client_type)| Some((epoch, client_type?)))
+            .collect();
+        let is_bam = |client_type: u8| matches!(ClientType::from_u8(client_type), ClientType::Bam);
+        let not_bam_client = client_types
+            .iter()
+            .filter(|(_, client_type)| !is_bam(*client_type))
+            .last();
+        statuses.push(BamCriterionStatus::from_epochs(
+            BamEligibilityCriterion::BamClient,
+            self.running_bam_end_epoch - self.running_bam_start_epoch + 1,
+            client_types.iter().filter(|(_, c)| is_bam(*c)).count(),
+            not_bam_client.map(|(epoch, _)| {
+                BamIneligibilityReason::new(BamIneligibilityKind::NotBamClient)
+                    .with_epoch(*epoch as u64)
+            }),
+        ));
+
+        // Inflation commission at or under the maximum
+        let commissions: Vec<(u16, u8)> = (self.validator_commission_start_epoch
+            ..=self.validator_commission_end_epoch)
+            .zip(commissions)
+            .filter_map(|(epoch, commission)| Some((epoch, commission?)))
+            .collect();
+        let high_commission = commissions
+            .iter()
+            .filter(|(_, commission)| *commission > self.max_commission)
+            .last();
+        statuses.push(BamCriterionStatus::from_epochs(
+            BamEligibilityCriterion::Commission,
+            self.validator_commission_end_epoch - self.validator_commission_start_epoch + 1,
+            commissions
+                .iter()
+                .filter(|(_, c)| *c <= self.max_commission)
+                .count(),
+            high_commission.map(|(epoch, commission)| {
+                BamIneligibilityReason::new(BamIneligibilityKind::HighCommission)
+                    .with_epoch(*epoch as u64)
+                    .with_values(*commission as u64, self.max_commission as u64)
+            }),
+        ));
+
+        // MEV commission at or under the maximum
+        let mev_commissions: Vec<(u16, u16)> = (self.mev_commission_start_epoch
+            ..=self.mev_commission_end_epoch)
+            .zip(mev_commissions)
+            .filter_map(|(epoch, mev_commission)| Some((epoch, mev_commission?)))
+            .collect();
+        let high_mev_commission = mev_commissions
+            .iter()
+            .filter(|(_, mev_commission)| *mev_commission > self.max_mev_commission_bps)
+            .last();
+        statuses.push(BamCriterionStatus::from_epochs(
+            BamEligibilityCriterion::MevCommission,
+            self.mev_commission_end_epoch - self.mev_commission_start_epoch + 1,
+            mev_commissions
+                .iter()
+                .filter(|(_, c)| *c <= self.max_mev_commission_bps)
+                .count(),
+            high_mev_commission.map(|(epoch, mev_commission)| {
+                BamIneligibilityReason::new(BamIneligibilityKind::HighMevCommission)
+                    .with_epoch(*epoch as u64)
+                    .with_values(*mev_commission as u64, self.max_mev_commission_bps as u64)
+            }),
+        ));
+
+        // Non-superminority
+        let superminority: Vec<(u16, u8)> = (self.superminority_start_epoch
+            ..=self.superminority_end_epoch)
+            .zip(superminority)
+            .filter_map(|(epoch, is_superminority)| Some((epoch, is_superminority?)))
+            .collect();
+        let in_superminority = superminority
+            .iter()
+            .filter(|(_, is_superminority)| *is_superminority != 0)
+            .last();
+        statuses.push(BamCriterionStatus::from_epochs(
+            BamEligibilityCriterion::Superminority,
+            self.superminority_end_epoch - self.superminority_start_epoch + 1,
+            superminority.iter().filter(|(_, s)| *s == 0).count(),
+            in_superminority.map(|(epoch, _)| {
+                BamIneligibilityReason::new(BamIneligibilityKind::InSuperminority)
+                    .with_epoch(*epoch as u64)
+            }),
+        ));
+
+        // Minimum share of chain maximum vote credits
+        let epoch_credits: Vec<(u16, u32, u32)> = (self.epoch_credits_start_epoch
+            ..=self.epoch_credits_end_epoch)
+            .zip(epoch_credits)
+            .filter_map(|(epoch, credits)| {
+                let max_credits = *self.chain_max_credits.get(&epoch)?;
+                let min_required = (max_credits as f64 * self.min_vote_credits_ratio) as u32;
+                Some((epoch, credits?, min_required))
+            })
+            .collect();
+        let low_vote_credits = epoch_credits
+            .iter()
+            .filter(|(_, credits, min_required)| credits < min_required)
+            .last();
+        statuses.push(BamCriterionStatus::from_epochs(
+            BamEligibilityCriterion::VoteCredits,
+            self.epoch_credits_end_epoch - self.epoch_credits_start_epoch + 1,
+            epoch_credits
+                .iter()
+                .filter(|(_, credits, min_required)| credits >= min_required)
+                .count(),
+            low_vote_credits.map(|(epoch, credits, min_required)| {
+                BamIneligibilityReason::new(BamIneligibilityKind::LowVoteCredits)
+                    .with_epoch(*epoch as u64)
+                    .with_values(*credits as u64, *min_required as u64)
+            }),
+        ));
+
+        statuses.push(BamCriterionStatus::from_blacklist(
+            BamEligibilityCriterion::OnChainBlacklist,
+            BamIneligibilityKind::OnChainBlacklist,
+            matches!(
+                steward_config
+                    .validator_history_blacklist
+                    .get(validator_history.index as usize),
+                Ok(true)
+            ),
+        ));
+
+        statuses.push(BamCriterionStatus::from_blacklist(
+            BamEligibilityCriterion::OffChainBlacklist,
+            BamIneligibilityKind::OffChainBlacklist,
+            blacklist_validators.contains(&validator_history.vote_account),
+        ));
+
+        statuses
+    }
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::bam_eligibility_rules::BamEligibilityRules;
+    use jito_steward::{utils::U8Bool, LargeBitMask, Parameters};
+    use solana_pubkey::Pubkey;
+    use validator_history::{CircBuf, ValidatorHistory, ValidatorHistoryEntry};
+
+    fn create_steward_config() -> Config {
+        Config {
+            stake_pool: Pubkey::new_unique(),
+            validator_list: Pubkey::new_unique(),
+            admin: Pubkey::new_unique(),
+            parameters_authority: Pubkey::new_unique(),
+            blacklist_authority: Pubkey::new_unique(),
+            validator_history_blacklist: LargeBitMask::default(),
+            parameters: Parameters::default(),
+            paused: U8Bool::from(false),
+            _padding_0: [0; 7],
+            priority_fee_parameters_authority: Pubkey::new_unique(),
+            directed_stake_meta_upload_authority: Pubkey::new_unique(),
+            directed_stake_whitelist_authority: Pubkey::new_unique(),
+            directed_stake_ticket_override_authority: Pubkey::new_unique(),
+            _padding: [0; 888],
+        }
+    }
+
+    fn rules() -> BamEligibilityRules {
+        BamEligibilityRules::load(None).unwrap()
+    }
+
+    // Helper to create a mock validator history entry
+    fn create_entry(
+        epoch: u16,
+        client_type: u8,
+        commission: u8,
+        mev_commission: u16,
+        is_superminority: u8,
+        epoch_credits: u32,
+    ) -> ValidatorHistoryEntry {
+        ValidatorHistoryEntry {
+            epoch,
+            commission,
+            mev_commission,
+            is_superminority,
+            epoch_credits,
+            client_type,
+            ..Default::default()
+        }
+    }
+
+    // Helper to create a validator history with entries
+    fn create_validator_history(entries: Vec<ValidatorHistoryEntry>) -> ValidatorHistory {
+        let mut history = ValidatorHistory {
+            struct_version: 1,
+            vote_account: Pubkey::from_str("CcaHc2L43ZWjwCHART3oZoJvHLAe9hzT2DJNUpBzoTN2").unwrap(),
+            index: 0,
+            bump: 0,
+            _padding0: [0; 7],
+            last_ip_timestamp: 0,
+            last_version_timestamp: 0,
+            validator_age: 0,
+            validator_age_last_updated_epoch: 0,
+            _padding1: [0; 226],
+            history: CircBuf::default(),
+        };
+
+        for entry in entries {
+            history.history.arr[entry.epoch as usize % history.history.arr.len()] = entry;
+        }
+        history
+    }
+
+    #[test]
+    fn test_eligible_validator_passes() {
+        let blacklist_validators = vec![];
+        let steward_config = create_steward_config();
+
+        let mut entries = Vec::new();
+        for i in 0..=29 {
+            let entry = create_entry(i, 6, 0, 1000, 0, 10000);
+            entries.push(entry);
+        }
+        let vh1 = create_validator_history(entries);
+
+        let checker = BamValidatorEligibility::new(30, &[vh1.clone()], &rules());
+
+        assert!(checker
+            .check_eligibility(&blacklist_validators, &steward_config, &vh1)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_not_bam_client_fails() {
+        let blacklist_validators = vec![];
+        let steward_config = create_steward_config();
+        let vh = create_validator_history(vec![
+            create_entry(97, 2, 0, 10, 0, 10000), // Firedancer
+            create_entry(98, 6, 5, 10, 0, 10000),
+            create_entry(99, 6, 0, 10, 0, 10000),
+            create_entry(100, 6, 0, 10, 0, 10000),
+        ]);
+
+        let checker = BamValidatorEligibility::new(100, &[vh.clone()], &rules());
+
+        assert_eq!(
+            checker.check_eligibility(&blacklist_validators, &steward_config, &vh),
+            Err(vec![
+                BamIneligibilityReason::new(BamIneligibilityKind::NotBamClient).with_epoch(97),
+                BamIneligibilityReason::new(BamIneligibilityKind::HighCommission)
+                    .with_epoch(98)
+                    .with_values(5, 0),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_non_zero_commission_fails() {
+        let blacklist_validators = vec![];
+        let steward_config = create_steward_config();
+
+        let mut entries = Vec::new();
+        for i in 0..=29 {
+            let entry = create_entry(i, 6, 0, 10, 0, 10000);
+            entries.push(entry);
+        }
+        entries.push(create_entry(30, 6, 5, 10, 0, 10000)); // 5% commission
+
+        let vh = create_validator_history(entries);
+
+        let checker = BamValidatorEligibility::new(31, &[vh.clone()], &rules());
+
+        assert_eq!(
+            checker.check_eligibility(&blacklist_validators, &steward_config, &vh),
+            Err(vec![BamIneligibilityReason::new(
+                BamIneligibilityKind::HighCommission
+            )
+            .with_epoch(30)
+            .with_values(5, 0)])
+        );
+    }
+
+    #[test]
+    fn test_high_mev_commission_fails() {
+        let blacklist_validators = vec![];
+        let steward_config = create_steward_config();
+
+        let mut entries = Vec::new();
+        for i in 0..=29 {
+            let entry = create_entry(i, 6, 0, 1000, 0, 10000);
+            entries.push(entry);
+        }
+        entries.push(create_entry(30, 6, 0, 1500, 0, 10000)); // 15% MEV commission
+
+        let vh = create_validator_history(entries);
+
+        let checker = BamValidatorEligibility::new(31, &[vh.clone()], &rules());
+
+        assert_eq!(
+            checker.check_eligibility(&blacklist_validators, &steward_config, &vh),
+            Err(vec![BamIneligibilityReason::new(
+                BamIneligibilityKind::HighMevCommission
+            )
+            .with_epoch(30)
+            .with_values(1500, 1000)])
+        );
+    }
+
+    #[test]
+    fn test_superminority_fails() {
+        let blacklist_validators = vec![];
+        let steward_config = create_steward_config();
+        let vh = create_validator_history(vec![
+            create_entry(97, 6, 0, 10, 0, 10000),
+            create_entry(98, 6, 0, 10, 1, 10000), // In superminority
+            create_entry(99, 6, 0, 10, 0, 10000),
+            create_entry(100, 6, 0, 10, 0, 10000),
+        ]);
+
+        let checker = BamValidatorEligibility::new(100, &[vh.clone()], &rules());
+
+        assert_eq!(
+            checker.check_eligibility(&blacklist_validators, &steward_config, &vh),
+            Err(vec![BamIneligibilityReason::new(
+                BamIneligibilityKind::InSuperminority
+            )
+            .with_epoch(98)])
+        );
+    }
+
+    #[test]
+    fn test_low_vote_credits_fails() {
+        let blacklist_validators = vec![];
+        let steward_config = create_steward_config();
+        let vh_good = create_validator_history(vec![
+            create_entry(97, 6, 0, 10, 0, 10000),
+            create_entry(98, 6, 0, 10, 0, 10000),
+            create_entry(99, 6, 0, 10, 0, 10000),
+            create_entry(100, 6, 0, 10, 0, 10000),
+        ]);
+
+        let vh_bad = create_validator_history(vec![
+            create_entry(97, 6, 0, 10, 0, 10000),
+            create_entry(98, 6, 0, 10, 0, 9600), // 96% of max (below 97%)
+            create_entry(99, 6, 0, 10, 0, 10000),
+            create_entry(100, 6, 0, 10, 0, 10000),
+        ]);
+
+        let checker = BamValidatorEligibility::new(100, &[vh_good, vh_bad.clone()], &rules());
+
+        assert_eq!(
+            checker.check_eligibility(&blacklist_validators, &steward_config, &vh_bad),
+            Err(vec![BamIneligibilityReason::new(
+                BamIneligibilityKind::LowVoteCredits
+            )
+            .with_epoch(98)
+            .with_values(9600, 9700)]) // 97% of 10000
+        );
+    }
+
+    #[test]
+    fn test_insufficient_history_fails() {
+        let blacklist_validators = vec![];
+        let steward_config = create_steward_config();
+        // Only 2 epochs instead of required 3
+        let vh = create_validator_history(vec![
+            create_entry(99, 6, 0, 10, 0, 10000),
+            create_entry(100, 6, 0, 10, 0, 10000),
+        ]);
+
+        let checker = BamValidatorEligibility::new(100, &[vh.clone()], &rules());
+
+        assert_eq!(
+            checker.check_eligibility(&blacklist_validators, &steward_config, &vh),
+            Err(vec![BamIneligibilityReason::new(
+                BamIneligibilityKind::InsufficientHistory
+            )
+            .with_values(1, 3)])
+        );
+    }
+
+    #[test]
+    fn test_exactly_97_percent_passes() {
+        let blacklist_validators = vec![];
+        let steward_config = create_steward_config();
+        let vh_max = create_validator_history(vec![
+            create_entry(97, 6, 0, 10, 0, 10000),
+            create_entry(98, 6, 0, 10, 0, 10000),
+            create_entry(99, 6, 0, 10, 0, 10000),
+            create_entry(100, 6, 0, 10, 0, 10000),
+        ]);
+
+        let vh_97 = create_validator_history(vec![
+            create_entry(97, 6, 0, 10, 0, 9700), // Exactly 97%
+            create_entry(98, 6, 0, 10, 0, 9700),
+            create_entry(99, 6, 0, 10, 0, 9700),
+            create_entry(100, 6, 0, 10, 0, 9700),
+        ]);
+
+        let checker = BamValidatorEligibility::new(100, &[vh_max, vh_97.clone()], &rules());
+
+        assert!(checker
+            .check_eligibility(&blacklist_validators, &steward_config, &vh_97)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_mev_commission_boundary() {
+        let blacklist_validators = vec![];
+        let steward_config = create_steward_config();
+        // Exactly 10% MEV commission should pass
+        let vh_10 = create_validator_history(vec![
+            create_entry(97, 6, 0, 1000, 0, 10000),
+            create_entry(98, 6, 0, 1000, 0, 10000),
+            create_entry(99, 6, 0, 1000, 0, 10000),
+            create_entry(100, 6, 0, 1000, 0, 10000),
+        ]);
+
+        // 11% should fail
+        let vh_11 = create_validator_history(vec![
+            create_entry(97, 6, 0, 1100, 0, 10000),
+            create_entry(98, 6, 0, 1100, 0, 10000),
+            create_entry(99, 6, 0, 1100, 0, 10000),
+            create_entry(100, 6, 0, 1100, 0, 10000),
+        ]);
+
+        let checker = BamValidatorEligibility::new(100, &[vh_10.clone(), vh_11.clone()], &rules());
+
+        assert!(checker
+            .check_eligibility(&blacklist_validators, &steward_config, &vh_10)
+            .is_ok());
+        assert!(checker
+            .check_eligibility(&blacklist_validators, &steward_config, &vh_11)
+            .is_err());
+    }
+
+    #[test]
+    fn test_on_chain_blacklist() {
+        let blacklist_validators = vec![];
+        let mut steward_config = create_steward_config();
+        steward_config
+            .validator_history_blacklist
+            .set(0, true)
+            .unwrap();
+
+        let vh = create_validator_history(vec![
+            create_entry(97, 6, 0, 10, 0, 10000),
+            create_entry(98, 6, 0, 10, 0, 10000),
+            create_entry(99, 6, 0, 10, 0, 10000),
+            create_entry(100, 6, 0, 10, 0, 10000),
+        ]);
+
+        let checker = BamValidatorEligibility::new(100, &[vh.clone()], &rules());
+
+        assert_eq!(
+            checker.check_eligibility(&blacklist_validators, &steward_config, &vh),
+            Err(vec![BamIneligibilityReason::new(
+                BamIneligibilityKind::OnChainBlacklist
+            )])
+        );
+    }
+
+    #[test]
+    fn test_off_chain_blacklist() {
+        let blacklist_validators =
+            vec![Pubkey::from_str("CcaHc2L43ZWjwCHART3oZoJvHLAe9hzT2DJNUpBzoTN2").unwrap()];
+        let steward_config = create_steward_config();
+
+        let vh = create_validator_history(vec![
+            create_entry(97, 6, 0, 10, 0, 10000),
+            create_entry(98, 6, 0, 10, 0, 10000),
+            create_entry(99, 6, 0, 10, 0, 10000),
+            create_entry(100, 6, 0, 10, 0, 10000),
+        ]);
+
+        let checker = BamValidatorEligibility::new(100, &[vh.clone()], &rules());
+
+        assert_eq!(
+            checker.check_eligibility(&blacklist_validators, &steward_config, &vh),
+            Err(vec![BamIneligibilityReason::new(
+                BamIneligibilityKind::OffChainBlacklist
+            )])
+        );
+    }
+
+    #[test]
+    fn test_rules_thresholds_applied() {
+        let blacklist_validators = vec![];
+        let steward_config = create_steward_config();
+        let vh = create_validator_history(vec![
+            create_entry(97, 6, 5, 1000, 0, 10000),
+            create_entry(98, 6, 5, 1000, 0, 10000),
+            create_entry(99, 6, 5, 1000, 0, 10000),
+            create_entry(100, 6, 5, 1000, 0, 10000),
+        ]);
+
+        let jip_28 = BamValidatorEligibility::new(100, &[vh.clone()], &rules());
+        assert_eq!(
+            jip_28.check_eligibility(&blacklist_validators, &steward_config, &vh),
+            Err(vec![BamIneligibilityReason::new(
+                BamIneligibilityKind::HighCommission
+            )
+            .with_epoch(99)
+            .with_values(5, 0)])
+        );
+
+        let mut relaxed_rules = rules();
+        relaxed_rules.max_commission = 5;
+        relaxed_rules.max_mev_commission_bps = 800;
+        let relaxed = BamValidatorEligibility::new(100, &[vh.clone()], &relaxed_rules);
+        assert_eq!(
+            relaxed.check_eligibility(&blacklist_validators, &steward_config, &vh),
+            Err(vec![BamIneligibilityReason::new(
+                BamIneligibilityKind::HighMevCommission
+            )
+            .with_epoch(99)
+            .with_values(1000, 800)])
+        );
+    }
+
+    #[test]
+    fn test_every_reason_reported() {
+        let blacklist_validators =
+            vec![Pubkey::from_str("CcaHc2L43ZWjwCHART3oZoJvHLAe9hzT2DJNUpBzoTN2").unwrap()];
+        let steward_config = create_steward_config();
+        let vh = create_validator_history(vec![
+            create_entry(97, 6, 10, 1000, 0, 10000),
+            create_entry(98, 6, 8, 2000, 1, 10000),
+            create_entry(99, 2, 5, 1500, 0, 10000),
+            create_entry(100, 6, 0, 10, 0, 10000),
+        ]);
+
+        let checker = BamValidatorEligibility::new(100, &[vh.clone()], &rules());
+
+        assert_eq!(
+            checker.check_eligibility(&blacklist_validators, &steward_config, &vh),
+            Err(vec![
+                BamIneligibilityReason::new(BamIneligibilityKind::NotBamClient).with_epoch(99),
+                BamIneligibilityReason::new(BamIneligibilityKind::HighCommission)
+                    .with_epoch(99)
+                    .with_values(5, 0),
+                BamIneligibilityReason::new(BamIneligibilityKind::HighMevCommission)
+                    .with_epoch(99)
+                    .with_values(1500, 1000),
+                BamIneligibilityReason::new(BamIneligibilityKind::InSuperminority).with_epoch(98),
+                BamIneligibilityReason::new(BamIneligibilityKind::OffChainBlacklist),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_evaluate_reports_progress() {
+        let blacklist_validators = vec![];
+        let steward_config = create_steward_config();
+        // Switched to BAM in epoch 100, MEV commission lowered in epoch 99
+        let vh = create_validator_history(vec![
+            create_entry(97, 3, 0, 1500, 0, 10000),
+            create_entry(98, 3, 0, 1000, 0, 10000),
+            create_entry(99, 3, 0, 1000, 0, 10000),
+            create_entry(100, 6, 0, 1000, 0, 10000),
+        ]);
+
+        // Checked for epoch 101, the lookbacks ending with epoch 100
+        let checker = BamValidatorEligibility::new(101, &[vh.clone()], &rules());
+        let statuses = checker.evaluate(&blacklist_validators, &steward_config, &vh);
+
+        let bam_client = statuses
+            .iter()
+            .find(|status| status.criterion == BamEligibilityCriterion::BamClient)
+            .unwrap();
+        assert!(!bam_client.passed);
+        assert_eq!(bam_client.epochs_met, Some(1));
+        assert_eq!(bam_client.epochs_required, Some(3));
+        assert_eq!(bam_client.eligible_from_epoch, Some(103));
+        assert_eq!(bam_client.to_string(), "running BAM for 1 of 3 epochs");
+
+        let mev_commission = statuses
+            .iter()
+            .find(|status| status.criterion == BamEligibilityCriterion::MevCommission)
+            .unwrap();
+        assert!(!mev_commission.passed);
+        assert_eq!(mev_commission.epochs_met, Some(3));
+        assert_eq!(mev_commission.eligible_from_epoch, Some(108));
+
+        assert!(statuses
+            .iter()
+            .filter(|status| !matches!(
+                status.criterion,
+                BamEligibilityCriterion::BamClient | BamEligibilityCriterion::MevCommission
+            ))
+            .all(|status| status.passed));
+        assert_eq!(checker.eligible_from_epoch(&statuses), Some(108));
+    }
+
+    #[test]
+    fn test_blacklisted_has_no_eligible_epoch() {
+        let blacklist_validators =
+            vec![Pubkey::from_str("CcaHc2L43ZWjwCHART3oZoJvHLAe9hzT2DJNUpBzoTN2").unwrap()];
+        let steward_config = create_steward_config();
+        let vh = create_validator_history(vec![
+            create_entry(97, 6, 0, 10, 0, 10000),
+            create_entry(98, 6, 0, 10, 0, 10000),
+            create_entry(99, 6, 0, 10, 0, 10000),
+        ]);
+
+        let checker = BamValidatorEligibility::new(100, &[vh.clone()], &rules());
+        let statuses = checker.evaluate(&blacklist_validators, &steward_config, &vh);
+        assert_eq!(checker.eligible_from_epoch(&statuses), None);
+
+        let statuses = checker.evaluate(&[], &steward_config, &vh);
+        assert_eq!(checker.eligible_from_epoch(&statuses), Some(100));
+    }
+
+    #[test]
+    fn test_score() {
+        // Earning every chain maximum vote credit with no MEV commission over the whole lookback
+        let full = create_validator_history(
+            (70..100)
+                .map(|i| create_entry(i, 6, 0, 0, 0, 10000))
+                .collect(),
+        );
+        let checker = BamValidatorEligibility::new(100, &[full.clone()], &rules());
+        assert_eq!(checker.score(&full), 100);
+
+        // Three epochs of uptime out of thirty, 10% MEV commission
+        let recent = create_validator_history(vec![
+            create_entry(97, 6, 0, 1000, 0, 10000),
+            create_entry(98, 6, 0, 1000, 0, 10000),
+            create_entry(99, 6, 0, 1000, 0, 10000),
+        ]);
+        let checker = BamValidatorEligibility::new(100, &[recent.clone()], &rules());
+        assert_eq!(checker.score(&recent), 9);
+
+        // Half of the chain maximum vote credits
+        let half = create_validator_history(
+            (70..100)
+                .map(|i| create_entry(i, 6, 0, 0, 0, 5000))
+                .collect(),
+        );
+        let checker = BamValidatorEligibility::new(100, &[full, half.clone()], &rules());
+        assert_eq!(checker.score(&half), 50);
+    }
+}
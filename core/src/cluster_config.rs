@@ -0,0 +1,242 @@
+//! Typed cluster profiles.
+//!
+//! Program IDs and account addresses for each cluster are declared in a TOML file, one table per
+//! profile. The default file ([`DEFAULT_CLUSTER_CONFIG`]) is embedded in the binaries, so a
+//! `--cluster-config` file is only needed to override addresses, e.g. for a local test validator.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use solana_pubkey::Pubkey;
+
+use crate::{error::KobeCoreError, validators_app::Cluster};
+
+/// Profiles shipped with Kobe. Mainnet-beta, testnet and devnet are complete; localnet only sets
+/// the stake pool program, so services other than the cranker need a `--cluster-config` file with
+/// the addresses of the local setup.
+pub const DEFAULT_CLUSTER_CONFIG: &str = include_str!("../config/clusters.toml");
+
+/// Program IDs and account addresses for a cluster
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ClusterConfig {
+    /// Cluster this profile targets
+    #[serde_as(as = "DisplayFromStr")]
+    pub cluster: Cluster,
+
+    /// SPL stake pool program
+    #[serde_as(as = "DisplayFromStr")]
+    pub stake_pool_program_id: Pubkey,
+
+    /// JitoSOL stake pool
+    #[serde_as(as = "DisplayFromStr")]
+    pub stake_pool_address: Pubkey,
+
+    /// JitoSOL validator list, read from the stake pool account when not set
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub validator_list_address: Option<Pubkey>,
+
+    /// JitoSOL mint
+    #[serde_as(as = "DisplayFromStr")]
+    pub jitosol_mint: Pubkey,
+
+    /// Steward program
+    #[serde_as(as = "DisplayFromStr")]
+    pub steward_program_id: Pubkey,
+
+    /// Steward config account
+    #[serde_as(as = "DisplayFromStr")]
+    pub steward_config: Pubkey,
+
+    /// Validator history program
+    #[serde_as(as = "DisplayFromStr")]
+    pub validator_history_program_id: Pubkey,
+
+    /// Tip distribution program
+    #[serde_as(as = "DisplayFromStr")]
+    pub tip_distribution_program_id: Pubkey,
+
+    /// Priority fee distribution program
+    #[serde_as(as = "DisplayFromStr")]
+    pub priority_fee_distribution_program_id: Pubkey,
+
+    /// Jito BAM Boost program
+    #[serde_as(as = "DisplayFromStr")]
+    pub bam_boost_program_id: Pubkey,
+}
+
+/// Stake pool program of a profile, the only address the cranker needs
+#[serde_as]
+#[derive(Deserialize)]
+struct StakePoolProgramProfile {
+    #[serde_as(as = "DisplayFromStr")]
+    cluster: Cluster,
+
+    #[serde_as(as = "DisplayFromStr")]
+    stake_pool_program_id: Pubkey,
+}
+
+impl ClusterConfig {
+    /// Load the profile for `cluster`
+    ///
+    /// Reads `path` when given, otherwise the embedded [`DEFAULT_CLUSTER_CONFIG`].
+    pub fn load(path: Option<&Path>, cluster: Cluster) -> Result<Self, KobeCoreError> {
+        Self::from_toml_str(&read_cluster_config(path)?, cluster)
+    }
+
+    /// Parse the profile for `cluster` out of a TOML document
+    ///
+    /// Other profiles are not validated, so an incomplete profile only fails when it is selected.
+    pub fn from_toml_str(contents: &str, cluster: Cluster) -> Result<Self, KobeCoreError> {
+        let config: ClusterConfig = parse_profile(contents, cluster)?;
+        check_profile_cluster(config.cluster, cluster)?;
+
+        Ok(config)
+    }
+
+    /// Load only the stake pool program of the profile for `cluster`
+    ///
+    /// The other addresses of the profile may be left unset, e.g. in a localnet profile used by
+    /// the cranker.
+    pub fn load_stake_pool_program_id(
+        path: Option<&Path>,
+        cluster: Cluster,
+    ) -> Result<Pubkey, KobeCoreError> {
+        let profile: StakePoolProgramProfile = parse_profile(&read_cluster_config(path)?, cluster)?;
+        check_profile_cluster(profile.cluster, cluster)?;
+
+        Ok(profile.stake_pool_program_id)
+    }
+}
+
+/// Read `path`, or the embedded [`DEFAULT_CLUSTER_CONFIG`] when not given
+fn read_cluster_config(path: Option<&Path>) -> Result<String, KobeCoreError> {
+    match path {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|e| KobeCoreError::InvalidClusterConfig(format!("{}: {e}", path.display()))),
+        None => Ok(DEFAULT_CLUSTER_CONFIG.to_string()),
+    }
+}
+
+/// Deserialize the profile for `cluster` out of a TOML document
+fn parse_profile<T: DeserializeOwned>(
+    contents: &str,
+    cluster: Cluster,
+) -> Result<T, KobeCoreError> {
+    let mut profiles: HashMap<String, toml::Value> =
+        toml::from_str(contents).map_err(|e| KobeCoreError::InvalidClusterConfig(e.to_string()))?;
+
+    let profile_name = cluster.to_string();
+    let profile = profiles
+        .remove(&profile_name)
+        .ok_or_else(|| KobeCoreError::MissingClusterProfile(profile_name.clone()))?;
+
+    profile
+        .try_into()
+        .map_err(|e| KobeCoreError::InvalidClusterConfig(format!("profile '{profile_name}': {e}")))
+}
+
+/// Reject a profile that declares another cluster than the one it is selected for
+fn check_profile_cluster(declared: Cluster, cluster: Cluster) -> Result<(), KobeCoreError> {
+    if declared != cluster {
+        return Err(KobeCoreError::InvalidClusterConfig(format!(
+            "profile '{cluster}' declares cluster '{declared}'"
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_default_profiles_load() {
+        for cluster in [Cluster::MainnetBeta, Cluster::Testnet, Cluster::Devnet] {
+            let config = ClusterConfig::load(None, cluster).unwrap();
+            assert_eq!(config.cluster, cluster);
+        }
+    }
+
+    #[test]
+    fn test_default_stake_pool_program_ids_load() {
+        for (cluster, stake_pool_program_id) in [
+            (Cluster::MainnetBeta, spl_stake_pool::id()),
+            (Cluster::Testnet, spl_stake_pool::id()),
+            (Cluster::Devnet, spl_stake_pool::devnet::id()),
+            (Cluster::Localnet, spl_stake_pool::id()),
+        ] {
+            assert_eq!(
+                ClusterConfig::load_stake_pool_program_id(None, cluster).unwrap(),
+                stake_pool_program_id
+            );
+        }
+    }
+
+    #[test]
+    fn test_incomplete_localnet_profile() {
+        assert!(matches!(
+            ClusterConfig::load(None, Cluster::Localnet),
+            Err(KobeCoreError::InvalidClusterConfig(message)) if message.contains("missing field")
+        ));
+    }
+
+    #[test]
+    fn test_custom_localnet_profile() {
+        let mainnet = DEFAULT_CLUSTER_CONFIG
+            .split("[testnet]")
+            .next()
+            .unwrap()
+            .replace(
+                "[mainnet-beta]\ncluster = \"mainnet-beta\"",
+                "[localnet]\ncluster = \"localnet\"",
+            );
+        let config = ClusterConfig::from_toml_str(&mainnet, Cluster::Localnet).unwrap();
+
+        assert_eq!(config.cluster, Cluster::Localnet);
+        assert_eq!(config.steward_program_id, jito_steward::id());
+    }
+
+    #[test]
+    fn test_mainnet_profile_matches_deployments() {
+        let config = ClusterConfig::load(None, Cluster::MainnetBeta).unwrap();
+
+        assert_eq!(config.stake_pool_program_id, spl_stake_pool::id());
+        assert_eq!(config.steward_program_id, jito_steward::id());
+        assert_eq!(config.validator_history_program_id, validator_history::id());
+        assert_eq!(
+            config.validator_list_address,
+            Some(Pubkey::from_str("3R3nGZpQs2aZo5FDQvd2MUQ6R7KhAPainds6uT6uE2mn").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_missing_profile() {
+        let contents = DEFAULT_CLUSTER_CONFIG.replace("[localnet]", "[other]");
+        let result = ClusterConfig::from_toml_str(&contents, Cluster::Localnet);
+
+        assert!(matches!(
+            result,
+            Err(KobeCoreError::MissingClusterProfile(name)) if name == "localnet"
+        ));
+    }
+
+    #[test]
+    fn test_profile_cluster_mismatch() {
+        let contents = DEFAULT_CLUSTER_CONFIG.replace(
+            "[mainnet-beta]\ncluster = \"mainnet-beta\"",
+            "[mainnet-beta]\ncluster = \"testnet\"",
+        );
+        let result = ClusterConfig::from_toml_str(&contents, Cluster::MainnetBeta);
+
+        assert!(matches!(
+            result,
+            Err(KobeCoreError::InvalidClusterConfig(_))
+        ));
+    }
+}
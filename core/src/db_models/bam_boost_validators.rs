@@ -1,10 +1,13 @@
 use std::time::Instant;
 
 use futures::TryStreamExt;
-use mongodb::{bson::doc, options::ReplaceOptions, Collection};
+use mongodb::{bson::doc, options::ReplaceOptions, Collection, IndexModel};
 use serde::{Deserialize, Serialize};
 
-use crate::{constants::BAM_BOOST_VALIDATORS_COLLECTION_NAME, db_models::error::DataStoreError};
+use crate::{
+    constants::BAM_BOOST_VALIDATORS_COLLECTION_NAME,
+    db_models::{error::DataStoreError, unique_index},
+};
 
 #[derive(Clone, Serialize, Deserialize, Default, Debug, PartialOrd, PartialEq)]
 pub struct BamBoostValidator {
@@ -39,6 +42,14 @@ impl BamBoostValidatorsStore {
         Self { collection }
     }
 
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![unique_index(
+            "epoch_identity_account",
+            doc! {"epoch": 1, "identity_account": 1},
+        )]
+    }
+
     /// Upsert a [`BamBoostValidator`] record
     pub async fn upsert(&self, items: &[BamBoostValidator]) -> Result<(), mongodb::error::Error> {
         let start = Instant::now();
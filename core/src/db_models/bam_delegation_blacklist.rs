@@ -1,10 +1,11 @@
 //! DB model for a BAM Delegation Blacklist.
 
+use chrono::{serde::ts_seconds_option, DateTime, Utc};
 use futures::TryStreamExt;
-use mongodb::Collection;
+use mongodb::{bson::doc, Collection, IndexModel};
 use serde::{Deserialize, Serialize};
 
-use crate::db_models::error::DataStoreError;
+use crate::db_models::{error::DataStoreError, index, unique_index};
 
 #[derive(Clone, Default, Debug, PartialOrd, PartialEq, Deserialize, Serialize)]
 pub struct BamDelegationBlacklistEntry {
@@ -13,6 +14,95 @@ pub struct BamDelegationBlacklistEntry {
 
     /// The epoch number added to blacklist
     added_epoch: u64,
+
+    /// Why the validator was blacklisted, `None` for entries added before reasons were recorded
+    #[serde(default)]
+    reason: Option<String>,
+
+    /// Who added the entry
+    #[serde(default)]
+    added_by: Option<String>,
+
+    /// When the entry was added
+    #[serde(default, with = "ts_seconds_option")]
+    added_at: Option<DateTime<Utc>>,
+
+    /// When the entry stops applying, `None` for entries that never expire
+    #[serde(default, with = "ts_seconds_option")]
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl BamDelegationBlacklistEntry {
+    pub fn new(vote_account: &str, added_epoch: u64) -> Self {
+        Self {
+            vote_account: vote_account.to_string(),
+            added_epoch,
+            reason: None,
+            added_by: None,
+            added_at: Some(Utc::now()),
+            expires_at: None,
+        }
+    }
+
+    pub fn with_reason(mut self, reason: &str) -> Self {
+        self.reason = Some(reason.to_string());
+        self
+    }
+
+    pub fn with_added_by(mut self, added_by: &str) -> Self {
+        self.added_by = Some(added_by.to_string());
+        self
+    }
+
+    pub fn with_expiry(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Get vote account pubkey
+    pub fn get_vote_account(&self) -> &str {
+        &self.vote_account
+    }
+
+    /// Get the epoch the validator was blacklisted in
+    pub fn get_added_epoch(&self) -> u64 {
+        self.added_epoch
+    }
+
+    /// Get why the validator was blacklisted
+    pub fn get_reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    /// Get who added the entry
+    pub fn get_added_by(&self) -> Option<&str> {
+        self.added_by.as_deref()
+    }
+
+    /// Get when the entry was added
+    pub fn get_added_at(&self) -> Option<DateTime<Utc>> {
+        self.added_at
+    }
+
+    /// Get when the entry stops applying
+    pub fn get_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    /// Set why the validator was blacklisted
+    pub fn set_reason(&mut self, reason: Option<String>) {
+        self.reason = reason;
+    }
+
+    /// Set when the entry stops applying
+    pub fn set_expires_at(&mut self, expires_at: Option<DateTime<Utc>>) {
+        self.expires_at = expires_at;
+    }
+
+    /// Whether the entry stopped applying at `now`
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+    }
 }
 
 #[derive(Clone)]
@@ -28,7 +118,16 @@ impl BamDelegationBlacklistStore {
         Self { collection }
     }
 
-    /// Insert a [`BamDelegationBlacklistEntry`]
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            unique_index("vote_account", doc! {"vote_account": 1}),
+            index(doc! {"expires_at": 1}),
+        ]
+    }
+
+    /// Insert a [`BamDelegationBlacklistEntry`], failing with a duplicate key error when its vote
+    /// account is already blacklisted
     pub async fn insert(
         &self,
         entry: BamDelegationBlacklistEntry,
@@ -37,6 +136,39 @@ impl BamDelegationBlacklistStore {
         Ok(())
     }
 
+    /// Find the [`BamDelegationBlacklistEntry`] of a vote account
+    pub async fn find_by_vote_account(
+        &self,
+        vote_account: &str,
+    ) -> Result<Option<BamDelegationBlacklistEntry>, mongodb::error::Error> {
+        self.collection
+            .find_one(doc! {"vote_account": vote_account}, None)
+            .await
+    }
+
+    /// Replace the [`BamDelegationBlacklistEntry`] of its vote account, returns whether an entry
+    /// was replaced
+    pub async fn replace(
+        &self,
+        entry: &BamDelegationBlacklistEntry,
+    ) -> Result<bool, mongodb::error::Error> {
+        let result = self
+            .collection
+            .replace_one(doc! {"vote_account": &entry.vote_account}, entry, None)
+            .await?;
+        Ok(result.matched_count > 0)
+    }
+
+    /// Delete the [`BamDelegationBlacklistEntry`] records of a vote account, returns the number
+    /// of deleted entries
+    pub async fn delete(&self, vote_account: &str) -> Result<u64, mongodb::error::Error> {
+        let result = self
+            .collection
+            .delete_many(doc! {"vote_account": vote_account}, None)
+            .await?;
+        Ok(result.deleted_count)
+    }
+
     /// Find [`BamDelegationBlacklistEntry`] records
     pub async fn find(&self) -> Result<Vec<BamDelegationBlacklistEntry>, DataStoreError> {
         let cursor = self.collection.find(None, None).await?;
@@ -44,4 +176,51 @@ impl BamDelegationBlacklistStore {
 
         Ok(entries)
     }
+
+    /// Find the [`BamDelegationBlacklistEntry`] records not expired at `now`
+    pub async fn find_active(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<BamDelegationBlacklistEntry>, DataStoreError> {
+        let filter = doc! {
+            "$or": [
+                {"expires_at": null},
+                {"expires_at": {"$gt": now.timestamp()}},
+            ]
+        };
+        let cursor = self.collection.find(filter, None).await?;
+        let entries: Vec<BamDelegationBlacklistEntry> = cursor.try_collect().await?;
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use mongodb::bson;
+
+    use super::*;
+
+    #[test]
+    fn test_expiry() {
+        let now = Utc::now();
+        let entry = BamDelegationBlacklistEntry::new("vote", 800).with_reason("sandwiching");
+        assert!(!entry.is_expired(now));
+
+        let entry = entry.with_expiry(now + Duration::days(1));
+        assert!(!entry.is_expired(now));
+        assert!(entry.is_expired(now + Duration::days(1)));
+    }
+
+    #[test]
+    fn test_legacy_entry_deserializes() {
+        let entry: BamDelegationBlacklistEntry =
+            bson::from_document(doc! {"vote_account": "vote", "added_epoch": 800_i64}).unwrap();
+
+        assert_eq!(entry.get_vote_account(), "vote");
+        assert_eq!(entry.get_reason(), None);
+        assert_eq!(entry.get_added_at(), None);
+        assert!(!entry.is_expired(Utc::now()));
+    }
 }
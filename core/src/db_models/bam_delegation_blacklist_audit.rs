@@ -0,0 +1,200 @@
+//! DB model for the audit log of the BAM Delegation Blacklist.
+//!
+//! Every change made to the blacklist through the admin API is recorded here, before the change is
+//! applied. The log is append-only: a change that fails to apply is followed by a
+//! [`BamDelegationBlacklistAction::Failed`] entry pointing at it.
+
+use chrono::{serde::ts_seconds, DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId},
+    options::FindOptions,
+    Collection, IndexModel,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db_models::{bam_delegation_blacklist::BamDelegationBlacklistEntry, index};
+
+/// Change made to the BAM delegation blacklist
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BamDelegationBlacklistAction {
+    Add,
+    Update,
+    Remove,
+
+    /// The change of the entry referenced by `failed_change` did not apply
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BamDelegationBlacklistAuditEntry {
+    /// Id of the record
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+
+    /// Vote account address
+    pub vote_account: String,
+
+    /// Change made
+    pub action: BamDelegationBlacklistAction,
+
+    /// Admin pubkey that made the change
+    pub actor: String,
+
+    /// Entry before the change, `None` when adding
+    pub before: Option<BamDelegationBlacklistEntry>,
+
+    /// Entry after the change, `None` when removing
+    pub after: Option<BamDelegationBlacklistEntry>,
+
+    /// Id of the record of the change that failed to apply, set on `Failed` records
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed_change: Option<ObjectId>,
+
+    /// Timestamp
+    #[serde(with = "ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+}
+
+impl BamDelegationBlacklistAuditEntry {
+    pub fn new(
+        vote_account: &str,
+        action: BamDelegationBlacklistAction,
+        actor: &str,
+        before: Option<BamDelegationBlacklistEntry>,
+        after: Option<BamDelegationBlacklistEntry>,
+    ) -> Self {
+        Self {
+            id: ObjectId::new(),
+            vote_account: vote_account.to_string(),
+            action,
+            actor: actor.to_string(),
+            before,
+            after,
+            failed_change: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Record that the change recorded by `change` failed to apply
+    ///
+    /// The blacklist is left as it was before the change, so `before` and `after` both hold the
+    /// entry before the change.
+    pub fn failed(change: &Self) -> Self {
+        Self {
+            failed_change: Some(change.id),
+            ..Self::new(
+                &change.vote_account,
+                BamDelegationBlacklistAction::Failed,
+                &change.actor,
+                change.before.clone(),
+                change.before.clone(),
+            )
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct BamDelegationBlacklistAuditStore {
+    collection: Collection<BamDelegationBlacklistAuditEntry>,
+}
+
+impl BamDelegationBlacklistAuditStore {
+    pub const COLLECTION: &'static str = "bam_delegation_blacklist_audit_log";
+
+    /// Initialize a [`BamDelegationBlacklistAuditStore`]
+    pub fn new(collection: Collection<BamDelegationBlacklistAuditEntry>) -> Self {
+        Self { collection }
+    }
+
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            index(doc! {"timestamp": -1}),
+            index(doc! {"vote_account": 1, "timestamp": -1}),
+        ]
+    }
+
+    /// Insert a [`BamDelegationBlacklistAuditEntry`]
+    pub async fn insert(
+        &self,
+        entry: &BamDelegationBlacklistAuditEntry,
+    ) -> Result<(), mongodb::error::Error> {
+        self.collection.insert_one(entry, None).await?;
+        Ok(())
+    }
+
+    /// Find the latest [`BamDelegationBlacklistAuditEntry`] records, of one vote account when
+    /// `vote_account` is set
+    pub async fn find(
+        &self,
+        vote_account: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<BamDelegationBlacklistAuditEntry>, mongodb::error::Error> {
+        let filter = vote_account.map(|vote_account| doc! {"vote_account": vote_account});
+        let options = FindOptions::builder()
+            .sort(doc! {"timestamp": -1})
+            .limit(limit)
+            .build();
+        let cursor = self.collection.find(filter, options).await?;
+
+        cursor.try_collect().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson;
+
+    use super::*;
+
+    #[test]
+    fn test_audit_entry_roundtrip() {
+        let before = BamDelegationBlacklistEntry::new("vote", 800).with_reason("sandwiching");
+        let entry = BamDelegationBlacklistAuditEntry::new(
+            "vote",
+            BamDelegationBlacklistAction::Remove,
+            "admin",
+            Some(before),
+            None,
+        );
+
+        let document = bson::to_document(&entry).unwrap();
+        assert_eq!(document.get_str("action").unwrap(), "remove");
+
+        let decoded: BamDelegationBlacklistAuditEntry = bson::from_document(document).unwrap();
+        let decoded_before = decoded.before.unwrap();
+        assert_eq!(decoded_before.get_vote_account(), "vote");
+        assert_eq!(decoded_before.get_reason(), Some("sandwiching"));
+        assert_eq!(decoded.after, None);
+        assert_eq!(decoded.id, entry.id);
+        assert_eq!(decoded.failed_change, None);
+        assert_eq!(decoded.timestamp.timestamp(), entry.timestamp.timestamp());
+    }
+
+    #[test]
+    fn test_failed_entry_references_change() {
+        let before = BamDelegationBlacklistEntry::new("vote", 800);
+        let after = before.clone().with_reason("sandwiching");
+        let change = BamDelegationBlacklistAuditEntry::new(
+            "vote",
+            BamDelegationBlacklistAction::Update,
+            "admin",
+            Some(before.clone()),
+            Some(after),
+        );
+
+        let failed = BamDelegationBlacklistAuditEntry::failed(&change);
+        assert_ne!(failed.id, change.id);
+        assert_eq!(failed.failed_change, Some(change.id));
+        assert_eq!(failed.action, BamDelegationBlacklistAction::Failed);
+        assert_eq!(failed.actor, "admin");
+        assert_eq!(failed.before, Some(before.clone()));
+        assert_eq!(failed.after, Some(before));
+
+        let document = bson::to_document(&failed).unwrap();
+        assert_eq!(document.get_str("action").unwrap(), "failed");
+        assert_eq!(document.get_object_id("failed_change").unwrap(), change.id);
+    }
+}
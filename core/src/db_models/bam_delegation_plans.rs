@@ -0,0 +1,142 @@
+//! DB model for a BAM delegation plan.
+
+use chrono::{serde::ts_seconds, DateTime, Utc};
+use mongodb::{
+    bson::{self, doc},
+    options::{FindOneOptions, UpdateOptions},
+    Collection, IndexModel,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db_models::{bam_epoch_metrics::BamDistribution, unique_index};
+
+/// Directed stake target of a validator in a [`BamDelegationPlan`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BamDelegationTarget {
+    /// Vote account address
+    pub vote_account: String,
+
+    /// Whether the validator is eligible for BAM delegation, ineligible validators get a zero
+    /// target
+    pub eligible: bool,
+
+    /// Directed stake the validator should have in lamports
+    pub target_lamports: u64,
+
+    /// BAM-directed stake the steward directed stake meta holds for the validator in lamports
+    pub current_lamports: u64,
+
+    /// `target_lamports` minus `current_lamports`
+    pub delta_lamports: i64,
+}
+
+impl BamDelegationTarget {
+    pub fn new(
+        vote_account: &str,
+        eligible: bool,
+        target_lamports: u64,
+        current_lamports: u64,
+    ) -> Self {
+        Self {
+            vote_account: vote_account.to_string(),
+            eligible,
+            target_lamports,
+            current_lamports,
+            delta_lamports: target_lamports as i64 - current_lamports as i64,
+        }
+    }
+}
+
+/// Directed stake targets of the BAM validators for an epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BamDelegationPlan {
+    /// Epoch number
+    pub epoch: u64,
+
+    /// Total JitoSOL stake available for BAM delegation in lamports
+    pub available_bam_delegation_stake: u64,
+
+    /// Distribution the targets were computed with, `None` in override mode
+    #[serde(default)]
+    pub distribution: Option<BamDistribution>,
+
+    /// Targets of the eligible validators, and zero targets of the validators that lost
+    /// eligibility
+    pub targets: Vec<BamDelegationTarget>,
+
+    /// Timestamp
+    #[serde(with = "ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+}
+
+impl BamDelegationPlan {
+    pub fn new(
+        epoch: u64,
+        available_bam_delegation_stake: u64,
+        distribution: Option<BamDistribution>,
+        targets: Vec<BamDelegationTarget>,
+    ) -> Self {
+        Self {
+            epoch,
+            available_bam_delegation_stake,
+            distribution,
+            targets,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Sum of the targets in lamports
+    pub fn total_target_lamports(&self) -> u64 {
+        self.targets.iter().map(|t| t.target_lamports).sum()
+    }
+}
+
+#[derive(Clone)]
+pub struct BamDelegationPlanStore {
+    /// Collection of BamDelegationPlan
+    collection: Collection<BamDelegationPlan>,
+}
+
+impl BamDelegationPlanStore {
+    pub const COLLECTION: &'static str = "bam_delegation_plans";
+
+    /// Initialize a [`BamDelegationPlanStore`]
+    pub fn new(collection: Collection<BamDelegationPlan>) -> Self {
+        Self { collection }
+    }
+
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![unique_index("epoch", doc! {"epoch": -1})]
+    }
+
+    /// Upsert the [`BamDelegationPlan`] of an epoch
+    pub async fn upsert(&self, plan: &BamDelegationPlan) -> Result<(), mongodb::error::Error> {
+        let update = doc! { "$set": bson::to_document(plan)? };
+        let filter = doc! { "epoch": plan.epoch as i64 };
+        let options = UpdateOptions::builder().upsert(true).build();
+        self.collection.update_one(filter, update, options).await?;
+        Ok(())
+    }
+
+    /// Find the [`BamDelegationPlan`] of an epoch, the latest one when `epoch` is unset
+    pub async fn find_by_epoch(
+        &self,
+        epoch: Option<u64>,
+    ) -> Result<Option<BamDelegationPlan>, mongodb::error::Error> {
+        let filter = epoch.map(|epoch| doc! {"epoch": epoch as i64});
+        let options = FindOneOptions::builder().sort(doc! {"epoch": -1}).build();
+        self.collection.find_one(filter, options).await
+    }
+
+    /// Find the latest [`BamDelegationPlan`] before `epoch`
+    pub async fn find_previous(
+        &self,
+        epoch: u64,
+    ) -> Result<Option<BamDelegationPlan>, mongodb::error::Error> {
+        let options = FindOneOptions::builder().sort(doc! {"epoch": -1}).build();
+        self.collection
+            .find_one(doc! {"epoch": {"$lt": epoch as i64}}, options)
+            .await
+    }
+}
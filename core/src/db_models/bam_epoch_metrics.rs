@@ -3,15 +3,94 @@
 use chrono::{serde::ts_seconds, DateTime, Utc};
 use mongodb::{
     bson::{self, doc},
-    Collection,
+    options::FindOneOptions,
+    Collection, IndexModel,
 };
 use serde::{Deserialize, Serialize};
 
+use crate::{bam_eligibility_rules::BamEligibilityRules, db_models::unique_index};
+
+/// Why an allocation applied to an epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BamAllocationReason {
+    /// The BAM stakeweight met the tier threshold in every confirmation epoch
+    TierConfirmed,
+
+    /// Metrics are missing for some of the confirmation epochs, the floor applied
+    InsufficientHistory,
+
+    /// No tier allocation above the floor was confirmed, the floor applied
+    Floor,
+
+    /// The allocation was overridden by the operator
+    Override,
+}
+
+/// Allocation tier that applied to an epoch and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BamAllocationTier {
+    /// Version of the allocation rules in effect
+    pub rules_version: String,
+
+    /// Stakeweight threshold (BPS) of the tier that applied, `None` when the floor applied
+    pub stakeweight_threshold_bps: Option<u64>,
+
+    /// Share of JitoSOL (BPS) allocated to BAM validators
+    pub allocation_bps: u64,
+
+    /// Consecutive epochs the stakeweight had to meet the tier threshold
+    pub confirmation_epochs: u64,
+
+    /// Lowest BAM stakeweight (BPS) over the confirmation epochs, `None` without enough history
+    pub confirmed_stakeweight_bps: Option<u64>,
+
+    pub reason: BamAllocationReason,
+}
+
+/// How the BAM delegation is split between the eligible validators
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BamDistributionStrategy {
+    /// Same target for every validator
+    #[default]
+    Equal,
+
+    /// Same target for every validator, capped by a share of its own active stake
+    StakeCapped,
+
+    /// Targets proportional to the validator scores
+    ScoreWeighted,
+}
+
+/// Distribution of the BAM delegation between the eligible validators
+///
+/// Stake capped out of a validator's target goes to the uncapped validators, stake no validator
+/// can take stays undelegated.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BamDistribution {
+    #[serde(default)]
+    pub strategy: BamDistributionStrategy,
+
+    /// Highest target as a share (BPS) of the validator's active stake, `stake_capped` only
+    #[serde(default)]
+    pub stake_cap_bps: Option<u64>,
+
+    /// Highest target as a share (BPS) of the available delegation, no cap when unset
+    #[serde(default)]
+    pub max_validator_share_bps: Option<u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BamEpochMetrics {
     /// Allocation tier based on JIP-28 in BPS
     allocation_bps: u64,
 
+    /// Tier behind `allocation_bps`, `None` for epochs computed before tiers were recorded
+    #[serde(default)]
+    allocation_tier: Option<BamAllocationTier>,
+
     /// Total JitoSOL stake available for BAM delegation in lamports.
     /// This is calculated as: (current_tier_allocation_bps / 10000) * total_jitosol_stake
     /// Represents the portion of JitoSOL that will be delegated to validators based on
@@ -21,15 +100,51 @@ pub struct BamEpochMetrics {
     /// Total stake amount of BAM-running validators in lamports
     bam_stake: u64,
 
+    /// Distribution of the delegation between the eligible validators, `None` for epochs
+    /// computed before distributions were recorded and in override mode
+    #[serde(default)]
+    distribution: Option<BamDistribution>,
+
     /// Eligible BAM validator count
     eligible_bam_validator_count: u64,
 
+    /// Eligibility rules the epoch was evaluated with, `None` for epochs evaluated before the
+    /// rules were recorded
+    #[serde(default)]
+    eligibility_rules: Option<BamEligibilityRules>,
+
+    /// Version of the eligibility rules the epoch was evaluated with, `None` for epochs evaluated
+    /// before the rules were versioned
+    #[serde(default)]
+    eligibility_rules_version: Option<String>,
+
+    /// SHA-256 hash of the eligibility rules file, base58 encoded
+    #[serde(default)]
+    eligibility_rules_hash: Option<String>,
+
     /// Epoch number
     epoch: u64,
 
+    /// Whether the record comes from the last scheduled run of the epoch, earlier records are
+    /// provisional
+    #[serde(default, rename = "final")]
+    is_final: bool,
+
     /// Total JitoSOL TVL in lamports
     jitosol_stake: u64,
 
+    /// Slot the record was computed at, `None` for records written before runs were numbered
+    #[serde(default)]
+    slot: Option<u64>,
+
+    /// Number of the run within the epoch, starting at 1
+    #[serde(default)]
+    snapshot: Option<u32>,
+
+    /// Epoch progress threshold of the run in BPS
+    #[serde(default)]
+    threshold_bps: Option<u64>,
+
     /// Timestamp
     #[serde(with = "ts_seconds")]
     timestamp: DateTime<Utc>,
@@ -50,11 +165,20 @@ impl BamEpochMetrics {
 
         Self {
             allocation_bps: 0,
+            allocation_tier: None,
             available_bam_delegation_stake: 0,
             bam_stake,
+            distribution: None,
             eligible_bam_validator_count,
+            eligibility_rules: None,
+            eligibility_rules_version: None,
+            eligibility_rules_hash: None,
             epoch,
+            is_final: false,
             jitosol_stake,
+            slot: None,
+            snapshot: None,
+            threshold_bps: None,
             timestamp,
             total_stake,
         }
@@ -65,6 +189,22 @@ impl BamEpochMetrics {
         self.allocation_bps = allocation_bps;
     }
 
+    /// Set the allocation tier, and the allocation percentage in BPS it gives
+    pub fn set_allocation_tier(&mut self, allocation_tier: BamAllocationTier) {
+        self.allocation_bps = allocation_tier.allocation_bps;
+        self.allocation_tier = Some(allocation_tier);
+    }
+
+    /// Get allocation percentage in BPS
+    pub fn get_allocation_bps(&self) -> u64 {
+        self.allocation_bps
+    }
+
+    /// Get the allocation tier
+    pub fn get_allocation_tier(&self) -> Option<&BamAllocationTier> {
+        self.allocation_tier.as_ref()
+    }
+
     /// Get available bam delegation stake in lamports
     pub fn get_available_bam_delegation_stake(&self) -> u64 {
         self.available_bam_delegation_stake
@@ -80,6 +220,38 @@ impl BamEpochMetrics {
         self.bam_stake
     }
 
+    /// Set the distribution of the delegation between the eligible validators
+    pub fn set_distribution(&mut self, distribution: BamDistribution) {
+        self.distribution = Some(distribution);
+    }
+
+    /// Get the distribution of the delegation between the eligible validators
+    pub fn get_distribution(&self) -> Option<&BamDistribution> {
+        self.distribution.as_ref()
+    }
+
+    /// Set the eligibility rules the epoch was evaluated with, with their version and hash
+    pub fn set_eligibility_rules(&mut self, eligibility_rules: &BamEligibilityRules) {
+        self.eligibility_rules_version = Some(eligibility_rules.version.clone());
+        self.eligibility_rules_hash = Some(eligibility_rules.hash().to_string());
+        self.eligibility_rules = Some(eligibility_rules.clone());
+    }
+
+    /// Get the eligibility rules, their hash is [`Self::get_eligibility_rules_hash`]
+    pub fn get_eligibility_rules(&self) -> Option<&BamEligibilityRules> {
+        self.eligibility_rules.as_ref()
+    }
+
+    /// Get the version of the eligibility rules
+    pub fn get_eligibility_rules_version(&self) -> Option<&str> {
+        self.eligibility_rules_version.as_deref()
+    }
+
+    /// Get the hash of the eligibility rules
+    pub fn get_eligibility_rules_hash(&self) -> Option<&str> {
+        self.eligibility_rules_hash.as_deref()
+    }
+
     /// Get epoch number
     pub fn get_epoch(&self) -> u64 {
         self.epoch
@@ -99,6 +271,34 @@ impl BamEpochMetrics {
     pub fn get_total_stake(&self) -> u64 {
         self.total_stake
     }
+
+    /// Set the scheduled run the record comes from
+    pub fn set_snapshot(&mut self, snapshot: u32, threshold_bps: u64, slot: u64, is_final: bool) {
+        self.snapshot = Some(snapshot);
+        self.threshold_bps = Some(threshold_bps);
+        self.slot = Some(slot);
+        self.is_final = is_final;
+    }
+
+    /// Get the number of the run within the epoch
+    pub fn get_snapshot(&self) -> Option<u32> {
+        self.snapshot
+    }
+
+    /// Get the epoch progress threshold of the run in BPS
+    pub fn get_threshold_bps(&self) -> Option<u64> {
+        self.threshold_bps
+    }
+
+    /// Get the slot the record was computed at
+    pub fn get_slot(&self) -> Option<u64> {
+        self.slot
+    }
+
+    /// Whether the record comes from the last scheduled run of the epoch
+    pub fn is_final(&self) -> bool {
+        self.is_final
+    }
 }
 
 #[derive(Clone)]
@@ -115,6 +315,11 @@ impl BamEpochMetricsStore {
         Self { collection }
     }
 
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![unique_index("epoch", doc! {"epoch": 1})]
+    }
+
     /// Insert a [`BamEpochMetrics`] record
     pub async fn insert(
         &self,
@@ -147,4 +352,14 @@ impl BamEpochMetricsStore {
             .find_one(doc! {"epoch": epoch as u32}, None)
             .await
     }
+
+    /// Find the latest [`BamEpochMetrics`] record with its eligibility rules
+    pub async fn find_latest_with_eligibility_rules(
+        &self,
+    ) -> Result<Option<BamEpochMetrics>, mongodb::error::Error> {
+        let options = FindOneOptions::builder().sort(doc! {"epoch": -1}).build();
+        self.collection
+            .find_one(doc! {"eligibility_rules": {"$ne": null}}, options)
+            .await
+    }
 }
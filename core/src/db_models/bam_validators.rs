@@ -4,11 +4,91 @@ use std::{str::FromStr, time::Instant};
 
 use chrono::{serde::ts_seconds, DateTime, Utc};
 use futures::TryStreamExt;
-use mongodb::{bson::doc, options::ReplaceOptions, Collection};
+use mongodb::{bson::doc, options::ReplaceOptions, Collection, IndexModel};
 use serde::{Deserialize, Serialize};
 use solana_pubkey::{ParsePubkeyError, Pubkey};
 
-use crate::db_models::error::DataStoreError;
+use crate::db_models::{error::DataStoreError, unique_index};
+
+/// BAM eligibility criterion a validator failed
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BamIneligibilityKind {
+    /// Not running the BAM client
+    NotBamClient,
+
+    /// Inflation commission above the maximum
+    HighCommission,
+
+    /// MEV commission above the maximum
+    HighMevCommission,
+
+    /// In the superminority
+    InSuperminority,
+
+    /// Vote credits below the required share of the chain maximum
+    LowVoteCredits,
+
+    /// Not enough epochs of validator history
+    InsufficientHistory,
+
+    /// On the steward blacklist
+    OnChainBlacklist,
+
+    /// On the BAM delegation blacklist
+    OffChainBlacklist,
+}
+
+/// Failed BAM eligibility criterion with the value that failed it
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct BamIneligibilityReason {
+    pub kind: BamIneligibilityKind,
+
+    /// Epoch the criterion failed in, the latest one when it failed in several epochs
+    pub epoch: Option<u64>,
+
+    /// Value observed in `epoch`: commission (%), MEV commission (bps), vote credits or epochs
+    /// of history
+    pub observed: Option<u64>,
+
+    /// Threshold `observed` was compared to
+    pub threshold: Option<u64>,
+}
+
+impl BamIneligibilityReason {
+    pub fn new(kind: BamIneligibilityKind) -> Self {
+        Self {
+            kind,
+            epoch: None,
+            observed: None,
+            threshold: None,
+        }
+    }
+
+    pub fn with_epoch(mut self, epoch: u64) -> Self {
+        self.epoch = Some(epoch);
+        self
+    }
+
+    pub fn with_values(mut self, observed: u64, threshold: u64) -> Self {
+        self.observed = Some(observed);
+        self.threshold = Some(threshold);
+        self
+    }
+}
+
+impl std::fmt::Display for BamIneligibilityReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.kind)?;
+        if let (Some(observed), Some(threshold)) = (self.observed, self.threshold) {
+            write!(f, ": {observed} (threshold: {threshold})")?;
+        }
+        if let Some(epoch) = self.epoch {
+            write!(f, " in epoch {epoch}")?;
+        }
+        Ok(())
+    }
+}
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct BamValidator {
@@ -24,12 +104,17 @@ pub struct BamValidator {
     /// Is eligible validator
     is_eligible: bool,
 
-    /// Reason for ineligibility
+    /// Reason for ineligibility, summary of `ineligibility_reasons`
     ineligibility_reason: Option<String>,
 
-    /// BAM delegation scoring
+    /// Every failed eligibility criterion
+    #[serde(default)]
+    ineligibility_reasons: Vec<BamIneligibilityReason>,
+
+    /// BAM delegation scoring, from 1 to 100 for eligible validators
     ///
-    /// Validators with a score of 0 will receive a target delegation of 0 lamports when next updating the directed stake meta
+    /// Validators with a score of 0 will receive a target delegation of 0 lamports when next updating the directed stake meta.
+    /// The score weights the targets of the `score_weighted` distribution.
     score: Option<u8>,
 
     /// Timestamp
@@ -50,6 +135,7 @@ impl Default for BamValidator {
             identity_account: String::new(),
             is_eligible: false,
             ineligibility_reason: None,
+            ineligibility_reasons: Vec::new(),
             score: None,
             timestamp,
             vote_account: String::new(),
@@ -74,6 +160,7 @@ impl BamValidator {
             identity_account: identity_account.to_string(),
             is_eligible,
             ineligibility_reason: None,
+            ineligibility_reasons: Vec::new(),
             score: None,
             timestamp,
             vote_account: vote_account.to_string(),
@@ -115,6 +202,26 @@ impl BamValidator {
         self.ineligibility_reason = ineligibility_reason;
     }
 
+    /// Get every failed eligibility criterion
+    pub fn get_ineligibility_reasons(&self) -> &[BamIneligibilityReason] {
+        &self.ineligibility_reasons
+    }
+
+    /// Set the failed eligibility criteria, and the ineligibility reason summarizing them
+    pub fn set_ineligibility_reasons(
+        &mut self,
+        ineligibility_reasons: Vec<BamIneligibilityReason>,
+    ) {
+        self.ineligibility_reason = (!ineligibility_reasons.is_empty()).then(|| {
+            ineligibility_reasons
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ")
+        });
+        self.ineligibility_reasons = ineligibility_reasons;
+    }
+
     /// Get identity account pubkey (node public key)
     pub fn get_identity_account(&self) -> Result<Pubkey, ParsePubkeyError> {
         Pubkey::from_str(&self.identity_account)
@@ -159,6 +266,14 @@ impl BamValidatorStore {
         Self { collection }
     }
 
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![unique_index(
+            "epoch_vote_account",
+            doc! {"epoch": 1, "vote_account": 1},
+        )]
+    }
+
     /// Insert many [`BamValidator`]
     pub async fn insert_many(&self, items: &[BamValidator]) -> Result<(), DataStoreError> {
         if items.is_empty() {
@@ -0,0 +1,162 @@
+//! DB model for the snapshots of the BAM writer runs.
+//!
+//! The BAM writer runs at several epoch progress thresholds and overwrites the epoch's
+//! `bam_epoch_metrics` and `bam_validators` records each time. Every run is also kept here as a
+//! numbered snapshot, which doubles as the threshold progress of the epoch: a threshold with a
+//! snapshot has run.
+
+use chrono::{serde::ts_seconds, DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{self, doc},
+    options::{FindOptions, UpdateOptions},
+    Collection, IndexModel,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db_models::{
+    bam_epoch_metrics::BamEpochMetrics, bam_validators::BamValidator, unique_index,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BamWriterSnapshot {
+    /// Epoch number
+    pub epoch: u64,
+
+    /// Number of the run within the epoch, starting at 1
+    pub snapshot: u32,
+
+    /// Epoch progress threshold of the run in BPS
+    pub threshold_bps: u64,
+
+    /// Slot the run was computed at
+    pub slot: u64,
+
+    /// Whether this is the last scheduled run of the epoch
+    #[serde(rename = "final")]
+    pub is_final: bool,
+
+    /// Epoch metrics written by the run
+    pub bam_epoch_metrics: BamEpochMetrics,
+
+    /// BAM validators written by the run
+    pub bam_validators: Vec<BamValidator>,
+
+    /// Timestamp
+    #[serde(with = "ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+}
+
+impl BamWriterSnapshot {
+    pub fn new(
+        bam_epoch_metrics: BamEpochMetrics,
+        bam_validators: Vec<BamValidator>,
+    ) -> Option<Self> {
+        Some(Self {
+            epoch: bam_epoch_metrics.get_epoch(),
+            snapshot: bam_epoch_metrics.get_snapshot()?,
+            threshold_bps: bam_epoch_metrics.get_threshold_bps()?,
+            slot: bam_epoch_metrics.get_slot()?,
+            is_final: bam_epoch_metrics.is_final(),
+            bam_epoch_metrics,
+            bam_validators,
+            timestamp: Utc::now(),
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct BamWriterSnapshotStore {
+    /// Collection of BamWriterSnapshot
+    collection: Collection<BamWriterSnapshot>,
+}
+
+impl BamWriterSnapshotStore {
+    pub const COLLECTION: &'static str = "bam_writer_snapshots";
+
+    /// Initialize a [`BamWriterSnapshotStore`]
+    pub fn new(collection: Collection<BamWriterSnapshot>) -> Self {
+        Self { collection }
+    }
+
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![unique_index(
+            "epoch_threshold_bps",
+            doc! {"epoch": -1, "threshold_bps": 1},
+        )]
+    }
+
+    /// Upsert the [`BamWriterSnapshot`] of an epoch and threshold, so a repeated run replaces
+    /// its snapshot
+    pub async fn upsert(&self, snapshot: &BamWriterSnapshot) -> Result<(), mongodb::error::Error> {
+        let update = doc! { "$set": bson::to_document(snapshot)? };
+        let filter = doc! {
+            "epoch": snapshot.epoch as i64,
+            "threshold_bps": snapshot.threshold_bps as i64,
+        };
+        let options = UpdateOptions::builder().upsert(true).build();
+        self.collection.update_one(filter, update, options).await?;
+        Ok(())
+    }
+
+    /// Find the [`BamWriterSnapshot`] records of an epoch, in run order
+    pub async fn find_by_epoch(
+        &self,
+        epoch: u64,
+    ) -> Result<Vec<BamWriterSnapshot>, mongodb::error::Error> {
+        let options = FindOptions::builder().sort(doc! {"snapshot": 1}).build();
+        let cursor = self
+            .collection
+            .find(doc! {"epoch": epoch as i64}, options)
+            .await?;
+
+        cursor.try_collect().await
+    }
+
+    /// Find the final [`BamWriterSnapshot`] of an epoch
+    pub async fn find_final(
+        &self,
+        epoch: u64,
+    ) -> Result<Option<BamWriterSnapshot>, mongodb::error::Error> {
+        self.collection
+            .find_one(doc! {"epoch": epoch as i64, "final": true}, None)
+            .await
+    }
+
+    /// Thresholds in BPS that already ran in an epoch
+    pub async fn find_threshold_bps(&self, epoch: u64) -> Result<Vec<u64>, mongodb::error::Error> {
+        Ok(self
+            .find_by_epoch(epoch)
+            .await?
+            .iter()
+            .map(|snapshot| snapshot.threshold_bps)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_from_metrics() {
+        let mut metrics = BamEpochMetrics::new(800, 10, 100, 50, 2);
+        assert!(BamWriterSnapshot::new(metrics.clone(), vec![]).is_none());
+
+        metrics.set_snapshot(3, 9_000, 345_600_000, true);
+        let snapshot = BamWriterSnapshot::new(metrics, vec![]).unwrap();
+        assert_eq!(snapshot.epoch, 800);
+        assert_eq!(snapshot.snapshot, 3);
+        assert_eq!(snapshot.threshold_bps, 9_000);
+        assert!(snapshot.is_final);
+
+        let document = bson::to_document(&snapshot).unwrap();
+        assert!(document.get_bool("final").unwrap());
+        assert!(document
+            .get_document("bam_epoch_metrics")
+            .unwrap()
+            .get_bool("final")
+            .unwrap());
+    }
+}
@@ -19,4 +19,10 @@ pub enum DataStoreError {
 
     #[error("Invalid Pubkey")]
     InvalidPubkey(#[from] ParsePubkeyError),
+
+    #[error("migration {version} failed: {source}")]
+    MigrationFailed {
+        version: u32,
+        source: mongodb::error::Error,
+    },
 }
@@ -5,14 +5,14 @@ use mongodb::{
     bson,
     bson::doc,
     options::{FindOneOptions, FindOptions},
-    Collection,
+    Collection, IndexModel,
 };
 use serde::{Deserialize, Serialize};
 use solana_pubkey::Pubkey;
 
 use crate::{
     constants::{STAKER_REWARDS_COLLECTION_NAME, VALIDATOR_REWARDS_COLLECTION_NAME},
-    db_models::error::DataStoreError,
+    db_models::{error::DataStoreError, index},
     SortOrder,
 };
 
@@ -45,6 +45,14 @@ impl ValidatorRewardsStore {
         Self { collection }
     }
 
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            index(doc! {"vote_account": 1, "epoch": -1}),
+            index(doc! {"epoch": -1, "mev_revenue": -1}),
+        ]
+    }
+
     pub async fn get_mev_rewards_sum(&self, epoch: u64) -> Result<u64, DataStoreError> {
         let pipeline = vec![
             doc! {
@@ -175,6 +183,15 @@ impl StakerRewardsStore {
         Self { collection }
     }
 
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            index(doc! {"stake_authority": 1, "epoch": -1}),
+            index(doc! {"validator_vote_account": 1, "epoch": -1}),
+            index(doc! {"epoch": -1, "amount": -1}),
+        ]
+    }
+
     pub async fn get_staker_rewards(
         &self,
         staker_authority: Option<&str>,
@@ -1,9 +1,50 @@
+use mongodb::{
+    bson::Document,
+    error::{CommandError, ErrorKind, WriteError, WriteFailure},
+    options::IndexOptions,
+    IndexModel,
+};
+
 pub mod bam_boost_validators;
 pub mod bam_delegation_blacklist;
+pub mod bam_delegation_blacklist_audit;
+pub mod bam_delegation_plans;
 pub mod bam_epoch_metrics;
 pub mod bam_validators;
+pub mod bam_writer_snapshots;
 pub mod error;
 pub mod mev_rewards;
 pub mod stake_pool_stats;
+pub mod steward_backfill_checkpoints;
 pub mod steward_events;
+pub mod steward_parameters;
+pub mod steward_state_snapshots;
 pub mod validators;
+
+/// Index on `keys`
+pub(crate) fn index(keys: Document) -> IndexModel {
+    IndexModel::builder().keys(keys).build()
+}
+
+/// Unique index on `keys`
+///
+/// Named explicitly so a later migration can drop it when the key changes.
+pub(crate) fn unique_index(name: &str, keys: Document) -> IndexModel {
+    let options = IndexOptions::builder()
+        .name(name.to_string())
+        .unique(true)
+        .build();
+    IndexModel::builder().keys(keys).options(options).build()
+}
+
+/// Whether `error` is a write rejected by a unique index
+pub fn is_duplicate_key_error(error: &mongodb::error::Error) -> bool {
+    const DUPLICATE_KEY: i32 = 11000;
+    match error.kind.as_ref() {
+        ErrorKind::Write(WriteFailure::WriteError(WriteError { code, .. })) => {
+            *code == DUPLICATE_KEY
+        }
+        ErrorKind::Command(CommandError { code, .. }) => *code == DUPLICATE_KEY,
+        _ => false,
+    }
+}
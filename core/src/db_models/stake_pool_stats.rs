@@ -3,12 +3,12 @@ use std::collections::HashMap;
 use chrono::{serde::ts_seconds, DateTime, Utc};
 use constants::STAKE_POOL_STATS_COLLECTION_NAME;
 use futures_util::StreamExt;
-use mongodb::{bson, bson::doc, Collection};
+use mongodb::{bson, bson::doc, Collection, IndexModel};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::constants;
-use crate::db_models::error::DataStoreError;
+use crate::db_models::{error::DataStoreError, index};
 
 #[derive(Error, Debug, Clone)]
 pub enum StakePoolStatsError {
@@ -47,6 +47,11 @@ impl StakePoolStatsStore {
         Self { collection }
     }
 
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![index(doc! {"timestamp": 1}), index(doc! {"epoch": 1})]
+    }
+
     /// Groups documents into daily buckets between the provided time range.
     /// Returns the last document in each group.
     pub async fn aggregate(
@@ -0,0 +1,160 @@
+//! DB model for the progress of a steward event backfill.
+
+use mongodb::{
+    bson::{doc, DateTime},
+    options::UpdateOptions,
+    Collection, IndexModel,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{constants::STEWARD_BACKFILL_CHECKPOINTS_COLLECTION_NAME, db_models::unique_index};
+
+/// Progress of a backfill of `stream` over the slot window `start_slot..=end_slot`
+///
+/// Windows are walked from `end_slot` down to `start_slot`, so everything newer than
+/// `cursor_slot` has been written.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StewardBackfillCheckpoint {
+    /// Events backfilled, see [`StewardBackfillCheckpoint::stream`]
+    pub stream: String,
+
+    /// First slot of the window
+    pub start_slot: u64,
+
+    /// Last slot of the window
+    pub end_slot: u64,
+
+    /// Oldest signature processed so far, signatures are fetched before it on resume
+    pub cursor_signature: Option<String>,
+
+    /// Slot of `cursor_signature`
+    pub cursor_slot: Option<u64>,
+
+    /// Number of events written for the window
+    pub events_written: u64,
+
+    /// Whether every signature of the window has been processed
+    pub completed: bool,
+
+    /// Last update
+    pub updated_at: DateTime,
+}
+
+impl StewardBackfillCheckpoint {
+    pub fn new(stream: &str, start_slot: u64, end_slot: u64) -> Self {
+        Self {
+            stream: stream.to_string(),
+            start_slot,
+            end_slot,
+            cursor_signature: None,
+            cursor_slot: None,
+            events_written: 0,
+            completed: false,
+            updated_at: DateTime::now(),
+        }
+    }
+
+    /// Identity of a backfill: the events of `program_id` for `stake_pool`, written to
+    /// `collection`
+    ///
+    /// A window is only resumed by a backfill of the same stream, so backfilling another program,
+    /// stake pool or collection over the same slots starts from scratch.
+    pub fn stream(collection: &str, program_id: &str, stake_pool: &str) -> String {
+        format!("{collection}:{program_id}:{stake_pool}")
+    }
+
+    /// Slots of the window covered so far
+    pub fn slots_covered(&self) -> u64 {
+        match (self.completed, self.cursor_slot) {
+            (true, _) => self.end_slot - self.start_slot + 1,
+            (false, Some(cursor_slot)) => self
+                .end_slot
+                .saturating_sub(cursor_slot.max(self.start_slot)),
+            (false, None) => 0,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct StewardBackfillCheckpointStore {
+    collection: Collection<StewardBackfillCheckpoint>,
+}
+
+impl StewardBackfillCheckpointStore {
+    pub const COLLECTION: &'static str = STEWARD_BACKFILL_CHECKPOINTS_COLLECTION_NAME;
+
+    pub fn new(collection: Collection<StewardBackfillCheckpoint>) -> Self {
+        Self { collection }
+    }
+
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![unique_index(
+            "stream_start_slot_end_slot",
+            doc! {"stream": 1, "start_slot": 1, "end_slot": 1},
+        )]
+    }
+
+    /// Checkpoint of a window of `stream`, `None` if the window was never started
+    pub async fn find(
+        &self,
+        stream: &str,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> Result<Option<StewardBackfillCheckpoint>, mongodb::error::Error> {
+        let filter = doc! {
+            "stream": stream,
+            "start_slot": start_slot as i64,
+            "end_slot": end_slot as i64,
+        };
+        self.collection.find_one(filter, None).await
+    }
+
+    /// Record the progress of a window
+    pub async fn upsert(
+        &self,
+        checkpoint: &StewardBackfillCheckpoint,
+    ) -> Result<(), mongodb::error::Error> {
+        let filter = doc! {
+            "stream": &checkpoint.stream,
+            "start_slot": checkpoint.start_slot as i64,
+            "end_slot": checkpoint.end_slot as i64,
+        };
+        let update = doc! {"$set": mongodb::bson::to_document(checkpoint)?};
+        let options = UpdateOptions::builder().upsert(true).build();
+        self.collection.update_one(filter, update, options).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_identity() {
+        let stream = StewardBackfillCheckpoint::stream("steward_events", "program", "pool");
+        assert_eq!(stream, "steward_events:program:pool");
+
+        assert_ne!(
+            stream,
+            StewardBackfillCheckpoint::stream("steward_events", "program", "other_pool")
+        );
+        assert_ne!(
+            stream,
+            StewardBackfillCheckpoint::stream("other_events", "program", "pool")
+        );
+    }
+
+    #[test]
+    fn test_slots_covered() {
+        let mut checkpoint = StewardBackfillCheckpoint::new("stream", 200, 299);
+        assert_eq!(checkpoint.slots_covered(), 0);
+
+        checkpoint.cursor_slot = Some(250);
+        assert_eq!(checkpoint.slots_covered(), 49);
+
+        checkpoint.completed = true;
+        assert_eq!(checkpoint.slots_covered(), 100);
+    }
+}
@@ -11,20 +11,34 @@ use jito_steward::{
 };
 use log::info;
 use mongodb::{
-    bson::{self, doc, DateTime, Document},
-    options::FindOneOptions,
-    Collection,
+    bson::{self, doc, Bson, DateTime, Document},
+    change_stream::{event::ChangeStreamEvent, ChangeStream},
+    options::{FindOneOptions, IndexOptions},
+    Collection, IndexModel,
 };
 use serde::{Deserialize, Serialize};
 use solana_pubkey::Pubkey;
 use solana_signature::Signature;
 
-use crate::constants::STEWARD_EVENTS_COLLECTION_NAME;
+use crate::{constants::STEWARD_EVENTS_COLLECTION_NAME, db_models::index, SortOrder};
+
+const COMMITMENT_PROCESSED: &str = "processed";
+const COMMITMENT_CONFIRMED: &str = "confirmed";
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StewardEvent {
     pub signature: String,
     pub instruction_idx: u32,
+
+    /// Index among the inner instructions of `instruction_idx` of the instruction that emitted the
+    /// event, `None` when emitted by the top-level instruction itself
+    #[serde(default)]
+    pub inner_instruction_idx: Option<u32>,
+
+    /// Position of the event among the events emitted by `instruction_idx`
+    #[serde(default)]
+    pub event_idx: u32,
+
     pub event_type: String,
     pub vote_account: Option<String>,
     pub metadata: Option<bson::Document>,
@@ -34,9 +48,17 @@ pub struct StewardEvent {
     pub epoch: u64,
     pub slot: u64,
     pub timestamp: Option<DateTime>,
+
+    /// Commitment level the event was read at, `None` for events written before it was tracked,
+    /// which were all read at `finalized`
+    #[serde(default)]
+    pub commitment: Option<String>,
 }
 
 impl StewardEvent {
+    /// Event type of `Program data:` payloads the writer could not decode
+    pub const UNKNOWN_EVENT_TYPE: &'static str = "Unknown";
+
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         signature: &Signature,
@@ -54,6 +76,8 @@ impl StewardEvent {
         Self {
             signature: signature.to_string(),
             instruction_idx,
+            inner_instruction_idx: None,
+            event_idx: 0,
             event_type: event_type.to_string(),
             vote_account: vote_account.map(|pk| pk.to_string()),
             metadata,
@@ -63,9 +87,18 @@ impl StewardEvent {
             epoch,
             timestamp: timestamp.map(|t| DateTime::from_millis(t * 1000)),
             slot,
+            commitment: None,
         }
     }
 
+    /// Whether the event was read from a finalized block
+    pub fn is_finalized(&self) -> bool {
+        !matches!(
+            self.commitment.as_deref(),
+            Some(COMMITMENT_PROCESSED | COMMITMENT_CONFIRMED)
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn from_rebalance_event(
         event: RebalanceEvent,
@@ -386,6 +419,42 @@ impl StewardEvent {
         )
     }
 
+    /// Event the writer has no decoder for, `metadata` keeps the hex encoded `discriminator` and
+    /// the base64 `data` of the `Program data:` log so it can be decoded later
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_unknown_event(
+        discriminator: &[u8],
+        data: &str,
+        signature: &Signature,
+        instruction_idx: u32,
+        tx_error: Option<String>,
+        signer: &Pubkey,
+        stake_pool: &Pubkey,
+        timestamp: Option<i64>,
+        epoch: u64,
+        slot: u64,
+    ) -> Self {
+        let discriminator: String = discriminator.iter().map(|b| format!("{b:02x}")).collect();
+        let metadata = doc! {
+            "discriminator": discriminator,
+            "data": data,
+        };
+
+        Self::new(
+            signature,
+            instruction_idx,
+            &Self::UNKNOWN_EVENT_TYPE.to_string(),
+            None,
+            Some(metadata),
+            tx_error,
+            signer,
+            stake_pool,
+            epoch,
+            timestamp,
+            slot,
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn from_auto_add_validator_event(
         event: AutoAddValidatorEvent,
@@ -487,6 +556,224 @@ impl StewardEvent {
     }
 }
 
+/// Filter of [`StewardEventsStore::find_steward_events`], unset fields match every event
+#[derive(Debug, Clone, Default)]
+pub struct StewardEventsFilter {
+    /// Event types, `ScoreComponents` and `InstantUnstakeComponents` match all their versions
+    pub event_types: Vec<String>,
+    pub vote_account: Option<String>,
+    pub epoch: Option<u64>,
+
+    /// First slot, inclusive
+    pub start_slot: Option<u64>,
+
+    /// Last slot, inclusive
+    pub end_slot: Option<u64>,
+
+    /// Earliest block time, inclusive
+    pub start_time: Option<DateTime>,
+
+    /// Latest block time, inclusive
+    pub end_time: Option<DateTime>,
+
+    pub signer: Option<String>,
+    pub stake_pool: Option<String>,
+
+    /// Only events of failed transactions when `true`, of successful ones when `false`
+    pub has_error: Option<bool>,
+}
+
+impl StewardEventsFilter {
+    fn to_document(&self) -> Document {
+        let mut filter = Document::new();
+
+        let event_types: Vec<&str> = self
+            .event_types
+            .iter()
+            .flat_map(|event_type| match event_type.as_str() {
+                "ScoreComponents" => StewardEventsStore::SCORE_COMPONENTS_EVENT_TYPES.to_vec(),
+                "InstantUnstakeComponents" => vec![
+                    "InstantUnstakeComponents",
+                    "InstantUnstakeComponentsV2",
+                    "InstantUnstakeComponentsV3",
+                ],
+                event_type => vec![event_type],
+            })
+            .collect();
+        if !event_types.is_empty() {
+            filter.insert("event_type", doc! { "$in": event_types });
+        }
+        if let Some(vote_account) = &self.vote_account {
+            filter.insert("vote_account", vote_account);
+        }
+        if let Some(epoch) = self.epoch {
+            filter.insert("epoch", epoch as i64);
+        }
+        if let Some(signer) = &self.signer {
+            filter.insert("signer", signer);
+        }
+        if let Some(stake_pool) = &self.stake_pool {
+            filter.insert("stake_pool", stake_pool);
+        }
+
+        let mut slot = Document::new();
+        if let Some(start_slot) = self.start_slot {
+            slot.insert("$gte", start_slot as i64);
+        }
+        if let Some(end_slot) = self.end_slot {
+            slot.insert("$lte", end_slot as i64);
+        }
+        if !slot.is_empty() {
+            filter.insert("slot", slot);
+        }
+
+        let mut timestamp = Document::new();
+        if let Some(start_time) = self.start_time {
+            timestamp.insert("$gte", start_time);
+        }
+        if let Some(end_time) = self.end_time {
+            timestamp.insert("$lte", end_time);
+        }
+        if !timestamp.is_empty() {
+            filter.insert("timestamp", timestamp);
+        }
+
+        match self.has_error {
+            Some(true) => {
+                filter.insert("tx_error", doc! { "$ne": null });
+            }
+            Some(false) => {
+                filter.insert("tx_error", Bson::Null);
+            }
+            None => {}
+        }
+
+        filter
+    }
+
+    /// Change stream `$match` stage of the inserted events matching the filter, whose fields are
+    /// nested under `fullDocument`
+    fn to_change_stream_match(&self) -> Document {
+        let mut event_match = doc! { "operationType": "insert" };
+        for (key, value) in self.to_document() {
+            event_match.insert(format!("fullDocument.{key}"), value);
+        }
+
+        doc! { "$match": event_match }
+    }
+}
+
+/// Position of an event in the (slot, signature, instruction_idx, event_idx) order, serialized
+/// as `slot:signature:instruction_idx:event_idx`. Cursors order like the events they point at
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StewardEventsCursor {
+    pub slot: u64,
+    pub signature: String,
+    pub instruction_idx: u32,
+    pub event_idx: u32,
+}
+
+impl StewardEventsCursor {
+    /// Cursor of the page following `event`
+    pub fn after(event: &StewardEvent) -> Self {
+        Self {
+            slot: event.slot,
+            signature: event.signature.clone(),
+            instruction_idx: event.instruction_idx,
+            event_idx: event.event_idx,
+        }
+    }
+
+    /// Events strictly after the cursor, `operator` being `$gt` ascending and `$lt` descending
+    fn to_document(&self, operator: &str) -> Document {
+        let slot = self.slot as i64;
+        doc! {
+            "$or": [
+                { "slot": { operator: slot } },
+                { "slot": slot, "signature": { operator: &self.signature } },
+                {
+                    "slot": slot,
+                    "signature": &self.signature,
+                    "instruction_idx": { operator: self.instruction_idx },
+                },
+                {
+                    "slot": slot,
+                    "signature": &self.signature,
+                    "instruction_idx": self.instruction_idx,
+                    "event_idx": { operator: self.event_idx },
+                },
+            ]
+        }
+    }
+}
+
+impl std::fmt::Display for StewardEventsCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            self.slot, self.signature, self.instruction_idx, self.event_idx
+        )
+    }
+}
+
+impl FromStr for StewardEventsCursor {
+    type Err = String;
+
+    fn from_str(cursor: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid cursor {cursor}");
+        let mut parts = cursor.split(':');
+        let mut next = || parts.next().ok_or_else(invalid);
+
+        let slot = next()?.parse().map_err(|_| invalid())?;
+        let signature = next()?.to_string();
+        let instruction_idx = next()?.parse().map_err(|_| invalid())?;
+        let event_idx = next()?.parse().map_err(|_| invalid())?;
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            slot,
+            signature,
+            instruction_idx,
+            event_idx,
+        })
+    }
+}
+
+/// Stake moved to and from a validator by the rebalances of successful transactions of an epoch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorRebalance {
+    pub vote_account: String,
+
+    /// Number of rebalance events
+    pub rebalances: u64,
+
+    pub increase_lamports: u64,
+
+    /// Decrease due to the validator score falling out of the delegation set
+    pub scoring_unstake_lamports: u64,
+
+    /// Decrease due to an instant unstake
+    pub instant_unstake_lamports: u64,
+
+    /// Decrease to cover stake deposit withdrawals
+    pub stake_deposit_unstake_lamports: u64,
+
+    /// Decrease of directed rebalances
+    pub directed_unstake_lamports: u64,
+}
+
+impl ValidatorRebalance {
+    pub fn decrease_lamports(&self) -> u64 {
+        self.scoring_unstake_lamports
+            + self.instant_unstake_lamports
+            + self.stake_deposit_unstake_lamports
+            + self.directed_unstake_lamports
+    }
+}
+
 #[derive(Clone)]
 pub struct StewardEventsStore {
     collection: Collection<StewardEvent>,
@@ -495,18 +782,73 @@ pub struct StewardEventsStore {
 impl StewardEventsStore {
     pub const COLLECTION: &'static str = STEWARD_EVENTS_COLLECTION_NAME;
 
+    /// Versions of the ScoreComponents event
+    pub const SCORE_COMPONENTS_EVENT_TYPES: [&'static str; 4] = [
+        "ScoreComponents",
+        "ScoreComponentsV2",
+        "ScoreComponentsV3",
+        "ScoreComponentsV4",
+    ];
+
+    /// Event types moving stake between validators
+    pub const REBALANCE_EVENT_TYPES: [&'static str; 2] =
+        ["RebalanceEvent", "DirectedRebalanceEvent"];
+
+    /// Name of the unique (signature, instruction_idx, event_idx) index
+    pub const UNIQUE_KEY_INDEX: &'static str = "signature_instruction_idx_event_idx";
+
     pub fn new(collection: Collection<StewardEvent>) -> Self {
         Self { collection }
     }
 
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            // Events written before `event_idx` was tracked are keyed by
+            // (signature, event_type, vote_account) and left out of the unique key
+            IndexModel::builder()
+                .keys(doc! {"signature": 1, "instruction_idx": 1, "event_idx": 1})
+                .options(
+                    IndexOptions::builder()
+                        .name(Self::UNIQUE_KEY_INDEX.to_string())
+                        .unique(true)
+                        .partial_filter_expression(doc! {"event_idx": {"$exists": true}})
+                        .build(),
+                )
+                .build(),
+            index(doc! {"signature": 1}),
+            index(doc! {"slot": -1}),
+            index(doc! {"slot": -1, "signature": -1, "instruction_idx": -1, "event_idx": -1}),
+            index(doc! {"signer": 1, "slot": -1}),
+            index(doc! {"stake_pool": 1, "slot": -1}),
+            index(doc! {"timestamp": -1}),
+            index(doc! {"tx_error": 1, "slot": -1}),
+            index(doc! {"vote_account": 1, "slot": -1}),
+            index(doc! {"event_type": 1, "slot": -1}),
+            index(doc! {"epoch": 1, "slot": -1}),
+        ]
+    }
+
     pub async fn insert(&self, event: StewardEvent) -> Result<(), mongodb::error::Error> {
         self.collection.insert_one(event, None).await?;
         Ok(())
     }
 
+    /// Upsert an event by (signature, instruction_idx, event_idx)
+    ///
+    /// Finalized events overwrite whatever is stored. Unfinalized events are only inserted, so they
+    /// never downgrade an event that was already written at `finalized`.
     pub async fn upsert(&self, event: StewardEvent) -> Result<(), mongodb::error::Error> {
-        let update = doc! { "$set": bson::to_document(&event)? };
-        let filter = doc! { "signature": &event.signature, "event_type": &event.event_type, "vote_account": &event.vote_account };
+        let update = if event.is_finalized() {
+            doc! { "$set": bson::to_document(&event)? }
+        } else {
+            doc! { "$setOnInsert": bson::to_document(&event)? }
+        };
+        let filter = doc! {
+            "signature": &event.signature,
+            "instruction_idx": event.instruction_idx,
+            "event_idx": event.event_idx,
+        };
         let options = mongodb::options::UpdateOptions::builder()
             .upsert(true)
             .build();
@@ -514,23 +856,54 @@ impl StewardEventsStore {
         Ok(())
     }
 
+    /// Replace the event stored under the key of `event`, whatever its commitment
+    pub async fn replace(&self, event: &StewardEvent) -> Result<(), mongodb::error::Error> {
+        let filter = doc! {
+            "signature": &event.signature,
+            "instruction_idx": event.instruction_idx,
+            "event_idx": event.event_idx,
+        };
+        self.collection.replace_one(filter, event, None).await?;
+        Ok(())
+    }
+
     pub async fn bulk_upsert(
         &self,
         events: Vec<StewardEvent>,
     ) -> Result<(), mongodb::error::Error> {
         info!("upserting {} steward events", events.len());
+
+        // Re-processed transactions replace events written before `event_idx` was tracked, which
+        // would not match the upsert filter
+        let signatures: Vec<&str> = events.iter().map(|e| e.signature.as_str()).collect();
+        if !signatures.is_empty() {
+            self.collection
+                .delete_many(
+                    doc! {
+                        "signature": { "$in": signatures },
+                        "event_idx": { "$exists": false },
+                    },
+                    None,
+                )
+                .await?;
+        }
+
         for event in events {
             self.upsert(event).await?;
         }
         Ok(())
     }
 
+    /// Signature and slot of the latest finalized event
     pub async fn get_latest_signature_and_slot(
         &self,
     ) -> Result<Option<(Signature, u64)>, mongodb::error::Error> {
         let options = FindOneOptions::builder().sort(doc! { "slot": -1 }).build();
 
-        let result = self.collection.find_one(None, options).await?;
+        let result = self
+            .collection
+            .find_one(Self::finalized_filter(), options)
+            .await?;
 
         match result {
             Some(event) => {
@@ -546,6 +919,34 @@ impl StewardEventsStore {
         }
     }
 
+    /// Delete unfinalized events older than `slot`
+    ///
+    /// Called once every finalized transaction up to `slot` has been written: events still
+    /// unfinalized at that point belong to blocks that were skipped.
+    pub async fn delete_unfinalized_before(&self, slot: u64) -> Result<u64, mongodb::error::Error> {
+        let result = self
+            .collection
+            .delete_many(
+                doc! {
+                    "commitment": {
+                        "$in": [COMMITMENT_PROCESSED, COMMITMENT_CONFIRMED]
+                    },
+                    "slot": { "$lt": slot as i64 },
+                },
+                None,
+            )
+            .await?;
+        Ok(result.deleted_count)
+    }
+
+    fn finalized_filter() -> Document {
+        doc! {
+            "commitment": {
+                "$nin": [COMMITMENT_PROCESSED, COMMITMENT_CONFIRMED]
+            }
+        }
+    }
+
     pub async fn find_by_vote_account(
         &self,
         vote_account: &String,
@@ -608,55 +1009,395 @@ impl StewardEventsStore {
         Ok(events)
     }
 
+    /// Page of the events matching `filter`, with the number of events matching it when `count`
+    /// is set
+    ///
+    /// Events are sorted by (slot, signature, instruction_idx, event_idx). With a `cursor`, the
+    /// page starts right after the cursor event and `skip` is usually left unset. Counting scans
+    /// every matching event, so callers only count for the first page.
     pub async fn find_steward_events(
         &self,
-        event_type: Option<String>,
-        vote_account: Option<String>,
-        epoch: Option<u64>,
+        filter: &StewardEventsFilter,
+        sort_order: SortOrder,
+        cursor: Option<&StewardEventsCursor>,
+        skip: Option<u64>,
+        limit: i64,
+        count: bool,
+    ) -> Result<(Vec<StewardEvent>, Option<u64>), mongodb::error::Error> {
+        let total_count = if count {
+            Some(
+                self.collection
+                    .count_documents(filter.to_document(), None)
+                    .await?,
+            )
+        } else {
+            None
+        };
+        let events = self
+            .find_page(filter, sort_order, cursor, skip, limit)
+            .await?;
+
+        Ok((events, total_count))
+    }
+
+    /// Events matching `filter` written after `cursor`, oldest first, for tailing the collection
+    /// when change streams are unavailable
+    ///
+    /// Events written late for a slot before the cursor are not returned.
+    pub async fn find_steward_events_after(
+        &self,
+        filter: &StewardEventsFilter,
+        cursor: Option<&StewardEventsCursor>,
         limit: i64,
-        skip: u64,
     ) -> Result<Vec<StewardEvent>, mongodb::error::Error> {
-        let mut filter = Document::new();
+        self.find_page(filter, SortOrder::Asc, cursor, None, limit)
+            .await
+    }
+
+    /// Change stream of the events matching `filter` inserted from now on
+    ///
+    /// Fails on deployments without change streams, such as standalone servers.
+    pub async fn watch_steward_events(
+        &self,
+        filter: &StewardEventsFilter,
+    ) -> Result<ChangeStream<ChangeStreamEvent<StewardEvent>>, mongodb::error::Error> {
+        self.collection
+            .watch(vec![filter.to_change_stream_match()], None)
+            .await
+    }
+
+    async fn find_page(
+        &self,
+        filter: &StewardEventsFilter,
+        sort_order: SortOrder,
+        cursor: Option<&StewardEventsCursor>,
+        skip: Option<u64>,
+        limit: i64,
+    ) -> Result<Vec<StewardEvent>, mongodb::error::Error> {
+        let filter = filter.to_document();
+        let (direction, operator) = match sort_order {
+            SortOrder::Asc => (1, "$gt"),
+            SortOrder::Desc => (-1, "$lt"),
+        };
+        let filter = match cursor {
+            Some(cursor) => doc! { "$and": [filter, cursor.to_document(operator)] },
+            None => filter,
+        };
+        let options = mongodb::options::FindOptions::builder()
+            .sort(doc! {
+                "slot": direction,
+                "signature": direction,
+                "instruction_idx": direction,
+                "event_idx": direction,
+            })
+            .limit(limit)
+            .skip(skip)
+            .build();
+
+        self.collection
+            .find(filter, options)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    /// Events of `event_types` of a validator over `start_epoch..=end_epoch`, oldest first
+    pub async fn find_validator_events(
+        &self,
+        vote_account: &str,
+        start_epoch: u64,
+        end_epoch: u64,
+        event_types: &[&str],
+    ) -> Result<Vec<StewardEvent>, mongodb::error::Error> {
+        let filter = doc! {
+            "vote_account": vote_account,
+            "epoch": { "$gte": start_epoch as i64, "$lte": end_epoch as i64 },
+            "event_type": { "$in": event_types },
+        };
+        let options = mongodb::options::FindOptions::builder()
+            .sort(doc! { "slot": 1, "instruction_idx": 1, "event_idx": 1 })
+            .build();
+
+        self.collection
+            .find(filter, options)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    /// Events of `event_type` emitted by the transactions of `signatures`
+    pub async fn find_by_signatures(
+        &self,
+        event_type: &str,
+        signatures: &[String],
+    ) -> Result<Vec<StewardEvent>, mongodb::error::Error> {
+        let filter = doc! {
+            "event_type": event_type,
+            "signature": { "$in": signatures },
+        };
+
+        self.collection
+            .find(filter, None)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    /// Latest ScoreComponents event of a validator, of `epoch` when set
+    pub async fn find_latest_score_components(
+        &self,
+        vote_account: &str,
+        epoch: Option<u64>,
+    ) -> Result<Option<StewardEvent>, mongodb::error::Error> {
+        let mut filter = doc! {
+            "vote_account": vote_account,
+            "event_type": { "$in": Self::SCORE_COMPONENTS_EVENT_TYPES.to_vec() },
+        };
+        if let Some(epoch) = epoch {
+            filter.insert("epoch", epoch as i64);
+        }
+        let options = FindOneOptions::builder().sort(doc! { "slot": -1 }).build();
 
-        if let Some(event_type) = event_type {
-            match event_type.as_str() {
-                "ScoreComponents" => {
-                    filter.insert(
-                        "event_type",
-                        doc! { "$in": ["ScoreComponents", "ScoreComponentsV2", "ScoreComponentsV3", "ScoreComponentsV4"] },
-                    );
+        self.collection.find_one(filter, options).await
+    }
+
+    /// Stake moved per validator by the rebalances of `epoch`, sorted by vote account
+    ///
+    /// Events of failed transactions are left out, they did not move any stake.
+    pub async fn get_rebalance_summary(
+        &self,
+        epoch: u64,
+    ) -> Result<Vec<ValidatorRebalance>, mongodb::error::Error> {
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "epoch": epoch as i64,
+                    "event_type": { "$in": Self::REBALANCE_EVENT_TYPES.to_vec() },
+                    "tx_error": null,
                 }
-                "InstantUnstakeComponents" => {
-                    filter.insert(
-                        "event_type",
-                        doc! { "$in": ["InstantUnstakeComponents", "InstantUnstakeComponentsV2", "InstantUnstakeComponentsV3"] },
-                    );
+            },
+            // Only RebalanceEvent has decrease components and only DirectedRebalanceEvent has
+            // `decrease_lamports`, `$sum` ignores the missing fields
+            doc! {
+                "$group": {
+                    "_id": "$vote_account",
+                    "rebalances": { "$sum": 1 },
+                    "increase_lamports": { "$sum": "$metadata.increase_lamports" },
+                    "scoring_unstake_lamports": {
+                        "$sum": "$metadata.decrease_components.scoring_unstake_lamports"
+                    },
+                    "instant_unstake_lamports": {
+                        "$sum": "$metadata.decrease_components.instant_unstake_lamports"
+                    },
+                    "stake_deposit_unstake_lamports": {
+                        "$sum": "$metadata.decrease_components.stake_deposit_unstake_lamports"
+                    },
+                    "directed_unstake_lamports": { "$sum": "$metadata.decrease_lamports" },
                 }
-                _ => {
-                    filter.insert("event_type", event_type);
+            },
+            doc! {
+                "$project": {
+                    "_id": 0,
+                    "vote_account": "$_id",
+                    "rebalances": 1,
+                    "increase_lamports": 1,
+                    "scoring_unstake_lamports": 1,
+                    "instant_unstake_lamports": 1,
+                    "stake_deposit_unstake_lamports": 1,
+                    "directed_unstake_lamports": 1,
                 }
-            }
-        }
-        if let Some(vote_account) = vote_account {
-            filter.insert("vote_account", vote_account);
+            },
+            doc! { "$sort": { "vote_account": 1 } },
+        ];
+
+        let mut cursor = self.collection.aggregate(pipeline, None).await?;
+        let mut rebalances = Vec::new();
+        while let Some(result) = cursor.try_next().await? {
+            rebalances.push(bson::from_document(result)?);
         }
-        if let Some(epoch) = epoch {
-            filter.insert("epoch", epoch as i64);
+
+        Ok(rebalances)
+    }
+
+    /// Number of failed transactions that emitted rebalance events in `epoch`
+    pub async fn count_failed_rebalance_transactions(
+        &self,
+        epoch: u64,
+    ) -> Result<u64, mongodb::error::Error> {
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "epoch": epoch as i64,
+                    "event_type": { "$in": Self::REBALANCE_EVENT_TYPES.to_vec() },
+                    "tx_error": { "$ne": null },
+                }
+            },
+            doc! { "$group": { "_id": "$signature" } },
+            doc! { "$count": "failed_transactions" },
+        ];
+
+        let mut cursor = self.collection.aggregate(pipeline, None).await?;
+        match cursor.try_next().await? {
+            Some(result) => Ok(result.get_i32("failed_transactions").unwrap_or_default() as u64),
+            None => Ok(0),
         }
+    }
+}
 
-        let options = mongodb::options::FindOptions::builder()
-            .sort(doc! { "slot": -1 })
-            .limit(limit)
-            .skip(skip)
-            .build();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let mut cursor = self.collection.find(filter, options).await?;
+    fn cursor() -> StewardEventsCursor {
+        StewardEventsCursor {
+            slot: 345_600_000,
+            signature: "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d".to_string(),
+            instruction_idx: 2,
+            event_idx: 1,
+        }
+    }
 
-        let mut events = Vec::new();
-        while let Some(event) = cursor.try_next().await? {
-            events.push(event);
+    #[test]
+    fn test_cursor_roundtrip() {
+        let encoded = cursor().to_string();
+        assert_eq!(
+            encoded,
+            "345600000:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d:2:1"
+        );
+        assert_eq!(encoded.parse::<StewardEventsCursor>(), Ok(cursor()));
+    }
+
+    #[test]
+    fn test_invalid_cursors() {
+        for cursor in [
+            "",
+            "345600000",
+            "345600000:signature:2",
+            "345600000:signature:2:1:0",
+            "slot:signature:2:1",
+            "345600000:signature:-1:1",
+        ] {
+            assert!(
+                cursor.parse::<StewardEventsCursor>().is_err(),
+                "{cursor} parsed"
+            );
         }
+    }
 
-        Ok(events)
+    #[test]
+    fn test_cursor_filter() {
+        let document = cursor().to_document("$lt");
+        let branches = document.get_array("$or").unwrap();
+
+        assert_eq!(branches.len(), 4);
+        assert_eq!(
+            branches[0],
+            Bson::Document(doc! {"slot": {"$lt": 345_600_000_i64}})
+        );
+        assert_eq!(
+            branches[3],
+            Bson::Document(doc! {
+                "slot": 345_600_000_i64,
+                "signature": "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d",
+                "instruction_idx": 2_u32,
+                "event_idx": {"$lt": 1_u32},
+            })
+        );
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        assert_eq!(StewardEventsFilter::default().to_document(), doc! {});
+    }
+
+    #[test]
+    fn test_filter_document() {
+        let filter = StewardEventsFilter {
+            event_types: vec!["ScoreComponents".to_string(), "RebalanceEvent".to_string()],
+            vote_account: Some("vote".to_string()),
+            epoch: Some(800),
+            start_slot: Some(100),
+            end_slot: Some(200),
+            start_time: Some(DateTime::from_millis(1_000)),
+            end_time: None,
+            signer: Some("signer".to_string()),
+            stake_pool: Some("pool".to_string()),
+            has_error: Some(false),
+        };
+
+        assert_eq!(
+            filter.to_document(),
+            doc! {
+                "event_type": {"$in": [
+                    "ScoreComponents",
+                    "ScoreComponentsV2",
+                    "ScoreComponentsV3",
+                    "ScoreComponentsV4",
+                    "RebalanceEvent",
+                ]},
+                "vote_account": "vote",
+                "epoch": 800_i64,
+                "signer": "signer",
+                "stake_pool": "pool",
+                "slot": {"$gte": 100_i64, "$lte": 200_i64},
+                "timestamp": {"$gte": DateTime::from_millis(1_000)},
+                "tx_error": Bson::Null,
+            }
+        );
+    }
+
+    #[test]
+    fn test_filter_failed_transactions() {
+        let filter = StewardEventsFilter {
+            event_types: vec!["InstantUnstakeComponents".to_string()],
+            end_slot: Some(200),
+            has_error: Some(true),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            filter.to_document(),
+            doc! {
+                "event_type": {"$in": [
+                    "InstantUnstakeComponents",
+                    "InstantUnstakeComponentsV2",
+                    "InstantUnstakeComponentsV3",
+                ]},
+                "slot": {"$lte": 200_i64},
+                "tx_error": {"$ne": null},
+            }
+        );
+    }
+
+    #[test]
+    fn test_change_stream_match() {
+        let filter = StewardEventsFilter {
+            event_types: vec!["RebalanceEvent".to_string()],
+            vote_account: Some("vote".to_string()),
+            start_slot: Some(100),
+            has_error: Some(false),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            filter.to_change_stream_match(),
+            doc! {
+                "$match": {
+                    "operationType": "insert",
+                    "fullDocument.event_type": {"$in": ["RebalanceEvent"]},
+                    "fullDocument.vote_account": "vote",
+                    "fullDocument.slot": {"$gte": 100_i64},
+                    "fullDocument.tx_error": Bson::Null,
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn test_empty_change_stream_match() {
+        assert_eq!(
+            StewardEventsFilter::default().to_change_stream_match(),
+            doc! {"$match": {"operationType": "insert"}}
+        );
     }
 }
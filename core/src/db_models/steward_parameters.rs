@@ -0,0 +1,117 @@
+//! DB model for the steward config parameters over time.
+//!
+//! Scores are computed against the parameters of the steward config at the time, which change
+//! over time. The parameters are recorded along with each steward state snapshot, so the score of
+//! a past epoch is explained with the thresholds it was computed with.
+
+use chrono::{serde::ts_seconds, DateTime, Utc};
+use jito_steward::Parameters;
+use mongodb::{
+    bson::{self, doc},
+    options::{FindOneOptions, UpdateOptions},
+    Collection, IndexModel,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{constants::STEWARD_PARAMETERS_COLLECTION_NAME, db_models::unique_index};
+
+/// Steward config parameters scores are computed with
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StewardParameters {
+    /// Epoch the parameters were read in
+    pub epoch: u64,
+
+    /// Slot the parameters were read at
+    pub slot: u64,
+
+    /// Epochs of MEV commission history checked
+    pub mev_commission_range: u16,
+
+    /// Epochs of vote credits history checked
+    pub epoch_credits_range: u16,
+
+    /// Epochs of commission history checked
+    pub commission_range: u16,
+
+    /// Minimum ratio of vote credits to the cluster in each epoch
+    pub scoring_delinquency_threshold_ratio: f64,
+
+    /// Maximum MEV commission in BPS
+    pub mev_commission_bps_threshold: u16,
+
+    /// Maximum commission in %
+    pub commission_threshold: u8,
+
+    /// Maximum commission in % over the validator history
+    pub historical_commission_threshold: u8,
+
+    /// Epochs of priority fee commission history checked
+    pub priority_fee_lookback_epochs: u8,
+
+    /// Maximum average priority fee commission in BPS
+    pub priority_fee_max_commission_bps: u16,
+
+    /// Time the parameters were read
+    #[serde(with = "ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+}
+
+impl StewardParameters {
+    pub fn from_parameters(parameters: &Parameters, epoch: u64, slot: u64) -> Self {
+        Self {
+            epoch,
+            slot,
+            mev_commission_range: parameters.mev_commission_range,
+            epoch_credits_range: parameters.epoch_credits_range,
+            commission_range: parameters.commission_range,
+            scoring_delinquency_threshold_ratio: parameters.scoring_delinquency_threshold_ratio,
+            mev_commission_bps_threshold: parameters.mev_commission_bps_threshold,
+            commission_threshold: parameters.commission_threshold,
+            historical_commission_threshold: parameters.historical_commission_threshold,
+            priority_fee_lookback_epochs: parameters.priority_fee_lookback_epochs,
+            priority_fee_max_commission_bps: parameters.priority_fee_max_commission_bps,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct StewardParametersStore {
+    collection: Collection<StewardParameters>,
+}
+
+impl StewardParametersStore {
+    pub const COLLECTION: &'static str = STEWARD_PARAMETERS_COLLECTION_NAME;
+
+    pub fn new(collection: Collection<StewardParameters>) -> Self {
+        Self { collection }
+    }
+
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![unique_index("slot", doc! {"slot": -1})]
+    }
+
+    /// Record the parameters read at a slot
+    pub async fn upsert(
+        &self,
+        parameters: &StewardParameters,
+    ) -> Result<(), mongodb::error::Error> {
+        let update = doc! { "$set": bson::to_document(parameters)? };
+        let filter = doc! { "slot": parameters.slot as i64 };
+        let options = UpdateOptions::builder().upsert(true).build();
+        self.collection.update_one(filter, update, options).await?;
+        Ok(())
+    }
+
+    /// Parameters in effect at `slot`: the latest recorded at or before it
+    pub async fn find_in_effect(
+        &self,
+        slot: u64,
+    ) -> Result<Option<StewardParameters>, mongodb::error::Error> {
+        let options = FindOneOptions::builder().sort(doc! {"slot": -1}).build();
+        self.collection
+            .find_one(doc! {"slot": {"$lte": slot as i64}}, options)
+            .await
+    }
+}
@@ -0,0 +1,245 @@
+//! DB model for snapshots of the steward state.
+//!
+//! A snapshot is one document per pool validator, all sharing the `slot` the steward state was
+//! read at.
+
+use chrono::{serde::ts_seconds, DateTime, Utc};
+use futures::TryStreamExt;
+use jito_steward::constants::MAX_VALIDATORS;
+use mongodb::{
+    bson::{self, doc},
+    options::{FindOneOptions, FindOptions, UpdateOptions},
+    Collection, IndexModel,
+};
+use serde::{Deserialize, Serialize};
+use stakenet_sdk::models::aggregate_accounts::AllStewardAccounts;
+
+use crate::{
+    constants::STEWARD_STATE_SNAPSHOTS_COLLECTION_NAME,
+    db_models::{index, unique_index},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StewardStateSnapshot {
+    /// Epoch the state was read in
+    pub epoch: u64,
+
+    /// Slot the state was read at
+    pub slot: u64,
+
+    /// Time the state was read
+    #[serde(with = "ts_seconds")]
+    pub timestamp: DateTime<Utc>,
+
+    /// Phase of the steward state machine
+    pub state_tag: String,
+
+    /// Vote account of the validator
+    pub vote_account: String,
+
+    /// Index of the validator in the stake pool validator list
+    pub validator_list_index: u32,
+
+    /// Position in the steward ranking by raw score, 0 being the best, `None` if not ranked yet
+    pub rank: Option<u32>,
+
+    /// Final score
+    pub score: u64,
+
+    /// Raw score the validators are ranked by
+    ///
+    /// There is no yield score: the steward state at the pinned stakenet rev ranks validators by
+    /// raw score and no longer keeps the yield score of earlier steward versions.
+    pub raw_score: u64,
+
+    /// Numerator of the share of the pool targeted to the validator
+    pub delegation_numerator: u32,
+
+    /// Denominator of the share of the pool targeted to the validator
+    pub delegation_denominator: u32,
+
+    /// Target delegation in lamports, given the pool total lamports at `slot`
+    pub target_delegation_lamports: u64,
+
+    /// Active stake of the validator stake account in lamports
+    pub active_stake_lamports: u64,
+
+    /// Whether the validator is flagged for instant unstake
+    pub instant_unstake: bool,
+}
+
+impl StewardStateSnapshot {
+    /// Snapshot every pool validator of the steward state
+    pub fn from_steward_accounts(
+        accounts: &AllStewardAccounts,
+        epoch: u64,
+        slot: u64,
+    ) -> Vec<Self> {
+        let state = &accounts.state_account.state;
+        let num_validators = (state.num_pool_validators as usize)
+            .min(accounts.validator_list_account.validators.len());
+        let ranks = score_ranks(&state.sorted_raw_score_indices, num_validators);
+        let total_lamports = accounts.stake_pool_account.total_lamports;
+        let timestamp = Utc::now();
+        let state_tag = format!("{:?}", state.state_tag);
+
+        (0..num_validators)
+            .map(|index| {
+                let validator = &accounts.validator_list_account.validators[index];
+                let delegation = state.delegations[index];
+                let target_delegation_lamports = if delegation.denominator == 0 {
+                    0
+                } else {
+                    (total_lamports as u128 * delegation.numerator as u128
+                        / delegation.denominator as u128) as u64
+                };
+
+                Self {
+                    epoch,
+                    slot,
+                    timestamp,
+                    state_tag: state_tag.clone(),
+                    vote_account: validator.vote_account_address.to_string(),
+                    validator_list_index: index as u32,
+                    rank: ranks[index],
+                    score: u64::from(state.scores[index]),
+                    raw_score: state.raw_scores[index],
+                    delegation_numerator: delegation.numerator,
+                    delegation_denominator: delegation.denominator,
+                    target_delegation_lamports,
+                    active_stake_lamports: validator.active_stake_lamports.into(),
+                    instant_unstake: state.instant_unstake.get(index).unwrap_or(false),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Rank of each of the first `num_validators` validator list entries, from the validator list
+/// indices sorted by descending score
+fn score_ranks(sorted_indices: &[u16], num_validators: usize) -> Vec<Option<u32>> {
+    let mut ranks = vec![None; num_validators];
+    for (rank, &index) in sorted_indices.iter().take(num_validators).enumerate() {
+        let index = index as usize;
+        // Unused slots hold a sentinel
+        if index < num_validators && index < MAX_VALIDATORS {
+            ranks[index] = Some(rank as u32);
+        }
+    }
+
+    ranks
+}
+
+#[derive(Clone)]
+pub struct StewardStateSnapshotStore {
+    collection: Collection<StewardStateSnapshot>,
+}
+
+impl StewardStateSnapshotStore {
+    pub const COLLECTION: &'static str = STEWARD_STATE_SNAPSHOTS_COLLECTION_NAME;
+
+    pub fn new(collection: Collection<StewardStateSnapshot>) -> Self {
+        Self { collection }
+    }
+
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            unique_index("slot_vote_account", doc! {"slot": 1, "vote_account": 1}),
+            index(doc! {"vote_account": 1, "slot": -1}),
+            index(doc! {"epoch": 1, "slot": -1}),
+        ]
+    }
+
+    /// Upsert a snapshot by (slot, vote account), in order, so a snapshot taken again at the same
+    /// slot replaces the previous one without ever leaving it missing
+    pub async fn upsert_snapshot(
+        &self,
+        snapshot: &[StewardStateSnapshot],
+    ) -> Result<(), mongodb::error::Error> {
+        let options = UpdateOptions::builder().upsert(true).build();
+        for validator_snapshot in snapshot {
+            let update = doc! { "$set": bson::to_document(validator_snapshot)? };
+            let filter = doc! {
+                "slot": validator_snapshot.slot as i64,
+                "vote_account": &validator_snapshot.vote_account,
+            };
+            self.collection
+                .update_one(filter, update, options.clone())
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Latest snapshot, of `epoch` when set, ordered by rank
+    pub async fn find_latest(
+        &self,
+        epoch: Option<u64>,
+    ) -> Result<Vec<StewardStateSnapshot>, mongodb::error::Error> {
+        let filter = epoch.map(|epoch| doc! {"epoch": epoch as i64});
+        let options = FindOneOptions::builder().sort(doc! {"slot": -1}).build();
+        let Some(latest) = self.collection.find_one(filter, options).await? else {
+            return Ok(vec![]);
+        };
+
+        let mut snapshot: Vec<StewardStateSnapshot> = self
+            .collection
+            .find(doc! {"slot": latest.slot as i64}, None)
+            .await?
+            .try_collect()
+            .await?;
+        // Unranked validators last
+        snapshot.sort_by_key(|s| (s.rank.is_none(), s.rank, s.validator_list_index));
+
+        Ok(snapshot)
+    }
+
+    /// Snapshots of a validator, newest first
+    pub async fn find_by_vote_account(
+        &self,
+        vote_account: &str,
+        epoch: Option<u64>,
+        limit: i64,
+    ) -> Result<Vec<StewardStateSnapshot>, mongodb::error::Error> {
+        let mut filter = doc! {"vote_account": vote_account};
+        if let Some(epoch) = epoch {
+            filter.insert("epoch", epoch as i64);
+        }
+        let options = FindOptions::builder()
+            .sort(doc! {"slot": -1})
+            .limit(limit)
+            .build();
+
+        self.collection
+            .find(filter, options)
+            .await?
+            .try_collect()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_ranks() {
+        let mut sorted_indices = [MAX_VALIDATORS as u16; 8];
+        sorted_indices[..4].copy_from_slice(&[2, 0, 3, 1]);
+
+        assert_eq!(
+            score_ranks(&sorted_indices, 4),
+            vec![Some(1), Some(3), Some(0), Some(2)]
+        );
+    }
+
+    #[test]
+    fn test_score_ranks_skips_sentinels() {
+        let sorted_indices = [1, MAX_VALIDATORS as u16, 0];
+
+        assert_eq!(
+            score_ranks(&sorted_indices, 3),
+            vec![Some(2), Some(0), None]
+        );
+    }
+}
@@ -8,15 +8,28 @@ use std::{collections::HashMap, str::FromStr};
 
 use chrono::{serde::ts_seconds, DateTime, Utc};
 use futures::TryStreamExt;
-use mongodb::{bson, bson::doc, options::FindOneOptions, Collection};
+use mongodb::{bson, bson::doc, options::FindOneOptions, Collection, IndexModel};
 use serde::{Deserialize, Serialize};
 use solana_pubkey::Pubkey;
 
 use crate::{
-    constants::VALIDATOR_COLLECTION_NAME, db_models::error::DataStoreError, fetcher::ChainData,
+    constants::VALIDATOR_COLLECTION_NAME,
+    db_models::{error::DataStoreError, index},
+    fetcher::{ChainData, InflationRewards},
     validators_app::ValidatorsAppResponseEntry,
 };
 
+/// Whether the inflation rewards of a [`Validator`] record are estimated or paid
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InflationRewardsStatus {
+    /// The epoch is in progress, only `inflation_rewards_lamports` (the estimate) is recorded
+    Estimated,
+
+    /// The rewards paid for the epoch are recorded in the `paid_*` fields
+    Paid,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct Validator {
     pub active_stake: Option<u64>,
@@ -46,6 +59,9 @@ pub struct Validator {
 
     /// Whether or not running BAM client
     pub running_bam: Option<bool>,
+
+    /// Validator client, as named by validators.app or by the ValidatorHistory client type
+    pub software_client: Option<String>,
     pub software_version: Option<String>,
     pub software_version_score: Option<i64>,
     pub skipped_slot_percent: Option<String>,
@@ -62,7 +78,24 @@ pub struct Validator {
     pub vote_account: String,
     pub vote_credit_proportion: f64,
     pub www_url: Option<String>,
+
+    /// Inflation rewards estimated while the epoch is in progress
     pub inflation_rewards_lamports: u64,
+
+    /// Whether the paid inflation rewards are recorded yet, `None` for records written before
+    /// the status was tracked
+    #[serde(default)]
+    pub inflation_rewards_status: Option<InflationRewardsStatus>,
+
+    /// Inflation rewards paid for the epoch, the sum of `vote_account_inflation_rewards_lamports`
+    /// and `target_pool_inflation_rewards_lamports`. `None` until rewards are paid.
+    pub paid_inflation_rewards_lamports: Option<u64>,
+
+    /// Inflation rewards (commission) paid to the vote account
+    pub vote_account_inflation_rewards_lamports: Option<u64>,
+
+    /// Inflation rewards paid to the target pool's stake accounts for this validator
+    pub target_pool_inflation_rewards_lamports: Option<u64>,
 }
 
 impl Validator {
@@ -100,6 +133,7 @@ impl Validator {
             root_distance_score: validators_app_entry.root_distance_score,
             running_jito: on_chain_data.running_jito,
             running_bam: Some(on_chain_data.running_bam),
+            software_client: validators_app_entry.software_client.clone(),
             software_version: validators_app_entry.software_version.clone(),
             software_version_score: validators_app_entry.software_version_score,
             skipped_slot_percent: validators_app_entry.skipped_slot_percent.clone(),
@@ -116,6 +150,10 @@ impl Validator {
             vote_credit_proportion: on_chain_data.vote_credit_proportion,
             www_url: validators_app_entry.www_url.clone(),
             inflation_rewards_lamports: on_chain_data.inflation_rewards_lamports,
+            inflation_rewards_status: Some(InflationRewardsStatus::Estimated),
+            paid_inflation_rewards_lamports: None,
+            vote_account_inflation_rewards_lamports: None,
+            target_pool_inflation_rewards_lamports: None,
         }
     }
 
@@ -149,6 +187,14 @@ impl ValidatorStore {
         Self { collection }
     }
 
+    /// Indexes backing the queries of this store
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            index(doc! {"epoch": 1, "vote_account": 1}),
+            index(doc! {"running_jito": 1, "epoch": 1}),
+        ]
+    }
+
     pub async fn find(
         &self,
         epoch: u64,
@@ -184,6 +230,43 @@ impl ValidatorStore {
         Ok(validators_map.into_values().collect::<Vec<Validator>>())
     }
 
+    /// Find validators in `epoch` without paid inflation rewards recorded
+    pub async fn find_without_paid_inflation_rewards(
+        &self,
+        epoch: u64,
+    ) -> Result<Vec<Validator>, DataStoreError> {
+        let filter = doc! {
+            "epoch": epoch as u32,
+            "paid_inflation_rewards_lamports": null,
+        };
+        let cursor = self.collection.find(filter, None).await?;
+        Ok(cursor.try_collect().await?)
+    }
+
+    /// Record the paid inflation rewards of a validator next to its estimate
+    pub async fn update_inflation_rewards(
+        &self,
+        epoch: u64,
+        vote_account: &str,
+        rewards: &InflationRewards,
+    ) -> Result<(), DataStoreError> {
+        let filter = doc! {
+            "epoch": epoch as u32,
+            "vote_account": vote_account,
+        };
+        let update = doc! {
+            "$set": {
+                // InflationRewardsStatus::Paid
+                "inflation_rewards_status": "paid",
+                "paid_inflation_rewards_lamports": rewards.total_lamports() as i64,
+                "vote_account_inflation_rewards_lamports": rewards.vote_account_lamports as i64,
+                "target_pool_inflation_rewards_lamports": rewards.stake_pool_lamports as i64,
+            }
+        };
+        self.collection.update_one(filter, update, None).await?;
+        Ok(())
+    }
+
     pub async fn get_highest_epoch(&self) -> Result<u64, DataStoreError> {
         let find_options = FindOneOptions::builder().sort(doc! {"epoch": -1}).build();
         let validator = self
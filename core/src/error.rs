@@ -3,7 +3,19 @@ use thiserror::Error;
 #[derive(Debug, Error)]
 pub enum KobeCoreError {
     #[error(
-        "Invalid cluster value: '{0}'. Expected 'testnet', 'mainnet', 'mainnet-beta', or 'devnet'"
+        "Invalid cluster value: '{0}'. Expected 'testnet', 'mainnet', 'mainnet-beta', 'devnet', or 'localnet'"
     )]
     InvalidCluster(String),
+
+    #[error("Invalid validator metadata source: '{0}'. Expected 'validators-app' or 'on-chain'")]
+    InvalidValidatorMetadataSource(String),
+
+    #[error("Invalid cluster config: {0}")]
+    InvalidClusterConfig(String),
+
+    #[error("Cluster config has no profile for '{0}'")]
+    MissingClusterProfile(String),
+
+    #[error("Invalid BAM eligibility rules: {0}")]
+    InvalidBamEligibilityRules(String),
 }
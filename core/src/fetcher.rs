@@ -1,29 +1,32 @@
 use std::{
     collections::{HashMap, HashSet},
+    future::Future,
+    num::NonZeroU32,
     str::FromStr,
     sync::Arc,
+    time::Instant,
 };
 
 use anchor_lang::AccountDeserialize;
+use futures::stream::{self, StreamExt};
 use jito_priority_fee_distribution::state::PriorityFeeDistributionAccount;
 use jito_priority_fee_distribution_sdk::derive_priority_fee_distribution_account_address;
 use jito_tip_distribution::state::TipDistributionAccount;
 use log::*;
+use solana_account::Account;
 use solana_client::{nonblocking::rpc_client::RpcClient, rpc_response::RpcVoteAccountStatus};
+use solana_metrics::datapoint_info;
 use solana_pubkey::Pubkey;
-use spl_stake_pool::state::ValidatorStakeInfo;
+use spl_stake_pool::{
+    find_stake_program_address, find_transient_stake_program_address, state::ValidatorStakeInfo,
+};
 use spl_stake_pool_cli::client::get_validator_list;
 use stakenet_sdk::utils::accounts::{get_all_validator_history_accounts, get_directed_stake_meta};
 use validator_history::ValidatorHistory;
 
 use crate::{
-    client_type::ClientType,
-    constants::{
-        PRIORITY_FEE_DISTRIBUTION_PROGRAM, TIP_DISTRIBUTION_PROGRAM_MAINNET,
-        TIP_DISTRIBUTION_PROGRAM_TESTNET, VALIDATOR_HISTORY_PROGRAM_MAINNET,
-        VALIDATOR_HISTORY_PROGRAM_TESTNET,
-    },
-    validators_app::{Cluster, ValidatorsAppResponseEntry},
+    client_type::ClientType, cluster_config::ClusterConfig,
+    validators_app::ValidatorsAppResponseEntry,
 };
 
 mod jito_tip_distribution_sdk {
@@ -48,6 +51,66 @@ mod jito_tip_distribution_sdk {
 
 type Error = Box<dyn std::error::Error>;
 
+/// Max accounts per `getMultipleAccounts` request
+const MULTIPLE_ACCOUNTS_CHUNK_SIZE: usize = 100;
+
+/// Max in-flight `getMultipleAccounts` requests per data source
+const MAX_CONCURRENT_RPC_REQUESTS: usize = 8;
+
+/// Used to estimate inflation rewards for the in-progress epoch, hardcoded from cogentcrypto.io.
+/// Paid epochs use [`fetch_inflation_rewards`] instead.
+const ESTIMATED_EPOCHS_PER_YEAR: f64 = 163.;
+
+/// Actual inflation rewards paid for a validator in an epoch
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InflationRewards {
+    /// Rewards (commission) paid to the validator's vote account
+    pub vote_account_lamports: u64,
+
+    /// Rewards paid to the stake pool's stake accounts delegated to the validator
+    pub stake_pool_lamports: u64,
+}
+
+impl InflationRewards {
+    pub fn total_lamports(&self) -> u64 {
+        self.vote_account_lamports
+            .saturating_add(self.stake_pool_lamports)
+    }
+}
+
+/// Estimate the inflation rewards of a validator for the in-progress epoch from its stake and
+/// vote credits relative to the cluster average
+fn estimate_inflation_rewards(
+    inflation_rate: f64,
+    staked_lamports: f64,
+    vote_credit_proportion: f64,
+) -> u64 {
+    (inflation_rate / ESTIMATED_EPOCHS_PER_YEAR * staked_lamports * vote_credit_proportion) as u64
+}
+
+/// Add the rewards paid to `reward_addresses`, given as (vote account, address, is vote
+/// account), to the validators' [`InflationRewards`]. Addresses without a reward add nothing, so a
+/// validator without a reward on any address is recorded as paid zero.
+fn add_inflation_rewards(
+    rewards_map: &mut HashMap<Pubkey, InflationRewards>,
+    reward_addresses: &[(Pubkey, Pubkey, bool)],
+    reward_lamports: &[Option<u64>],
+) {
+    for ((vote_account, _, is_vote_account), lamports) in
+        core::iter::zip(reward_addresses, reward_lamports)
+    {
+        let entry = rewards_map.entry(*vote_account).or_default();
+        let Some(lamports) = lamports else {
+            continue;
+        };
+        if *is_vote_account {
+            entry.vote_account_lamports = entry.vote_account_lamports.saturating_add(*lamports);
+        } else {
+            entry.stake_pool_lamports = entry.stake_pool_lamports.saturating_add(*lamports);
+        }
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct ChainData {
     /// MEV commission BPS
@@ -63,7 +126,9 @@ pub struct ChainData {
     pub vote_credit_proportion: f64,
     pub stake_info: Option<ValidatorStakeInfo>,
     pub total_staked_lamports: u64,
+    /// Estimated inflation rewards of the in-progress epoch
     pub inflation_rewards_lamports: u64,
+
     pub priority_fee_commission_bps: u16,
     pub priority_fee_revenue_lamports: u64,
 
@@ -74,28 +139,6 @@ pub struct ChainData {
     pub jito_directed_stake_lamports: u64,
 }
 
-pub fn get_tip_distribution_program_id(cluster: &Cluster) -> Pubkey {
-    // These seem to be in flux
-    match cluster {
-        Cluster::Devnet => unimplemented!(),
-        Cluster::Testnet => Pubkey::from_str(TIP_DISTRIBUTION_PROGRAM_TESTNET).unwrap(),
-        Cluster::MainnetBeta => Pubkey::from_str(TIP_DISTRIBUTION_PROGRAM_MAINNET).unwrap(),
-    }
-}
-
-/// Get validator history program ID
-pub fn get_validator_history_program_id(cluster: &Cluster) -> Pubkey {
-    match cluster {
-        Cluster::Devnet => unimplemented!(),
-        Cluster::Testnet => Pubkey::from_str(VALIDATOR_HISTORY_PROGRAM_TESTNET).unwrap(),
-        Cluster::MainnetBeta => Pubkey::from_str(VALIDATOR_HISTORY_PROGRAM_MAINNET).unwrap(),
-    }
-}
-
-pub fn get_priority_fee_distribution_program_id() -> solana_pubkey::Pubkey {
-    solana_pubkey::Pubkey::from_str(PRIORITY_FEE_DISTRIBUTION_PROGRAM).unwrap()
-}
-
 /// Fetches on-chain data for a set of validators
 ///
 /// # Overview
@@ -117,7 +160,8 @@ pub fn get_priority_fee_distribution_program_id() -> solana_pubkey::Pubkey {
 /// - **Vote accounts**: Current validator vote account information
 /// - **Vote credits**: Performance metrics based on voting behavior
 /// - **Staking amounts**: Total lamports staked with each validator
-/// - **Inflation rewards**: Calculated based on stake and performance
+/// - **Inflation rewards**: Estimated based on stake and performance, since `epoch` is expected
+///   to be the in-progress epoch. See [`fetch_inflation_rewards`] for paid rewards.
 ///
 /// ## Jito Client Detection
 ///
@@ -129,27 +173,80 @@ pub fn get_priority_fee_distribution_program_id() -> solana_pubkey::Pubkey {
 ///
 /// - If BAM validator set exists and not empty: check if validator identity is in the set
 /// - Otherwise: fall back to client_type check from validator history
+///
+/// ## Performance
+///
+/// Data sources are fetched concurrently, distribution accounts are read with chunked
+/// `getMultipleAccounts` calls (see [`get_multiple_accounts_chunked`]), and per-validator lookups
+/// go through maps keyed by vote account. The duration of each source is reported as a
+/// `chain_data_fetch` datapoint.
 pub async fn fetch_chain_data(
     validators: &[ValidatorsAppResponseEntry],
     bam_validator_set: HashSet<String>,
     rpc_client: Arc<RpcClient>,
-    cluster: &Cluster,
+    cluster_config: &ClusterConfig,
     epoch: u64,
     validator_list_pubkey: &Pubkey,
-    steward_config_pubkey: &Pubkey,
 ) -> Result<HashMap<Pubkey, ChainData>, Error> {
-    // Fetch on-chain data
-    let tip_distributions =
-        fetch_tip_distribution_accounts(validators, &rpc_client, cluster, epoch).await?;
-    let priority_fee_distributions =
-        fetch_priority_fee_distribution_accounts(validators, &rpc_client, epoch).await?;
-    let vote_accounts = rpc_client.get_vote_accounts().await?;
+    // Independent data sources are fetched concurrently
+    let (
+        tip_distributions,
+        priority_fee_distributions,
+        vote_accounts,
+        staked_validators,
+        inflation_rate,
+        validator_histories,
+        directed_stake_meta,
+    ) = tokio::join!(
+        timed(
+            "tip_distribution",
+            fetch_tip_distribution_accounts(validators, &rpc_client, cluster_config, epoch),
+        ),
+        timed(
+            "priority_fee_distribution",
+            fetch_priority_fee_distribution_accounts(
+                validators,
+                &rpc_client,
+                cluster_config,
+                epoch,
+            ),
+        ),
+        timed("vote_accounts", rpc_client.get_vote_accounts()),
+        timed(
+            "validator_list",
+            get_validator_list(&rpc_client, validator_list_pubkey),
+        ),
+        timed("inflation_rate", rpc_client.get_inflation_rate()),
+        timed(
+            "validator_history",
+            fetch_validator_history_accounts(
+                &rpc_client,
+                cluster_config.validator_history_program_id,
+            ),
+        ),
+        timed(
+            "directed_stake_meta",
+            get_directed_stake_meta(
+                rpc_client.clone(),
+                &cluster_config.steward_config,
+                &cluster_config.steward_program_id,
+            ),
+        ),
+    );
+
+    let tip_distributions = tip_distributions?;
+    let priority_fee_distributions = priority_fee_distributions?;
+    let vote_accounts = vote_accounts?;
     let (global_average, vote_credits_map) = fetch_vote_credits(&vote_accounts)?;
-
     let total_staked_lamports = fetch_total_staked_lamports(&vote_accounts);
-
-    let staked_validators = get_validator_list(&rpc_client, validator_list_pubkey).await?;
-    let inflation_rate = match rpc_client.get_inflation_rate().await {
+    let staked_lamports_map = fetch_staked_lamports_map(&vote_accounts);
+
+    let stake_info_map: HashMap<Pubkey, ValidatorStakeInfo> = staked_validators?
+        .validators
+        .into_iter()
+        .map(|info| (info.vote_account_address, info))
+        .collect();
+    let inflation_rate = match inflation_rate {
         Ok(rate) => rate.total,
         Err(e) => {
             error!("Failed to fetch inflation rate: {e:#?}");
@@ -157,16 +254,13 @@ pub async fn fetch_chain_data(
         }
     };
 
-    let validator_history_program_id = get_validator_history_program_id(cluster);
-    let validator_histories =
-        fetch_validator_history_accounts(&rpc_client, validator_history_program_id).await?;
+    let validator_histories = validator_histories?;
 
-    let directed_stake_meta = get_directed_stake_meta(
-        rpc_client.clone(),
-        steward_config_pubkey,
-        &jito_steward::id(),
-    )
-    .await?;
+    let directed_stake_map: HashMap<Pubkey, u64> = directed_stake_meta?
+        .targets
+        .iter()
+        .map(|target| (target.vote_pubkey, target.total_staked_lamports))
+        .collect();
 
     Ok(HashMap::from_iter(validators.iter().map(|v| {
         let vote_account = v.vote_account;
@@ -217,26 +311,19 @@ pub async fn fetch_chain_data(
             };
         let vote_credits = *vote_credits_map.get(&vote_account).unwrap_or(&0.);
         let vote_credit_proportion = vote_credits / global_average;
-        let stake_info = staked_validators
-            .validators
-            .clone()
-            .into_iter()
-            .find(|info| v.vote_account == info.vote_account_address);
-
-        // hardcoded from cogentcrypto.io
-        let epochs_per_year = 163.;
+        let stake_info = stake_info_map.get(&vote_account).copied();
 
-        let staked_amount =
-            fetch_staked_lamports_for_validator(&vote_accounts, &vote_account) as f64;
+        let staked_amount = staked_lamports_map
+            .get(&vote_account)
+            .copied()
+            .unwrap_or_default() as f64;
 
         let inflation_rewards_lamports =
-            inflation_rate / epochs_per_year * staked_amount * vote_credit_proportion;
+            estimate_inflation_rewards(inflation_rate, staked_amount, vote_credit_proportion);
 
-        let (jito_directed_stake_target, jito_directed_stake_lamports) = directed_stake_meta
-            .targets
-            .iter()
-            .find(|target| target.vote_pubkey.eq(&v.vote_account))
-            .map(|target| (true, target.total_staked_lamports))
+        let (jito_directed_stake_target, jito_directed_stake_lamports) = directed_stake_map
+            .get(&vote_account)
+            .map(|lamports| (true, *lamports))
             .unwrap_or_default();
 
         let data = ChainData {
@@ -247,7 +334,7 @@ pub async fn fetch_chain_data(
             vote_credit_proportion,
             stake_info,
             total_staked_lamports,
-            inflation_rewards_lamports: inflation_rewards_lamports as u64,
+            inflation_rewards_lamports,
             priority_fee_commission_bps,
             priority_fee_revenue_lamports,
             jito_directed_stake_target,
@@ -261,40 +348,38 @@ pub async fn fetch_chain_data(
 pub async fn fetch_tip_distribution_accounts(
     validators: &[ValidatorsAppResponseEntry],
     rpc_client: &RpcClient,
-    cluster: &Cluster,
+    cluster_config: &ClusterConfig,
     epoch: u64,
 ) -> Result<HashMap<Pubkey, TipDistributionAccount>, Error> {
     // Set the commission rate on each validator if it exists. That means this validator is running jito this epoch
     // Commission rate also used for scoring
-    // Done in batches for efficiency
+    let tip_distribution_program_id = cluster_config.tip_distribution_program_id;
+    let pubkeys = validators
+        .iter()
+        .map(|v| {
+            jito_tip_distribution_sdk::derive_tip_distribution_account_address(
+                &tip_distribution_program_id,
+                &v.vote_account,
+                epoch,
+            )
+            .0
+        })
+        .collect::<Vec<Pubkey>>();
+    let accounts = get_multiple_accounts_chunked(rpc_client, &pubkeys).await;
+
     let mut commission_map = HashMap::new();
-    for chunk in validators.chunks(100) {
-        let pubkeys = chunk
-            .iter()
-            .map(|c| {
-                jito_tip_distribution_sdk::derive_tip_distribution_account_address(
-                    &get_tip_distribution_program_id(cluster),
-                    &c.vote_account,
-                    epoch,
-                )
-                .0
-            })
-            .collect::<Vec<Pubkey>>();
-        let response = rpc_client.get_multiple_accounts(&pubkeys).await;
-        if let Ok(result) = response {
-            for (v, acc) in core::iter::zip(chunk, result) {
-                if let Some(account) = acc {
-                    if get_tip_distribution_program_id(cluster) != account.owner {
-                        warn!("Validator {} may be trying to mess with their Tip Distribution Account", v.vote_account);
-                        continue;
-                    }
-                    let tip_distribution =
-                        TipDistributionAccount::try_deserialize(&mut account.data.as_slice())?;
-                    commission_map.insert(v.vote_account, tip_distribution);
-                }
+    for (v, acc) in core::iter::zip(validators, accounts) {
+        if let Some(account) = acc {
+            if tip_distribution_program_id != account.owner {
+                warn!(
+                    "Validator {} may be trying to mess with their Tip Distribution Account",
+                    v.vote_account
+                );
+                continue;
             }
-        } else if let Err(e) = response {
-            error!("Rpc error: {e:#?}");
+            let tip_distribution =
+                TipDistributionAccount::try_deserialize(&mut account.data.as_slice())?;
+            commission_map.insert(v.vote_account, tip_distribution);
         }
     }
     Ok(commission_map)
@@ -303,52 +388,113 @@ pub async fn fetch_tip_distribution_accounts(
 pub async fn fetch_priority_fee_distribution_accounts(
     validators: &[ValidatorsAppResponseEntry],
     rpc_client: &RpcClient,
+    cluster_config: &ClusterConfig,
     epoch: u64,
 ) -> Result<HashMap<Pubkey, PriorityFeeDistributionAccount>, Error> {
     // Set the commission rate on each validator if it exists. That means this validator is running jito this epoch
     // Commission rate also used for scoring
-    // Done in batches for efficiency
+    let priority_fee_distribution_program_id = cluster_config.priority_fee_distribution_program_id;
+    let pubkeys = validators
+        .iter()
+        .map(|v| {
+            derive_priority_fee_distribution_account_address(
+                &priority_fee_distribution_program_id,
+                &v.vote_account,
+                epoch,
+            )
+            .0
+        })
+        .collect::<Vec<Pubkey>>();
+    let accounts = get_multiple_accounts_chunked(rpc_client, &pubkeys).await;
+
     let mut commission_map = HashMap::new();
-    for chunk in validators.chunks(100) {
-        let pubkeys = chunk
-            .iter()
-            .map(|c| {
-                let vote_account_pubkey =
-                    solana_pubkey::Pubkey::from_str(&c.vote_account.to_string()).unwrap();
-                let pfda_pubkey: solana_pubkey::Pubkey =
-                    derive_priority_fee_distribution_account_address(
-                        &get_priority_fee_distribution_program_id(),
-                        &vote_account_pubkey,
-                        epoch,
-                    )
-                    .0;
-                Pubkey::from_str(&pfda_pubkey.to_string()).unwrap()
-            })
-            .collect::<Vec<Pubkey>>();
-        let response = rpc_client.get_multiple_accounts(&pubkeys).await;
-        if let Ok(result) = response {
-            for (v, acc) in core::iter::zip(chunk, result) {
-                if let Some(account) = acc {
-                    let program_pubkey =
-                        Pubkey::from_str(&get_priority_fee_distribution_program_id().to_string())
-                            .unwrap();
-                    if program_pubkey != account.owner {
-                        warn!("Validator {} may be trying to mess with their Tip Distribution Account", v.vote_account);
-                        continue;
-                    }
-                    let tip_distribution = PriorityFeeDistributionAccount::try_deserialize(
-                        &mut account.data.as_slice(),
-                    )?;
-                    commission_map.insert(v.vote_account, tip_distribution);
-                }
+    for (v, acc) in core::iter::zip(validators, accounts) {
+        if let Some(account) = acc {
+            if priority_fee_distribution_program_id != account.owner {
+                warn!(
+                    "Validator {} may be trying to mess with their Priority Fee Distribution Account",
+                    v.vote_account
+                );
+                continue;
             }
-        } else if let Err(e) = response {
-            error!("Rpc error: {e:#?}");
+            let priority_fee_distribution =
+                PriorityFeeDistributionAccount::try_deserialize(&mut account.data.as_slice())?;
+            commission_map.insert(v.vote_account, priority_fee_distribution);
         }
     }
     Ok(commission_map)
 }
 
+/// Fetch the inflation rewards paid for `epoch` to each validator's vote account and to the stake
+/// pool's stake accounts delegated to it
+///
+/// # Overview
+///
+/// Rewards for an epoch are paid at the start of the next one, so this only succeeds once
+/// `epoch` has ended and its reward distribution has completed. Any RPC error is returned so the
+/// caller can retry later rather than record partial rewards.
+///
+/// The pool's stake accounts (validator stake and transient stake) are derived from the current
+/// validator list, so stake that moved since `epoch` is attributed to its current validator.
+///
+/// Every vote account gets an entry; validators none of whose addresses returned a reward (no
+/// stake, no credits) were paid zero.
+pub async fn fetch_inflation_rewards(
+    rpc_client: &RpcClient,
+    vote_accounts: &[Pubkey],
+    cluster_config: &ClusterConfig,
+    validator_list_pubkey: &Pubkey,
+    epoch: u64,
+) -> Result<HashMap<Pubkey, InflationRewards>, Error> {
+    let validator_list = get_validator_list(rpc_client, validator_list_pubkey).await?;
+    let validator_stake_infos: HashMap<Pubkey, &ValidatorStakeInfo> = validator_list
+        .validators
+        .iter()
+        .map(|info| (info.vote_account_address, info))
+        .collect();
+
+    // (vote account, address, is vote account)
+    let mut reward_addresses: Vec<(Pubkey, Pubkey, bool)> = Vec::new();
+    for vote_account in vote_accounts {
+        reward_addresses.push((*vote_account, *vote_account, true));
+
+        if let Some(info) = validator_stake_infos.get(vote_account) {
+            let (stake_account, _) = find_stake_program_address(
+                &cluster_config.stake_pool_program_id,
+                vote_account,
+                &cluster_config.stake_pool_address,
+                NonZeroU32::new(info.validator_seed_suffix.into()),
+            );
+            reward_addresses.push((*vote_account, stake_account, false));
+
+            if u64::from(info.transient_stake_lamports) > 0 {
+                let (transient_stake_account, _) = find_transient_stake_program_address(
+                    &cluster_config.stake_pool_program_id,
+                    vote_account,
+                    &cluster_config.stake_pool_address,
+                    info.transient_seed_suffix.into(),
+                );
+                reward_addresses.push((*vote_account, transient_stake_account, false));
+            }
+        }
+    }
+
+    let mut rewards_map: HashMap<Pubkey, InflationRewards> = HashMap::new();
+    for chunk in reward_addresses.chunks(100) {
+        let addresses: Vec<Pubkey> = chunk.iter().map(|(_, address, _)| *address).collect();
+        let reward_lamports: Vec<Option<u64>> = rpc_client
+            .get_inflation_reward(&addresses, Some(epoch))
+            .await?
+            .into_iter()
+            .map(|reward| reward.map(|reward| reward.amount))
+            .collect();
+
+        add_inflation_rewards(&mut rewards_map, chunk, &reward_lamports);
+    }
+
+    Ok(rewards_map)
+}
+
 /// Fetch all [`ValidatorHistory`] accounts and retruns thems as a lookup map
 ///
 /// ## Overview
@@ -372,44 +518,108 @@ pub async fn fetch_validator_history_accounts(
 pub async fn fetch_mev_rewards(
     validators: &[ValidatorsAppResponseEntry],
     rpc_client: &RpcClient,
-    cluster: &Cluster,
+    cluster_config: &ClusterConfig,
     epoch: u64,
 ) -> Result<u64, Error> {
-    let mut total = 0;
     let tip_distribution_account_rent = rpc_client
         .get_minimum_balance_for_rent_exemption(TipDistributionAccount::SIZE)
         .await?;
-    for chunk in validators.chunks(100) {
-        let pubkeys = chunk
-            .iter()
-            .map(|c| {
-                jito_tip_distribution_sdk::derive_tip_distribution_account_address(
-                    &get_tip_distribution_program_id(cluster),
-                    &c.vote_account,
-                    epoch,
-                )
-                .0
-            })
-            .collect::<Vec<Pubkey>>();
-        let response = rpc_client.get_multiple_accounts(&pubkeys).await;
-        if let Ok(result) = response {
-            for (v, acc) in core::iter::zip(chunk, result) {
-                if let Some(account) = acc {
-                    if get_tip_distribution_program_id(cluster) != account.owner {
-                        warn!("Validator {} may be trying to mess with their Tip Distribution Account", v.vote_account);
-                        continue;
-                    }
+    let tip_distribution_program_id = cluster_config.tip_distribution_program_id;
+    let pubkeys = validators
+        .iter()
+        .map(|v| {
+            jito_tip_distribution_sdk::derive_tip_distribution_account_address(
+                &tip_distribution_program_id,
+                &v.vote_account,
+                epoch,
+            )
+            .0
+        })
+        .collect::<Vec<Pubkey>>();
+    let accounts = get_multiple_accounts_chunked(rpc_client, &pubkeys).await;
 
-                    total += account.lamports - tip_distribution_account_rent;
-                }
+    let mut total = 0;
+    for (v, acc) in core::iter::zip(validators, accounts) {
+        if let Some(account) = acc {
+            if tip_distribution_program_id != account.owner {
+                warn!(
+                    "Validator {} may be trying to mess with their Tip Distribution Account",
+                    v.vote_account
+                );
+                continue;
             }
-        } else if let Err(e) = response {
-            error!("Rpc error: {e:#?}");
+
+            total += account.lamports - tip_distribution_account_rent;
         }
     }
     Ok(total)
 }
 
+/// Fetch accounts with `getMultipleAccounts` in chunks, running up to
+/// [`MAX_CONCURRENT_RPC_REQUESTS`] requests at a time
+///
+/// The result lines up with `pubkeys`. A failed chunk is logged and returned as missing accounts,
+/// matching how a single failed batch was previously skipped.
+pub async fn get_multiple_accounts_chunked(
+    rpc_client: &RpcClient,
+    pubkeys: &[Pubkey],
+) -> Vec<Option<Account>> {
+    fetch_chunked(pubkeys, MULTIPLE_ACCOUNTS_CHUNK_SIZE, |chunk| {
+        rpc_client.get_multiple_accounts(chunk)
+    })
+    .await
+}
+
+/// Run `fetch` over chunks of `chunk_size` pubkeys, up to [`MAX_CONCURRENT_RPC_REQUESTS`] at a
+/// time, and line the fetched values up with `pubkeys`
+async fn fetch_chunked<'a, T, E, F, Fut>(
+    pubkeys: &'a [Pubkey],
+    chunk_size: usize,
+    fetch: F,
+) -> Vec<Option<T>>
+where
+    T: Clone,
+    E: std::fmt::Debug,
+    F: Fn(&'a [Pubkey]) -> Fut,
+    Fut: Future<Output = Result<Vec<Option<T>>, E>>,
+{
+    stream::iter(pubkeys.chunks(chunk_size))
+        .map(|chunk| {
+            let values = fetch(chunk);
+            async move {
+                match values.await {
+                    Ok(values) => values,
+                    Err(e) => {
+                        error!("Rpc error: {e:#?}");
+                        vec![None; chunk.len()]
+                    }
+                }
+            }
+        })
+        .buffered(MAX_CONCURRENT_RPC_REQUESTS)
+        .collect::<Vec<Vec<Option<T>>>>()
+        .await
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Await `future`, reporting its duration as a `chain_data_fetch` datapoint tagged by `source`
+async fn timed<F: Future>(source: &'static str, future: F) -> F::Output {
+    let start = Instant::now();
+    let output = future.await;
+    let elapsed = start.elapsed();
+
+    debug!("Fetched {source} in {}ms", elapsed.as_millis());
+    datapoint_info!(
+        "chain_data_fetch",
+        ("elapsed_ms", elapsed.as_millis() as i64, i64),
+        "source" => source
+    );
+
+    output
+}
+
 // fetches global average vote credits and average vote credits per validator over last 5 epochs
 pub fn fetch_vote_credits(
     vote_accounts: &RpcVoteAccountStatus,
@@ -449,6 +659,20 @@ pub fn fetch_total_staked_lamports(vote_accounts: &RpcVoteAccountStatus) -> u64
         .sum()
 }
 
+/// Active stake in lamports for every vote account, indexed by vote account
+pub fn fetch_staked_lamports_map(vote_accounts: &RpcVoteAccountStatus) -> HashMap<Pubkey, u64> {
+    vote_accounts
+        .current
+        .iter()
+        .chain(vote_accounts.delinquent.iter())
+        .filter_map(|info| {
+            Pubkey::from_str(&info.vote_pubkey)
+                .ok()
+                .map(|vote_account| (vote_account, info.activated_stake))
+        })
+        .collect()
+}
+
 pub fn fetch_staked_lamports_for_validator(
     vote_accounts: &RpcVoteAccountStatus,
     vote_account: &Pubkey,
@@ -461,3 +685,97 @@ pub fn fetch_staked_lamports_for_validator(
         .map(|info| info.activated_stake)
         .unwrap_or(0)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_chunked_keeps_order() {
+        let pubkeys: Vec<Pubkey> = (0..10).map(|_| Pubkey::new_unique()).collect();
+
+        // Later chunks finish first, and the second chunk fails
+        let fetched = fetch_chunked(&pubkeys, 3, |chunk| {
+            let first = pubkeys.iter().position(|p| *p == chunk[0]).unwrap();
+            async move {
+                tokio::time::sleep(Duration::from_millis(10 * (10 - first) as u64)).await;
+                if first == 3 {
+                    return Err("rpc error");
+                }
+                Ok(chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(i, pubkey)| ((first + i) % 2 == 0).then_some(*pubkey))
+                    .collect())
+            }
+        })
+        .await;
+
+        let expected: Vec<Option<Pubkey>> = pubkeys
+            .iter()
+            .enumerate()
+            .map(|(i, pubkey)| (i % 2 == 0 && !(3..6).contains(&i)).then_some(*pubkey))
+            .collect();
+        assert_eq!(fetched, expected);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_chunked_empty() {
+        let fetched =
+            fetch_chunked(&[], 3, |_| async { Ok::<Vec<Option<Pubkey>>, ()>(vec![]) }).await;
+        assert!(fetched.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_timed_returns_output() {
+        assert_eq!(timed("test", async { 42 }).await, 42);
+    }
+
+    #[test]
+    fn test_estimate_inflation_rewards() {
+        // 5% inflation over 163 epochs, 1M SOL staked at the average vote credits
+        let rewards = estimate_inflation_rewards(0.05, 1_000_000_000_000_000.0, 1.0);
+        assert_eq!(rewards, 306_748_466_257);
+
+        // Half the average vote credits earns half the rewards
+        assert_eq!(
+            estimate_inflation_rewards(0.05, 1_000_000_000_000_000.0, 0.5),
+            rewards / 2
+        );
+        assert_eq!(estimate_inflation_rewards(0.05, 0.0, 1.0), 0);
+    }
+
+    #[test]
+    fn test_add_inflation_rewards() {
+        let vote_account = Pubkey::new_unique();
+        let other_vote_account = Pubkey::new_unique();
+        let reward_addresses = [
+            (vote_account, vote_account, true),
+            (vote_account, Pubkey::new_unique(), false),
+            (vote_account, Pubkey::new_unique(), false),
+            (other_vote_account, other_vote_account, true),
+        ];
+
+        let mut rewards_map = HashMap::new();
+        add_inflation_rewards(
+            &mut rewards_map,
+            &reward_addresses,
+            &[Some(100), Some(1_000), Some(50), None],
+        );
+
+        assert_eq!(
+            rewards_map[&vote_account],
+            InflationRewards {
+                vote_account_lamports: 100,
+                stake_pool_lamports: 1_050,
+            }
+        );
+        assert_eq!(rewards_map[&vote_account].total_lamports(), 1_150);
+        assert_eq!(
+            rewards_map[&other_vote_account],
+            InflationRewards::default()
+        );
+    }
+}
@@ -0,0 +1,878 @@
+//! Versioned MongoDB schema migrations.
+//!
+//! Every [`Migration`] has a version and a list of [`MigrationStep`]s. Applied versions are
+//! recorded in [`MIGRATIONS_COLLECTION`], so [`MigrationRunner::run`] only applies the pending
+//! ones, in version order. Steps are idempotent, so a migration interrupted half way is re-run as
+//! a whole.
+//!
+//! Migrations are never edited once released: changing a store's indexes, or adding a field that
+//! needs a backfill, is done by appending a new version to [`migrations`].
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use futures::TryStreamExt;
+use log::{info, warn};
+use mongodb::{
+    bson::{doc, Bson, DateTime, Document},
+    error::{CommandError, ErrorKind},
+    options::{AggregateOptions, FindOptions, IndexOptions},
+    Collection, Database, IndexModel,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::db_models::{
+    bam_boost_validators::BamBoostValidatorsStore,
+    bam_delegation_blacklist::BamDelegationBlacklistStore,
+    bam_delegation_blacklist_audit::BamDelegationBlacklistAuditStore,
+    bam_delegation_plans::BamDelegationPlanStore,
+    bam_epoch_metrics::BamEpochMetricsStore,
+    bam_validators::BamValidatorStore,
+    bam_writer_snapshots::BamWriterSnapshotStore,
+    error::DataStoreError,
+    index,
+    mev_rewards::{StakerRewardsStore, ValidatorRewardsStore},
+    stake_pool_stats::StakePoolStatsStore,
+    steward_backfill_checkpoints::StewardBackfillCheckpointStore,
+    steward_events::StewardEventsStore,
+    steward_parameters::StewardParametersStore,
+    steward_state_snapshots::StewardStateSnapshotStore,
+    unique_index,
+    validators::ValidatorStore,
+};
+
+/// Collection recording applied migrations
+pub const MIGRATIONS_COLLECTION: &str = "schema_migrations";
+
+/// Number of documents a [`MigrationStep::Enumerate`] numbers per round of writes
+pub const ENUMERATE_BATCH_SIZE: usize = 1_000;
+
+/// Record of an applied [`Migration`]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AppliedMigration {
+    pub version: u32,
+    pub description: String,
+    pub applied_at: DateTime,
+}
+
+/// Single operation of a [`Migration`]
+#[derive(Clone, Debug)]
+pub enum MigrationStep {
+    /// Create indexes on a collection, indexes that already exist are left untouched
+    CreateIndexes {
+        collection: &'static str,
+        indexes: Vec<IndexModel>,
+    },
+
+    /// Drop indexes of a collection by name, indexes that do not exist are skipped
+    DropIndexes {
+        collection: &'static str,
+        names: Vec<&'static str>,
+    },
+
+    /// Apply `update` to every document of a collection matching `filter`
+    Backfill {
+        collection: &'static str,
+        filter: Document,
+        update: Document,
+    },
+
+    /// Delete every document of a collection but the first stored (`_id` order) of those with
+    /// the same `key` values
+    Deduplicate {
+        collection: &'static str,
+        key: Vec<&'static str>,
+    },
+
+    /// Set `field` of every document of a collection matching `filter` to its position among the
+    /// documents with the same `group_by` values, in stored (`_id`) order. Positions start after
+    /// the highest `field` already set in the group, so they never collide with it. Documents are
+    /// streamed in group order and written [`ENUMERATE_BATCH_SIZE`] at a time
+    Enumerate {
+        collection: &'static str,
+        filter: Document,
+        group_by: Vec<&'static str>,
+        field: &'static str,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub steps: Vec<MigrationStep>,
+}
+
+/// All migrations, in version order
+///
+/// Index specs are written out in each migration rather than read from the stores, so a released
+/// migration keeps creating the same indexes when a store's indexes change later.
+pub fn migrations() -> Vec<Migration> {
+    vec![
+        Migration {
+            version: 1,
+            description: "Create store indexes",
+            steps: vec![
+                MigrationStep::CreateIndexes {
+                    collection: ValidatorStore::COLLECTION,
+                    indexes: vec![
+                        index(doc! {"epoch": 1, "vote_account": 1}),
+                        index(doc! {"running_jito": 1, "epoch": 1}),
+                    ],
+                },
+                MigrationStep::CreateIndexes {
+                    collection: ValidatorRewardsStore::COLLECTION,
+                    indexes: vec![
+                        index(doc! {"vote_account": 1, "epoch": -1}),
+                        index(doc! {"epoch": -1, "mev_revenue": -1}),
+                    ],
+                },
+                MigrationStep::CreateIndexes {
+                    collection: StakerRewardsStore::COLLECTION,
+                    indexes: vec![
+                        index(doc! {"stake_authority": 1, "epoch": -1}),
+                        index(doc! {"validator_vote_account": 1, "epoch": -1}),
+                        index(doc! {"epoch": -1, "amount": -1}),
+                    ],
+                },
+                MigrationStep::CreateIndexes {
+                    collection: StewardEventsStore::COLLECTION,
+                    indexes: vec![
+                        // Events written before `event_idx` was tracked are left out of the key
+                        // until they are numbered
+                        IndexModel::builder()
+                            .keys(doc! {"signature": 1, "instruction_idx": 1, "event_idx": 1})
+                            .options(
+                                IndexOptions::builder()
+                                    .name("signature_instruction_idx_event_idx".to_string())
+                                    .unique(true)
+                                    .partial_filter_expression(
+                                        doc! {"event_idx": {"$exists": true}},
+                                    )
+                                    .build(),
+                            )
+                            .build(),
+                        index(doc! {"signature": 1}),
+                        index(doc! {"slot": -1}),
+                        index(doc! {"vote_account": 1, "slot": -1}),
+                        index(doc! {"event_type": 1, "slot": -1}),
+                        index(doc! {"epoch": 1, "slot": -1}),
+                    ],
+                },
+                MigrationStep::CreateIndexes {
+                    collection: StakePoolStatsStore::COLLECTION,
+                    indexes: vec![index(doc! {"timestamp": 1}), index(doc! {"epoch": 1})],
+                },
+                MigrationStep::CreateIndexes {
+                    collection: BamValidatorStore::COLLECTION,
+                    indexes: vec![unique_index(
+                        "epoch_vote_account",
+                        doc! {"epoch": 1, "vote_account": 1},
+                    )],
+                },
+                MigrationStep::CreateIndexes {
+                    collection: BamEpochMetricsStore::COLLECTION,
+                    indexes: vec![unique_index("epoch", doc! {"epoch": 1})],
+                },
+                MigrationStep::CreateIndexes {
+                    collection: BamBoostValidatorsStore::COLLECTION,
+                    indexes: vec![unique_index(
+                        "epoch_identity_account",
+                        doc! {"epoch": 1, "identity_account": 1},
+                    )],
+                },
+            ],
+        },
+        Migration {
+            version: 2,
+            description: "Backfill stake_pool_stats.fees_collected",
+            steps: vec![
+                // Stats written before mid November '22 have no fees_collected; store an explicit
+                // null so every document has the same shape
+                MigrationStep::Backfill {
+                    collection: StakePoolStatsStore::COLLECTION,
+                    filter: doc! {"fees_collected": {"$exists": false}},
+                    update: doc! {"$set": {"fees_collected": Bson::Null}},
+                },
+            ],
+        },
+        Migration {
+            version: 3,
+            description: "Create steward backfill checkpoint indexes",
+            steps: vec![MigrationStep::CreateIndexes {
+                collection: StewardBackfillCheckpointStore::COLLECTION,
+                indexes: vec![unique_index(
+                    "stream_start_slot_end_slot",
+                    doc! {"stream": 1, "start_slot": 1, "end_slot": 1},
+                )],
+            }],
+        },
+        Migration {
+            version: 4,
+            description: "Create steward state snapshot indexes",
+            steps: vec![MigrationStep::CreateIndexes {
+                collection: StewardStateSnapshotStore::COLLECTION,
+                indexes: vec![
+                    unique_index("slot_vote_account", doc! {"slot": 1, "vote_account": 1}),
+                    index(doc! {"vote_account": 1, "slot": -1}),
+                    index(doc! {"epoch": 1, "slot": -1}),
+                ],
+            }],
+        },
+        Migration {
+            version: 5,
+            description: "Create steward event filter and cursor indexes",
+            steps: vec![MigrationStep::CreateIndexes {
+                collection: StewardEventsStore::COLLECTION,
+                indexes: vec![
+                    index(
+                        doc! {"slot": -1, "signature": -1, "instruction_idx": -1, "event_idx": -1},
+                    ),
+                    index(doc! {"signer": 1, "slot": -1}),
+                    index(doc! {"stake_pool": 1, "slot": -1}),
+                    index(doc! {"timestamp": -1}),
+                    index(doc! {"tx_error": 1, "slot": -1}),
+                ],
+            }],
+        },
+        Migration {
+            version: 6,
+            description: "Create BAM delegation plan indexes",
+            steps: vec![MigrationStep::CreateIndexes {
+                collection: BamDelegationPlanStore::COLLECTION,
+                indexes: vec![unique_index("epoch", doc! {"epoch": -1})],
+            }],
+        },
+        Migration {
+            version: 7,
+            description: "Create BAM delegation blacklist indexes",
+            steps: vec![
+                // Nothing kept a vote account from being blacklisted twice before this index; keep
+                // the first stored entry of each vote account so the index can be built
+                MigrationStep::Deduplicate {
+                    collection: BamDelegationBlacklistStore::COLLECTION,
+                    key: vec!["vote_account"],
+                },
+                MigrationStep::CreateIndexes {
+                    collection: BamDelegationBlacklistStore::COLLECTION,
+                    indexes: vec![
+                        unique_index("vote_account", doc! {"vote_account": 1}),
+                        index(doc! {"expires_at": 1}),
+                    ],
+                },
+            ],
+        },
+        Migration {
+            version: 8,
+            description: "Create BAM delegation blacklist audit log indexes",
+            steps: vec![MigrationStep::CreateIndexes {
+                collection: BamDelegationBlacklistAuditStore::COLLECTION,
+                indexes: vec![
+                    index(doc! {"timestamp": -1}),
+                    index(doc! {"vote_account": 1, "timestamp": -1}),
+                ],
+            }],
+        },
+        Migration {
+            version: 9,
+            description: "Create BAM writer snapshot indexes",
+            steps: vec![MigrationStep::CreateIndexes {
+                collection: BamWriterSnapshotStore::COLLECTION,
+                indexes: vec![unique_index(
+                    "epoch_threshold_bps",
+                    doc! {"epoch": -1, "threshold_bps": 1},
+                )],
+            }],
+        },
+        Migration {
+            version: 10,
+            description: "Create steward parameters indexes",
+            steps: vec![MigrationStep::CreateIndexes {
+                collection: StewardParametersStore::COLLECTION,
+                indexes: vec![unique_index("slot", doc! {"slot": -1})],
+            }],
+        },
+        Migration {
+            version: 11,
+            description: "Number steward events written before event_idx was tracked",
+            steps: vec![
+                // Cursor pagination compares `event_idx`, which never matches a missing field, so
+                // a page ending inside a legacy transaction would skip the rest of its events
+                MigrationStep::Enumerate {
+                    collection: StewardEventsStore::COLLECTION,
+                    filter: doc! {"event_idx": {"$exists": false}},
+                    group_by: vec!["signature", "instruction_idx"],
+                    field: "event_idx",
+                },
+            ],
+        },
+    ]
+}
+
+/// Applies pending [`Migration`]s to a database
+pub struct MigrationRunner {
+    db: Database,
+    migrations: Vec<Migration>,
+}
+
+impl MigrationRunner {
+    /// Initialize a [`MigrationRunner`] with all [`migrations`]
+    pub fn new(db: Database) -> Self {
+        Self::with_migrations(db, migrations())
+    }
+
+    /// Initialize a [`MigrationRunner`] with a custom migration list
+    pub fn with_migrations(db: Database, mut migrations: Vec<Migration>) -> Self {
+        migrations.sort_by_key(|m| m.version);
+        Self { db, migrations }
+    }
+
+    /// Migrations recorded as applied, in version order
+    pub async fn applied(&self) -> Result<Vec<AppliedMigration>, DataStoreError> {
+        let cursor = self
+            .db
+            .collection::<AppliedMigration>(MIGRATIONS_COLLECTION)
+            .find(doc! {}, None)
+            .await?;
+        let mut applied: Vec<AppliedMigration> = cursor.try_collect().await?;
+        applied.sort_by_key(|m| m.version);
+
+        Ok(applied)
+    }
+
+    /// Migrations not applied yet, in version order
+    pub async fn pending(&self) -> Result<Vec<&Migration>, DataStoreError> {
+        let applied: HashSet<u32> = self.applied().await?.iter().map(|m| m.version).collect();
+
+        Ok(self
+            .migrations
+            .iter()
+            .filter(|m| !applied.contains(&m.version))
+            .collect())
+    }
+
+    /// Apply pending migrations, returning the applied versions
+    ///
+    /// Stops at the first failing migration, which is left unrecorded so the next run retries it.
+    pub async fn run(&self) -> Result<Vec<u32>, DataStoreError> {
+        let mut applied_versions = vec![];
+
+        for migration in self.pending().await? {
+            info!(
+                "Applying migration {}: {}",
+                migration.version, migration.description
+            );
+
+            for step in migration.steps.iter() {
+                self.apply_step(step)
+                    .await
+                    .map_err(|source| DataStoreError::MigrationFailed {
+                        version: migration.version,
+                        source,
+                    })?;
+            }
+
+            self.db
+                .collection::<AppliedMigration>(MIGRATIONS_COLLECTION)
+                .insert_one(
+                    AppliedMigration {
+                        version: migration.version,
+                        description: migration.description.to_string(),
+                        applied_at: DateTime::now(),
+                    },
+                    None,
+                )
+                .await?;
+            applied_versions.push(migration.version);
+        }
+
+        Ok(applied_versions)
+    }
+
+    async fn apply_step(&self, step: &MigrationStep) -> Result<(), mongodb::error::Error> {
+        match step {
+            MigrationStep::CreateIndexes {
+                collection,
+                indexes,
+            } => {
+                let result = self
+                    .db
+                    .collection::<Document>(collection)
+                    .create_indexes(indexes.clone(), None)
+                    .await?;
+                info!("Created indexes {:?} on {collection}", result.index_names);
+            }
+            MigrationStep::DropIndexes { collection, names } => {
+                for name in names {
+                    match self
+                        .db
+                        .collection::<Document>(collection)
+                        .drop_index(*name, None)
+                        .await
+                    {
+                        Ok(()) => info!("Dropped index {name} on {collection}"),
+                        Err(e) if is_index_not_found(&e) => {
+                            info!("Index {name} on {collection} does not exist, skipping")
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+            MigrationStep::Backfill {
+                collection,
+                filter,
+                update,
+            } => {
+                let result = self
+                    .db
+                    .collection::<Document>(collection)
+                    .update_many(filter.clone(), update.clone(), None)
+                    .await?;
+                info!(
+                    "Backfilled {} documents in {collection}",
+                    result.modified_count
+                );
+            }
+            MigrationStep::Deduplicate { collection, key } => {
+                let coll = self.db.collection::<Document>(collection);
+                let pipeline = vec![
+                    doc! {"$sort": {"_id": 1}},
+                    doc! {"$group": {
+                        "_id": field_refs(key),
+                        "ids": {"$push": "$_id"},
+                        "count": {"$sum": 1},
+                    }},
+                    doc! {"$match": {"count": {"$gt": 1}}},
+                ];
+                let options = AggregateOptions::builder().allow_disk_use(true).build();
+                let groups: Vec<Document> = coll
+                    .aggregate(pipeline, options)
+                    .await?
+                    .try_collect()
+                    .await?;
+
+                let mut deleted = 0;
+                for group in groups {
+                    let ids = group.get_array("ids").cloned().unwrap_or_default();
+                    let result = coll
+                        .delete_many(doc! {"_id": {"$in": duplicate_ids(&ids).to_vec()}}, None)
+                        .await?;
+                    warn!(
+                        "Deleted {} duplicate documents of {} in {collection}",
+                        result.deleted_count,
+                        group.get("_id").cloned().unwrap_or(Bson::Null)
+                    );
+                    deleted += result.deleted_count;
+                }
+                info!("Deleted {deleted} duplicate documents in {collection}");
+            }
+            MigrationStep::Enumerate {
+                collection,
+                filter,
+                group_by,
+                field,
+            } => {
+                let coll = self.db.collection::<Document>(collection);
+                let mut sort: Document = group_by
+                    .iter()
+                    .map(|key| (key.to_string(), Bson::Int32(1)))
+                    .collect();
+                sort.insert("_id", 1);
+                let projection = sort.clone();
+                let options = FindOptions::builder()
+                    .sort(sort)
+                    .projection(projection)
+                    .allow_disk_use(true)
+                    .build();
+                let mut cursor = coll.find(filter.clone(), options).await?;
+
+                // Documents arrive grouped, so only the group open at the end of a batch carries
+                // over to the next one
+                let mut open_group: Option<(String, i64)> = None;
+                let mut batch = vec![];
+                let mut numbered = 0;
+                loop {
+                    let document = cursor.try_next().await?;
+                    let end = document.is_none();
+                    if let Some(document) = document {
+                        batch.push(document);
+                    }
+                    if batch.len() < ENUMERATE_BATCH_SIZE && !end {
+                        continue;
+                    }
+
+                    if !batch.is_empty() {
+                        open_group = self
+                            .enumerate_batch(&coll, &batch, group_by, field, open_group)
+                            .await?;
+                        numbered += batch.len();
+                        batch.clear();
+                    }
+                    if end {
+                        break;
+                    }
+                }
+                info!("Numbered {numbered} documents of {collection} by {field}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Number a batch of documents sorted by group for [`MigrationStep::Enumerate`], returns the
+    /// group of the last document and the next position in it
+    async fn enumerate_batch(
+        &self,
+        coll: &Collection<Document>,
+        batch: &[Document],
+        group_by: &[&str],
+        field: &str,
+        open_group: Option<(String, i64)>,
+    ) -> Result<Option<(String, i64)>, mongodb::error::Error> {
+        let groups: Vec<Document> = batch
+            .iter()
+            .map(|document| group_of(document, group_by))
+            .collect();
+        let keys: Vec<String> = groups.iter().map(Document::to_string).collect();
+
+        // Start every group after the highest position already set in it, in one query per batch
+        let mut starts: HashMap<String, i64> = open_group.into_iter().collect();
+        let mut new_groups = vec![];
+        for (group, key) in groups.iter().zip(keys.iter()) {
+            if !starts.contains_key(key) {
+                starts.insert(key.clone(), 0);
+                new_groups.push(group.clone());
+            }
+        }
+        if !new_groups.is_empty() {
+            let pipeline = vec![
+                doc! {"$match": {"$and": [{"$or": new_groups}, {field: {"$exists": true}}]}},
+                doc! {"$group": {"_id": field_refs(group_by), "highest": {"$max": format!("${field}")}}},
+            ];
+            let mut cursor = coll.aggregate(pipeline, None).await?;
+            while let Some(highest) = cursor.try_next().await? {
+                let group = highest
+                    .get_document("_id")
+                    .map(|group| group_of(group, group_by))
+                    .unwrap_or_default();
+                if let Some(highest) = position_of(&highest, "highest") {
+                    starts.insert(group.to_string(), highest + 1);
+                }
+            }
+        }
+
+        let positions = enumerate_groups(&keys, &starts);
+        let open_group = keys.last().cloned().zip(positions.last().map(|p| p + 1));
+        let ids: Vec<Bson> = batch
+            .iter()
+            .map(|document| document.get("_id").cloned().unwrap_or(Bson::Null))
+            .collect();
+        for (position, ids) in ids_by_position(ids, positions) {
+            coll.update_many(
+                doc! {"_id": {"$in": ids}},
+                doc! {"$set": {field: position}},
+                None,
+            )
+            .await?;
+        }
+
+        Ok(open_group)
+    }
+}
+
+/// `{key: "$key"}` for each of `keys`, to group an aggregation by them
+fn field_refs(keys: &[&str]) -> Document {
+    keys.iter()
+        .map(|key| (key.to_string(), Bson::String(format!("${key}"))))
+        .collect()
+}
+
+/// Ids to delete of a group of duplicates, all but the first
+fn duplicate_ids(ids: &[Bson]) -> &[Bson] {
+    ids.get(1..).unwrap_or_default()
+}
+
+/// Ids of the documents to set to each position, so a batch is written with one update per
+/// position
+fn ids_by_position(ids: Vec<Bson>, positions: Vec<i64>) -> BTreeMap<i64, Vec<Bson>> {
+    let mut by_position: BTreeMap<i64, Vec<Bson>> = BTreeMap::new();
+    for (id, position) in ids.into_iter().zip(positions) {
+        by_position.entry(position).or_default().push(id);
+    }
+
+    by_position
+}
+
+/// Values of the `group_by` fields of a document, a missing field being null
+fn group_of(document: &Document, group_by: &[&str]) -> Document {
+    group_by
+        .iter()
+        .map(|key| {
+            (
+                key.to_string(),
+                document.get(*key).cloned().unwrap_or(Bson::Null),
+            )
+        })
+        .collect()
+}
+
+/// Integer value of `field`, whichever width it was stored with
+fn position_of(document: &Document, field: &str) -> Option<i64> {
+    match document.get(field)? {
+        Bson::Int32(value) => Some(*value as i64),
+        Bson::Int64(value) => Some(*value),
+        _ => None,
+    }
+}
+
+/// Position of each key among the keys of its group, in order, counting from the group's start
+fn enumerate_groups(keys: &[String], starts: &HashMap<String, i64>) -> Vec<i64> {
+    let mut next = starts.clone();
+
+    keys.iter()
+        .map(|key| {
+            let position = next.entry(key.clone()).or_insert(0);
+            *position += 1;
+            *position - 1
+        })
+        .collect()
+}
+
+fn is_index_not_found(error: &mongodb::error::Error) -> bool {
+    const INDEX_NOT_FOUND: i32 = 27;
+    matches!(
+        error.kind.as_ref(),
+        ErrorKind::Command(CommandError { code, .. }) if *code == INDEX_NOT_FOUND
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db_models::steward_events::{StewardEvent, StewardEventsCursor};
+
+    use super::*;
+
+    #[test]
+    fn test_migration_versions_ascending() {
+        let versions: Vec<u32> = migrations().iter().map(|m| m.version).collect();
+
+        assert_eq!(versions.first(), Some(&1));
+        assert!(versions.windows(2).all(|w| w[1] == w[0] + 1));
+    }
+
+    fn is_unique(index: &IndexModel) -> bool {
+        index.options.as_ref().and_then(|o| o.unique) == Some(true)
+    }
+
+    /// Every index a store queries with must be created by some migration
+    #[test]
+    fn test_migrations_create_store_indexes() {
+        let created: HashSet<(&str, String, bool)> = migrations()
+            .into_iter()
+            .flat_map(|m| m.steps)
+            .filter_map(|step| match step {
+                MigrationStep::CreateIndexes {
+                    collection,
+                    indexes,
+                } => Some((collection, indexes)),
+                _ => None,
+            })
+            .flat_map(|(collection, indexes)| {
+                indexes
+                    .into_iter()
+                    .map(move |i| (collection, i.keys.to_string(), is_unique(&i)))
+            })
+            .collect();
+
+        let stores = [
+            (ValidatorStore::COLLECTION, ValidatorStore::indexes()),
+            (
+                ValidatorRewardsStore::COLLECTION,
+                ValidatorRewardsStore::indexes(),
+            ),
+            (
+                StakerRewardsStore::COLLECTION,
+                StakerRewardsStore::indexes(),
+            ),
+            (
+                StewardEventsStore::COLLECTION,
+                StewardEventsStore::indexes(),
+            ),
+            (
+                StakePoolStatsStore::COLLECTION,
+                StakePoolStatsStore::indexes(),
+            ),
+            (BamValidatorStore::COLLECTION, BamValidatorStore::indexes()),
+            (
+                BamEpochMetricsStore::COLLECTION,
+                BamEpochMetricsStore::indexes(),
+            ),
+            (
+                BamBoostValidatorsStore::COLLECTION,
+                BamBoostValidatorsStore::indexes(),
+            ),
+            (
+                StewardBackfillCheckpointStore::COLLECTION,
+                StewardBackfillCheckpointStore::indexes(),
+            ),
+            (
+                StewardStateSnapshotStore::COLLECTION,
+                StewardStateSnapshotStore::indexes(),
+            ),
+            (
+                StewardParametersStore::COLLECTION,
+                StewardParametersStore::indexes(),
+            ),
+            (
+                BamDelegationPlanStore::COLLECTION,
+                BamDelegationPlanStore::indexes(),
+            ),
+            (
+                BamDelegationBlacklistStore::COLLECTION,
+                BamDelegationBlacklistStore::indexes(),
+            ),
+            (
+                BamDelegationBlacklistAuditStore::COLLECTION,
+                BamDelegationBlacklistAuditStore::indexes(),
+            ),
+            (
+                BamWriterSnapshotStore::COLLECTION,
+                BamWriterSnapshotStore::indexes(),
+            ),
+        ];
+        for (collection, indexes) in stores {
+            for i in indexes {
+                assert!(
+                    created.contains(&(collection, i.keys.to_string(), is_unique(&i))),
+                    "no migration creates index {} on {collection}",
+                    i.keys
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_steward_events_unique_key() {
+        let unique_keys: Vec<Document> = StewardEventsStore::indexes()
+            .into_iter()
+            .filter(is_unique)
+            .map(|i| i.keys)
+            .collect();
+
+        assert_eq!(
+            unique_keys,
+            vec![doc! {"signature": 1, "instruction_idx": 1, "event_idx": 1}]
+        );
+    }
+
+    fn legacy_event(slot: i64, signature: &str, event_type: &str) -> Document {
+        doc! {
+            "signature": signature,
+            "instruction_idx": 0_i64,
+            "event_type": event_type,
+            "vote_account": Bson::Null,
+            "metadata": Bson::Null,
+            "tx_error": Bson::Null,
+            "signer": "signer",
+            "stake_pool": "stake_pool",
+            "epoch": 800_i64,
+            "slot": slot,
+            "timestamp": Bson::Null,
+        }
+    }
+
+    /// Events of the page following `cursor`, in ascending cursor order
+    fn next_page(
+        events: &[StewardEvent],
+        cursor: Option<&StewardEventsCursor>,
+        limit: usize,
+    ) -> Vec<StewardEventsCursor> {
+        let mut positions: Vec<StewardEventsCursor> =
+            events.iter().map(StewardEventsCursor::after).collect();
+        positions.sort();
+        positions
+            .into_iter()
+            .filter(|position| cursor.is_none_or(|cursor| position > cursor))
+            .take(limit)
+            .collect()
+    }
+
+    #[test]
+    fn test_legacy_events_split_across_pages() {
+        let mut documents = vec![
+            legacy_event(100, "tx-a", "RebalanceEvent"),
+            legacy_event(100, "tx-a", "RebalanceEvent"),
+            legacy_event(101, "tx-b", "StateTransition"),
+        ];
+        let groups: Vec<String> = documents
+            .iter()
+            .map(|document| group_of(document, &["signature", "instruction_idx"]).to_string())
+            .collect();
+        let positions = enumerate_groups(&groups, &HashMap::new());
+        assert_eq!(positions, vec![0, 1, 0]);
+
+        for (document, position) in documents.iter_mut().zip(positions) {
+            document.insert("event_idx", position);
+        }
+        let events: Vec<StewardEvent> = documents
+            .into_iter()
+            .map(|document| mongodb::bson::from_document(document).unwrap())
+            .collect();
+
+        let first_page = next_page(&events, None, 1);
+        let second_page = next_page(&events, first_page.last(), 1);
+        assert_eq!(first_page[0].event_idx, 0);
+        assert_eq!(second_page[0].signature, "tx-a");
+        assert_eq!(second_page[0].event_idx, 1);
+    }
+
+    #[test]
+    fn test_ids_by_position() {
+        let ids = vec![Bson::Int32(1), Bson::Int32(2), Bson::Int32(3)];
+
+        assert_eq!(
+            ids_by_position(ids, vec![0, 1, 0]),
+            BTreeMap::from([
+                (0, vec![Bson::Int32(1), Bson::Int32(3)]),
+                (1, vec![Bson::Int32(2)]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_duplicate_ids_keep_first() {
+        let ids = vec![Bson::Int32(1), Bson::Int32(2), Bson::Int32(3)];
+
+        assert_eq!(duplicate_ids(&ids), &[Bson::Int32(2), Bson::Int32(3)]);
+        assert!(duplicate_ids(&[]).is_empty());
+    }
+
+    /// The unique blacklist index can only be built once duplicate entries are gone
+    #[test]
+    fn test_blacklist_deduplicated_before_unique_index() {
+        let steps: Vec<MigrationStep> = migrations()
+            .into_iter()
+            .flat_map(|m| m.steps)
+            .filter(|step| match step {
+                MigrationStep::CreateIndexes { collection, .. }
+                | MigrationStep::Deduplicate { collection, .. } => {
+                    *collection == BamDelegationBlacklistStore::COLLECTION
+                }
+                _ => false,
+            })
+            .collect();
+
+        assert!(matches!(
+            steps.as_slice(),
+            [MigrationStep::Deduplicate { key, .. }, MigrationStep::CreateIndexes { .. }]
+                if key == &vec!["vote_account"]
+        ));
+    }
+
+    #[test]
+    fn test_enumerate_groups_after_existing_positions() {
+        let keys = vec!["a".to_string(), "b".to_string(), "a".to_string()];
+        let starts = HashMap::from([("a".to_string(), 3)]);
+
+        assert_eq!(enumerate_groups(&keys, &starts), vec![3, 0, 4]);
+    }
+}
@@ -53,6 +53,24 @@ async fn get_signatures_internal(
     Ok(temp_txs)
 }
 
+/// Fetch a single transaction at `commitment`, retrying while it is not yet visible to the node
+pub async fn retry_get_transaction(
+    rpc_client: &RpcClient,
+    signature: &Signature,
+    commitment: CommitmentConfig,
+) -> Result<EncodedConfirmedTransactionWithStatusMeta, RpcError> {
+    let config = RpcTransactionConfig {
+        commitment: commitment.into(),
+        encoding: UiTransactionEncoding::Base64.into(),
+        max_supported_transaction_version: Some(0),
+    };
+
+    Retry::spawn(retry(), || {
+        rpc_client.get_transaction_with_config(signature, config)
+    })
+    .await
+}
+
 pub async fn retry_get_slot(rpc_client: &RpcClient) -> Result<Slot, RpcError> {
     Retry::spawn(retry(), || rpc_client.get_slot()).await
 }
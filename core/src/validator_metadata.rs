@@ -0,0 +1,420 @@
+//! Sources of per-validator metadata used to build [`Validator`] rows.
+//!
+//! Validator rows have historically been keyed off validators.app responses. The on-chain source
+//! derives the same entries from `getVoteAccounts`, the Config program's validator-info accounts
+//! and ValidatorHistory, so the writer keeps running when validators.app is unavailable.
+//!
+//! [`Validator`]: crate::db_models::validators::Validator
+
+use std::{collections::HashMap, fmt, str::FromStr, sync::Arc};
+
+use log::*;
+use serde::Deserialize;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+    rpc_response::RpcVoteAccountInfo,
+};
+use solana_pubkey::Pubkey;
+use validator_history::{ValidatorHistory, ValidatorHistoryEntry};
+
+use crate::{
+    client_type::ClientType,
+    constants::{CONFIG_PROGRAM_ID, VALIDATOR_INFO_KEY},
+    error::KobeCoreError,
+    fetcher::fetch_validator_history_accounts,
+    validators_app::{Client, ValidatorsAppResponseEntry},
+};
+
+type Error = Box<dyn std::error::Error>;
+
+/// Which [`ValidatorMetadataSource`] the writer should use
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ValidatorMetadataSourceKind {
+    /// validators.app API (requires `VALIDATORS_APP_TOKEN`)
+    #[default]
+    ValidatorsApp,
+
+    /// Vote accounts, validator-info accounts and ValidatorHistory
+    OnChain,
+}
+
+impl FromStr for ValidatorMetadataSourceKind {
+    type Err = KobeCoreError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_ref() {
+            "validators-app" | "validators_app" => Ok(Self::ValidatorsApp),
+            "on-chain" | "on_chain" | "onchain" => Ok(Self::OnChain),
+            _ => Err(KobeCoreError::InvalidValidatorMetadataSource(
+                value.to_string(),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ValidatorMetadataSourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ValidatorsApp => write!(f, "validators-app"),
+            Self::OnChain => write!(f, "on-chain"),
+        }
+    }
+}
+
+/// Provides the list of network validators for an epoch
+pub enum ValidatorMetadataSource {
+    ValidatorsApp(Arc<Client>),
+    OnChain(OnChainValidatorMetadata),
+}
+
+impl ValidatorMetadataSource {
+    /// Fetch one entry per vote account.
+    ///
+    /// The on-chain source leaves validators.app-only fields (scores, data center, etc.) as
+    /// `None`.
+    pub async fn validators(&self, epoch: u64) -> Result<Vec<ValidatorsAppResponseEntry>, Error> {
+        match self {
+            Self::ValidatorsApp(client) => {
+                // validators.app client is blocking
+                let client = client.clone();
+                let response =
+                    tokio::task::spawn_blocking(move || client.validators(None, None, epoch))
+                        .await??;
+                Ok(response.as_ref().clone())
+            }
+            Self::OnChain(source) => source.validators(epoch).await,
+        }
+    }
+}
+
+/// Validator metadata derived entirely from on-chain data
+pub struct OnChainValidatorMetadata {
+    /// RPC client
+    rpc_client: Arc<RpcClient>,
+
+    /// Validator history program id
+    validator_history_program_id: Pubkey,
+}
+
+impl OnChainValidatorMetadata {
+    pub fn new(rpc_client: Arc<RpcClient>, validator_history_program_id: Pubkey) -> Self {
+        Self {
+            rpc_client,
+            validator_history_program_id,
+        }
+    }
+
+    /// Build validator entries from vote accounts, validator-info and ValidatorHistory
+    ///
+    /// - **Vote accounts**: identity, commission, active stake, delinquency, epoch credits
+    /// - **Validator-info**: name, website, details, keybase username
+    /// - **ValidatorHistory**: client type and software version of the latest recorded epoch
+    pub async fn validators(&self, epoch: u64) -> Result<Vec<ValidatorsAppResponseEntry>, Error> {
+        let vote_accounts = self.rpc_client.get_vote_accounts().await?;
+        let validator_infos = fetch_validator_info_accounts(&self.rpc_client)
+            .await
+            .unwrap_or_else(|e| {
+                error!("Failed to fetch validator info accounts: {e:#?}");
+                HashMap::new()
+            });
+        let validator_histories =
+            fetch_validator_history_accounts(&self.rpc_client, self.validator_history_program_id)
+                .await
+                .unwrap_or_else(|e| {
+                    error!("Failed to fetch validator history accounts: {e:#?}");
+                    HashMap::new()
+                });
+
+        let entries = vote_accounts
+            .current
+            .iter()
+            .map(|info| (info, false))
+            .chain(vote_accounts.delinquent.iter().map(|info| (info, true)))
+            .filter_map(|(info, delinquent)| {
+                let vote_account = Pubkey::from_str(&info.vote_pubkey).ok()?;
+                let validator_info = Pubkey::from_str(&info.node_pubkey)
+                    .ok()
+                    .and_then(|identity| validator_infos.get(&identity));
+                let validator_history = validator_histories.get(&vote_account);
+                let software_client =
+                    validator_history.and_then(|history| latest_software_client(history, epoch));
+                let software_version =
+                    validator_history.and_then(|history| latest_software_version(history, epoch));
+
+                Some(ValidatorsAppResponseEntry {
+                    account: Some(info.node_pubkey.clone()),
+                    active_stake: Some(info.activated_stake),
+                    commission: Some(info.commission),
+                    delinquent: Some(delinquent),
+                    details: validator_info.and_then(|v| v.details.clone()),
+                    epoch: Some(epoch),
+                    epoch_credits: Some(epoch_credits(info, epoch)),
+                    keybase_id: validator_info.and_then(|v| v.keybase_username.clone()),
+                    name: validator_info.and_then(|v| v.name.clone()),
+                    software_client,
+                    software_version,
+                    vote_account,
+                    www_url: validator_info.and_then(|v| v.website.clone()),
+                    ..ValidatorsAppResponseEntry::default()
+                })
+            })
+            .collect();
+
+        Ok(entries)
+    }
+}
+
+/// JSON payload stored in a validator-info account
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorInfo {
+    pub name: Option<String>,
+    pub website: Option<String>,
+    pub details: Option<String>,
+    pub keybase_username: Option<String>,
+}
+
+/// Fetch all validator-info accounts owned by the Config program, keyed by validator identity
+pub async fn fetch_validator_info_accounts(
+    rpc_client: &RpcClient,
+) -> Result<HashMap<Pubkey, ValidatorInfo>, Error> {
+    // Config account data starts with the short_vec length of its keys (a single byte for 2 keys),
+    // followed by the validator-info marker key.
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            1,
+            VALIDATOR_INFO_KEY.as_ref(),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let accounts = rpc_client
+        .get_program_accounts_with_config(&CONFIG_PROGRAM_ID, config)
+        .await?;
+
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(pubkey, account)| {
+            let parsed = parse_validator_info(&account.data);
+            if parsed.is_none() {
+                debug!("Skipping malformed validator info account {pubkey}");
+            }
+            parsed
+        })
+        .collect())
+}
+
+/// Parse a Config program validator-info account into `(identity, info)`
+///
+/// Layout: `short_vec<(Pubkey, bool)>` keys followed by a bincode `String` of JSON. The first key
+/// is [`VALIDATOR_INFO_KEY`] and the second is the validator identity, which must be a signer.
+pub fn parse_validator_info(data: &[u8]) -> Option<(Pubkey, ValidatorInfo)> {
+    let (num_keys, mut offset) = decode_short_u16(data)?;
+    let mut keys = Vec::with_capacity(num_keys as usize);
+    for _ in 0..num_keys {
+        let key = Pubkey::try_from(data.get(offset..offset + 32)?).ok()?;
+        let is_signer = *data.get(offset + 32)? != 0;
+        keys.push((key, is_signer));
+        offset += 33;
+    }
+
+    match keys.as_slice() {
+        [(marker, _), (identity, true)] if *marker == VALIDATOR_INFO_KEY => {
+            let len = u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?) as usize;
+            offset += 8;
+            let json = std::str::from_utf8(data.get(offset..offset.checked_add(len)?)?).ok()?;
+            let info = serde_json::from_str::<ValidatorInfo>(json).ok()?;
+            Some((*identity, info))
+        }
+        _ => None,
+    }
+}
+
+/// Decode a compact-u16 (short_vec length prefix), returning the value and bytes consumed
+fn decode_short_u16(data: &[u8]) -> Option<(u16, usize)> {
+    let mut value: u32 = 0;
+    for (i, byte) in data.iter().take(3).enumerate() {
+        value |= ((byte & 0x7f) as u32) << (i * 7);
+        if byte & 0x80 == 0 {
+            return u16::try_from(value).ok().map(|v| (v, i + 1));
+        }
+    }
+    None
+}
+
+/// Vote credits earned in `epoch`, or 0 if the vote account has no entry for it
+fn epoch_credits(info: &RpcVoteAccountInfo, epoch: u64) -> u64 {
+    info.epoch_credits
+        .iter()
+        .find(|(credits_epoch, _, _)| *credits_epoch == epoch)
+        .map(|(_, credits, prev_credits)| credits.saturating_sub(*prev_credits))
+        .unwrap_or(0)
+}
+
+/// Most recent ValidatorHistory entry at or before `epoch` for which `recorded` holds
+fn latest_entry(
+    validator_history: &ValidatorHistory,
+    epoch: u64,
+    recorded: impl Fn(&ValidatorHistoryEntry) -> bool,
+) -> Option<&ValidatorHistoryEntry> {
+    validator_history
+        .history
+        .arr
+        .iter()
+        .filter(|entry| entry.epoch != u16::MAX && entry.epoch as u64 <= epoch)
+        .filter(|entry| recorded(entry))
+        .max_by_key(|entry| entry.epoch)
+}
+
+/// Client type from the most recent ValidatorHistory entry at or before `epoch`
+fn latest_software_client(validator_history: &ValidatorHistory, epoch: u64) -> Option<String> {
+    latest_entry(validator_history, epoch, |entry| {
+        entry.client_type != u8::MAX
+    })
+    .map(|entry| ClientType::from_u8(entry.client_type).to_string())
+}
+
+/// Software version from the most recent ValidatorHistory entry at or before `epoch`
+fn latest_software_version(validator_history: &ValidatorHistory, epoch: u64) -> Option<String> {
+    latest_entry(validator_history, epoch, |entry| {
+        entry.version.major != u8::MAX
+    })
+    .map(|entry| {
+        format!(
+            "{}.{}.{}",
+            entry.version.major, entry.version.minor, entry.version.patch
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use validator_history::{CircBuf, ClientVersion};
+
+    use super::*;
+
+    fn validator_history(entries: &[ValidatorHistoryEntry]) -> ValidatorHistory {
+        let mut history = ValidatorHistory {
+            struct_version: 1,
+            vote_account: Pubkey::new_unique(),
+            index: 0,
+            bump: 0,
+            _padding0: [0; 7],
+            last_ip_timestamp: 0,
+            last_version_timestamp: 0,
+            validator_age: 0,
+            validator_age_last_updated_epoch: 0,
+            _padding1: [0; 226],
+            history: CircBuf::default(),
+        };
+        for entry in entries {
+            history.history.arr[entry.epoch as usize % history.history.arr.len()] = *entry;
+        }
+        history
+    }
+
+    #[test]
+    fn test_latest_software_client_and_version() {
+        let history = validator_history(&[
+            ValidatorHistoryEntry {
+                epoch: 798,
+                client_type: 1,
+                version: ClientVersion {
+                    major: 2,
+                    minor: 2,
+                    patch: 14,
+                },
+                ..Default::default()
+            },
+            ValidatorHistoryEntry {
+                epoch: 799,
+                client_type: 6,
+                ..Default::default()
+            },
+            ValidatorHistoryEntry {
+                epoch: 801,
+                client_type: 2,
+                ..Default::default()
+            },
+        ]);
+
+        // Entries after the epoch are ignored, unrecorded fields fall back to older entries
+        assert_eq!(
+            latest_software_client(&history, 800).as_deref(),
+            Some("BAM")
+        );
+        assert_eq!(
+            latest_software_version(&history, 800).as_deref(),
+            Some("2.2.14")
+        );
+        assert_eq!(latest_software_client(&history, 797), None);
+    }
+
+    fn validator_info_data(keys: &[(Pubkey, bool)], json: &str) -> Vec<u8> {
+        let mut data = vec![keys.len() as u8];
+        for (key, is_signer) in keys {
+            data.extend_from_slice(key.as_ref());
+            data.push(*is_signer as u8);
+        }
+        data.extend_from_slice(&(json.len() as u64).to_le_bytes());
+        data.extend_from_slice(json.as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_validator_info() {
+        let identity = Pubkey::new_unique();
+        let data = validator_info_data(
+            &[(VALIDATOR_INFO_KEY, false), (identity, true)],
+            r#"{"name":"Jito","website":"https://jito.network","keybaseUsername":"jito"}"#,
+        );
+
+        let (parsed_identity, info) = parse_validator_info(&data).unwrap();
+        assert_eq!(parsed_identity, identity);
+        assert_eq!(info.name.as_deref(), Some("Jito"));
+        assert_eq!(info.website.as_deref(), Some("https://jito.network"));
+        assert_eq!(info.keybase_username.as_deref(), Some("jito"));
+        assert_eq!(info.details, None);
+    }
+
+    #[test]
+    fn test_parse_validator_info_rejects_unsigned_identity() {
+        let data = validator_info_data(
+            &[(VALIDATOR_INFO_KEY, false), (Pubkey::new_unique(), false)],
+            r#"{"name":"Jito"}"#,
+        );
+
+        assert!(parse_validator_info(&data).is_none());
+    }
+
+    #[test]
+    fn test_parse_validator_info_rejects_truncated_data() {
+        let data = validator_info_data(
+            &[(VALIDATOR_INFO_KEY, false), (Pubkey::new_unique(), true)],
+            r#"{"name":"Jito"}"#,
+        );
+
+        assert!(parse_validator_info(&data[..data.len() - 1]).is_none());
+    }
+
+    #[test]
+    fn test_metadata_source_kind_from_str() {
+        assert_eq!(
+            "on-chain".parse::<ValidatorMetadataSourceKind>().unwrap(),
+            ValidatorMetadataSourceKind::OnChain
+        );
+        assert_eq!(
+            "validators-app"
+                .parse::<ValidatorMetadataSourceKind>()
+                .unwrap(),
+            ValidatorMetadataSourceKind::ValidatorsApp
+        );
+        assert!("foo".parse::<ValidatorMetadataSourceKind>().is_err());
+    }
+}
@@ -20,6 +20,7 @@ pub enum Cluster {
     Devnet,
     Testnet,
     MainnetBeta,
+    Localnet,
 }
 
 impl Cluster {
@@ -29,11 +30,20 @@ impl Cluster {
             "mainnet-beta" | "mainnet" | "m" => Ok(Cluster::MainnetBeta),
             "testnet" | "t" => Ok(Cluster::Testnet),
             "devnet" | "d" => Ok(Cluster::Devnet),
+            "localnet" | "localhost" | "l" => Ok(Cluster::Localnet),
             _ => Err(KobeCoreError::InvalidCluster(value.to_string())),
         }
     }
 }
 
+impl FromStr for Cluster {
+    type Err = KobeCoreError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::get_cluster(value)
+    }
+}
+
 impl std::fmt::Display for Cluster {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -43,6 +53,7 @@ impl std::fmt::Display for Cluster {
                 Self::Devnet => "devnet",
                 Self::Testnet => "testnet",
                 Self::MainnetBeta => "mainnet-beta",
+                Self::Localnet => "localnet",
             }
         )
     }
@@ -59,7 +70,9 @@ pub enum ClusterJson {
 impl ClusterJson {
     pub fn from_cluster(cluster: Cluster) -> ClusterJson {
         match cluster {
-            Cluster::Devnet => ClusterJson::Devnet,
+            // validators.app does not know about local clusters; localnet should use the on-chain
+            // validator metadata source instead
+            Cluster::Devnet | Cluster::Localnet => ClusterJson::Devnet,
             Cluster::MainnetBeta => ClusterJson::MainnetBeta,
             Cluster::Testnet => ClusterJson::Testnet,
         }
@@ -140,6 +153,7 @@ pub struct ValidatorsAppResponseRaw {
     pub skipped_slot_percent: Option<String>,
     pub skipped_slot_score: Option<i64>,
     pub skipped_slots: Option<u64>,
+    pub software_client: Option<String>,
     pub software_version: Option<String>,
     pub software_version_score: Option<i64>,
     pub stake_concentration_score: Option<i64>,
@@ -191,6 +205,7 @@ pub struct ValidatorsAppResponseEntry {
     pub skipped_slot_percent: Option<String>,
     pub skipped_slot_score: Option<i64>,
     pub skipped_slots: Option<u64>,
+    pub software_client: Option<String>,
     pub software_version: Option<String>,
     pub software_version_score: Option<i64>,
     pub stake_concentration_score: Option<i64>,
@@ -227,6 +242,7 @@ impl From<ValidatorsAppResponseRaw> for ValidatorsAppResponseEntry {
             skipped_slot_percent: entry.skipped_slot_percent,
             skipped_slot_score: entry.skipped_slot_score,
             skipped_slots: entry.skipped_slots,
+            software_client: entry.software_client,
             software_version: entry.software_version,
             software_version_score: entry.software_version_score,
             stake_concentration_score: entry.stake_concentration_score,
@@ -4,8 +4,16 @@ use std::{
 };
 
 use chrono::{Duration, SubsecRound, Utc};
-use kobe_core::db_models::stake_pool_stats::{StakePoolStats, StakePoolStatsStore};
-use mongodb::Client;
+use kobe_core::db_models::{
+    stake_pool_stats::{StakePoolStats, StakePoolStatsStore},
+    steward_events::{StewardEvent, StewardEventsStore, ValidatorRebalance},
+};
+use mongodb::{
+    bson::{doc, Document},
+    Client,
+};
+use solana_pubkey::Pubkey;
+use solana_signature::Signature;
 use tokio::time::timeout;
 
 /// NOTE: Must have MongoDB running.
@@ -187,3 +195,183 @@ async fn test_aggregate_stake_pool_stats_happy_path() {
     assert_eq!(resp[1], last_today_doc);
     assert_eq!(resp[2], last_one_day_future_doc);
 }
+
+#[tokio::test]
+async fn test_steward_rebalance_summary() {
+    env_logger();
+
+    let c = timeout(
+        std::time::Duration::from_secs(5),
+        Client::with_uri_str(MONGO_CONNECTION_URI),
+    )
+    .await
+    .expect("timed out")
+    .expect("Mongo connection failed.");
+
+    let c = c
+        .database(DATABASE)
+        .collection(StewardEventsStore::COLLECTION);
+    timeout(std::time::Duration::from_secs(1), c.drop(None))
+        .await
+        .expect("timed out. is mongo running?")
+        .unwrap();
+
+    let validator_a = Pubkey::new_unique();
+    let validator_b = Pubkey::new_unique();
+    let signer = Pubkey::new_unique();
+    let stake_pool = Pubkey::new_unique();
+    let event = |signature: u8,
+                 event_type: &str,
+                 vote_account: &Pubkey,
+                 metadata: Document,
+                 tx_error: Option<&str>,
+                 epoch: u64| {
+        StewardEvent::new(
+            &Signature::from([signature; 64]),
+            0,
+            &event_type.to_string(),
+            Some(*vote_account),
+            Some(metadata),
+            tx_error.map(|e| e.to_string()),
+            &signer,
+            &stake_pool,
+            epoch,
+            None,
+            epoch * 432_000,
+        )
+    };
+    let decrease = |scoring: i64, instant: i64, deposit: i64| {
+        doc! {
+            "rebalance_type_tag": "Decrease",
+            "increase_lamports": 0_i64,
+            "decrease_components": {
+                "scoring_unstake_lamports": scoring,
+                "instant_unstake_lamports": instant,
+                "stake_deposit_unstake_lamports": deposit,
+                "total_unstake_lamports": scoring + instant + deposit,
+            }
+        }
+    };
+    let increase = |lamports: i64| {
+        doc! {
+            "rebalance_type_tag": "Increase",
+            "increase_lamports": lamports,
+            "decrease_components": {
+                "scoring_unstake_lamports": 0_i64,
+                "instant_unstake_lamports": 0_i64,
+                "stake_deposit_unstake_lamports": 0_i64,
+                "total_unstake_lamports": 0_i64,
+            }
+        }
+    };
+
+    // Setup
+    let docs = vec![
+        event(1, "RebalanceEvent", &validator_a, increase(500), None, 800),
+        event(
+            2,
+            "RebalanceEvent",
+            &validator_a,
+            decrease(100, 0, 0),
+            None,
+            800,
+        ),
+        event(
+            3,
+            "RebalanceEvent",
+            &validator_b,
+            decrease(0, 300, 50),
+            None,
+            800,
+        ),
+        event(
+            4,
+            "DirectedRebalanceEvent",
+            &validator_b,
+            doc! {
+                "rebalance_type_tag": "Decrease",
+                "increase_lamports": 0_i64,
+                "decrease_lamports": 25_i64,
+            },
+            None,
+            800,
+        ),
+        // Failed transactions do not move stake
+        event(
+            5,
+            "RebalanceEvent",
+            &validator_a,
+            increase(1_000),
+            Some("InstructionError"),
+            800,
+        ),
+        event(
+            5,
+            "RebalanceEvent",
+            &validator_b,
+            increase(1_000),
+            Some("InstructionError"),
+            800,
+        ),
+        event(
+            6,
+            "RebalanceEvent",
+            &validator_b,
+            decrease(7, 0, 0),
+            Some("InstructionError"),
+            800,
+        ),
+        // Other epochs and event types are left out
+        event(7, "RebalanceEvent", &validator_a, increase(900), None, 801),
+        event(
+            8,
+            "ScoreComponentsV4",
+            &validator_a,
+            doc! {"score": 0_i64},
+            None,
+            800,
+        ),
+    ];
+    c.insert_many(docs, None).await.unwrap();
+
+    let store = StewardEventsStore::new(c);
+    let mut expected = vec![
+        ValidatorRebalance {
+            vote_account: validator_a.to_string(),
+            rebalances: 2,
+            increase_lamports: 500,
+            scoring_unstake_lamports: 100,
+            ..Default::default()
+        },
+        ValidatorRebalance {
+            vote_account: validator_b.to_string(),
+            rebalances: 2,
+            instant_unstake_lamports: 300,
+            stake_deposit_unstake_lamports: 50,
+            directed_unstake_lamports: 25,
+            ..Default::default()
+        },
+    ];
+    expected.sort_by(|a, b| a.vote_account.cmp(&b.vote_account));
+
+    assert_eq!(store.get_rebalance_summary(800).await.unwrap(), expected);
+    let rebalance_b = expected
+        .iter()
+        .find(|rebalance| rebalance.vote_account == validator_b.to_string())
+        .unwrap();
+    assert_eq!(rebalance_b.decrease_lamports(), 375);
+    assert_eq!(
+        store
+            .count_failed_rebalance_transactions(800)
+            .await
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        store
+            .count_failed_rebalance_transactions(801)
+            .await
+            .unwrap(),
+        0
+    );
+}
@@ -1,11 +1,15 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 use kobe_core::validators_app::Cluster;
 use solana_pubkey::Pubkey;
 
 fn validate_network(network: &str) -> Result<String, String> {
     match network {
-        "mainnet-beta" | "mainnet" | "m" | "testnet" | "t" | "devnet" => Ok(network.to_string()),
-        _ => Err("Network must be devnet or testnet or mainnet-beta".to_string()),
+        "mainnet-beta" | "mainnet" | "m" | "testnet" | "t" | "devnet" | "localnet" => {
+            Ok(network.to_string())
+        }
+        _ => Err("Network must be localnet or devnet or testnet or mainnet-beta".to_string()),
     }
 }
 
@@ -23,6 +27,10 @@ pub struct Args {
     )]
     pub config_file: Option<String>,
 
+    /// Cluster config file (TOML), defaults to the profiles embedded in kobe-core
+    #[arg(long = "cluster-config", value_name = "PATH", env = "CLUSTER_CONFIG")]
+    pub cluster_config: Option<PathBuf>,
+
     /// Stake pool address
     #[arg(
         long = "pool-address",
@@ -48,7 +56,7 @@ pub struct Args {
     #[arg(long = "simulate", env = "SIMULATE")]
     pub simulate: bool,
 
-    /// Network to use (testnet, mainnet-beta, mainnet, m, t)
+    /// Network to use (localnet, devnet, testnet, mainnet-beta, mainnet, m, t)
     #[arg(
         long = "network",
         env = "SOLANA_CLUSTER",
@@ -91,6 +99,7 @@ impl Args {
             "testnet" | "t" => Cluster::Testnet,
             "mainnet-beta" | "mainnet" | "m" => Cluster::MainnetBeta,
             "devnet" | "d" => Cluster::Devnet,
+            "localnet" => Cluster::Localnet,
             _ => panic!("invalid cluster specified"), // This shouldn't happen due to validation
         }
     }
@@ -108,6 +117,7 @@ impl Args {
                 Cluster::MainnetBeta => "https://api.mainnet-beta.solana.com".into(),
                 Cluster::Testnet => "https://api.testnet.solana.com".into(),
                 Cluster::Devnet => "https://api.devnet.solana.com".into(),
+                Cluster::Localnet => "http://127.0.0.1:8899".into(),
             })
     }
 }
@@ -7,7 +7,7 @@ use backon::{ExponentialBuilder, Retryable};
 use bincode::deserialize;
 use clap::Parser;
 use env_logger::{Builder, Target};
-use kobe_core::validators_app::Cluster;
+use kobe_core::cluster_config::ClusterConfig;
 use log::*;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_epoch_rewards::EpochRewards;
@@ -124,6 +124,9 @@ fn main() -> anyhow::Result<()> {
     info!("Sentry guard initialized");
 
     let cluster = args.get_cluster();
+    let stake_pool_program_id =
+        ClusterConfig::load_stake_pool_program_id(args.cluster_config.as_deref(), cluster)
+            .expect("Failed to load cluster config");
 
     let hostname_cmd = std::process::Command::new("hostname")
         .output()
@@ -154,7 +157,7 @@ fn main() -> anyhow::Result<()> {
 
         Config {
             rpc_client,
-            cluster: Cluster::get_cluster(&args.network).expect("Failed to get cluster"),
+            stake_pool_program_id,
             fee_payer,
             stake_pool_address,
             dry_run: args.dry_run,
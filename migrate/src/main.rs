@@ -0,0 +1,65 @@
+use clap::{Parser, Subcommand};
+use env_logger::Env;
+use kobe_core::{constants::DATABASE_NAME, migrations::MigrationRunner};
+use log::info;
+use mongodb::Client;
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    #[clap(subcommand)]
+    command: Commands,
+
+    /// Mongo connection URI.
+    #[clap(long, env)]
+    mongo_connection_uri: String,
+
+    /// Mongo database name.
+    #[clap(long, env, default_value = DATABASE_NAME)]
+    mongo_db_name: String,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// List applied and pending migrations
+    Status,
+
+    /// Apply pending migrations
+    Run,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
+    let args = Args::parse();
+
+    let client = Client::with_uri_str(&args.mongo_connection_uri).await?;
+    let runner = MigrationRunner::new(client.database(&args.mongo_db_name));
+
+    match args.command {
+        Commands::Status => {
+            for migration in runner.applied().await? {
+                println!(
+                    "applied  {:>3}  {}  ({})",
+                    migration.version, migration.description, migration.applied_at
+                );
+            }
+            for migration in runner.pending().await? {
+                println!(
+                    "pending  {:>3}  {}",
+                    migration.version, migration.description
+                );
+            }
+        }
+        Commands::Run => {
+            let applied = runner.run().await?;
+            if applied.is_empty() {
+                info!("No pending migrations");
+            } else {
+                info!("Applied migrations {applied:?}");
+            }
+        }
+    }
+
+    Ok(())
+}
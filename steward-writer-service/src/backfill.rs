@@ -0,0 +1,297 @@
+//! Parallel, checkpointed backfill of steward events.
+//!
+//! The requested slot range is split into windows aligned on multiples of the window size, and
+//! windows are backfilled concurrently. Each window pages `getSignaturesForAddress` backward from
+//! its last slot and records the oldest signature processed after every page, so an interrupted
+//! backfill resumes where it stopped and completed windows are skipped.
+
+use std::str::FromStr;
+
+use futures::{stream, StreamExt};
+use kobe_core::{
+    db_models::{
+        steward_backfill_checkpoints::{StewardBackfillCheckpoint, StewardBackfillCheckpointStore},
+        steward_events::StewardEventsStore,
+    },
+    rpc_utils::retry,
+};
+use log::{error, info, warn};
+use mongodb::bson::DateTime;
+use solana_client::{
+    client_error::ClientError, nonblocking::rpc_client::RpcClient,
+    rpc_client::GetConfirmedSignaturesForAddress2Config, rpc_config::RpcBlockConfig,
+};
+use solana_metrics::datapoint_info;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::TransactionDetails;
+
+use crate::{fetch_and_process_transactions, NUM_TRANSACTIONS};
+
+pub struct Backfill<'a> {
+    pub program_id: &'a Pubkey,
+    pub stake_pool: &'a Pubkey,
+    pub rpc_client: &'a RpcClient,
+    pub store: &'a StewardEventsStore,
+    pub checkpoints: &'a StewardBackfillCheckpointStore,
+    pub dry_run: bool,
+    pub cluster_name: &'a str,
+}
+
+impl Backfill<'_> {
+    /// Backfill `start_slot..=end_slot`, `concurrency` windows of `window_slots` at a time
+    ///
+    /// Every window is attempted; the error lists the windows that failed, which are resumed from
+    /// their checkpoint by the next run.
+    pub async fn run(
+        &self,
+        start_slot: u64,
+        end_slot: u64,
+        window_slots: u64,
+        concurrency: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let windows = slot_windows(start_slot, end_slot, window_slots);
+        info!(
+            "Backfilling slots {start_slot}..={end_slot} in {} windows",
+            windows.len()
+        );
+
+        let failed: Vec<(u64, u64)> = stream::iter(windows)
+            .map(|(start, end)| async move {
+                match self.backfill_window(start, end).await {
+                    Ok(checkpoint) => {
+                        info!(
+                            "Backfilled slots {start}..={end}: {} events",
+                            checkpoint.events_written
+                        );
+                        None
+                    }
+                    Err(e) => {
+                        error!("Error backfilling slots {start}..={end}: {e:?}");
+                        Some((start, end))
+                    }
+                }
+            })
+            .buffer_unordered(concurrency)
+            .filter_map(|failed| async move { failed })
+            .collect()
+            .await;
+
+        if !failed.is_empty() {
+            return Err(format!("Failed to backfill windows {failed:?}").into());
+        }
+
+        Ok(())
+    }
+
+    /// Backfill a single window from its checkpoint
+    async fn backfill_window(
+        &self,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> Result<StewardBackfillCheckpoint, Box<dyn std::error::Error>> {
+        let stream = StewardBackfillCheckpoint::stream(
+            StewardEventsStore::COLLECTION,
+            &self.program_id.to_string(),
+            &self.stake_pool.to_string(),
+        );
+        let mut checkpoint = self
+            .checkpoints
+            .find(&stream, start_slot, end_slot)
+            .await?
+            .unwrap_or_else(|| StewardBackfillCheckpoint::new(&stream, start_slot, end_slot));
+        if checkpoint.completed {
+            info!("Slots {start_slot}..={end_slot} already backfilled, skipping");
+            return Ok(checkpoint);
+        }
+
+        let mut rpc_errors = 0;
+        // Backoff of the failed signature pages, restarted by every page fetched
+        let mut retry_delays = retry();
+        let mut before = match &checkpoint.cursor_signature {
+            Some(signature) => Some(Signature::from_str(signature)?),
+            None => anchor_signature(self.rpc_client, end_slot).await?,
+        };
+
+        loop {
+            let signatures = match self
+                .rpc_client
+                .get_signatures_for_address_with_config(
+                    self.program_id,
+                    GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until: None,
+                        limit: Some(NUM_TRANSACTIONS),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                    },
+                )
+                .await
+            {
+                Ok(signatures) => {
+                    retry_delays = retry();
+                    signatures
+                }
+                Err(e) => {
+                    warn!("Error fetching RPC signatures before {before:?}: {e}");
+                    rpc_errors += 1;
+                    self.report(&checkpoint, rpc_errors);
+                    // The checkpoint is left on the last page processed, resumed by the next run
+                    let Some(delay) = retry_delays.next() else {
+                        return Err(e.into());
+                    };
+                    tokio::time::sleep(delay).await;
+                    continue;
+                }
+            };
+
+            // Newest first, an empty page is the start of the program history
+            let Some(oldest) = signatures.last().cloned() else {
+                break;
+            };
+
+            let window_signatures: Vec<_> = signatures
+                .into_iter()
+                .filter(|status| (start_slot..=end_slot).contains(&status.slot))
+                .rev()
+                .collect();
+            if !window_signatures.is_empty() {
+                info!(
+                    "Processing {} transactions starting at slot {}",
+                    window_signatures.len(),
+                    window_signatures[0].slot
+                );
+                // The checkpoint is left on the previous page, which is retried on resume
+                let events = match fetch_and_process_transactions(
+                    self.rpc_client,
+                    &window_signatures,
+                    self.program_id,
+                    self.stake_pool,
+                    self.store,
+                    self.dry_run,
+                )
+                .await
+                {
+                    Ok(events) => events,
+                    Err(e) => {
+                        rpc_errors += 1;
+                        self.report(&checkpoint, rpc_errors);
+                        return Err(e);
+                    }
+                };
+                checkpoint.events_written += events as u64;
+            }
+
+            checkpoint.cursor_slot = Some(oldest.slot);
+            checkpoint.cursor_signature = Some(oldest.signature.clone());
+            self.save(&mut checkpoint).await?;
+            self.report(&checkpoint, rpc_errors);
+
+            if oldest.slot < start_slot {
+                break;
+            }
+            before = Some(Signature::from_str(&oldest.signature)?);
+        }
+
+        checkpoint.completed = true;
+        self.save(&mut checkpoint).await?;
+        self.report(&checkpoint, rpc_errors);
+
+        Ok(checkpoint)
+    }
+
+    async fn save(
+        &self,
+        checkpoint: &mut StewardBackfillCheckpoint,
+    ) -> Result<(), mongodb::error::Error> {
+        checkpoint.updated_at = DateTime::now();
+        if self.dry_run {
+            return Ok(());
+        }
+        self.checkpoints.upsert(checkpoint).await
+    }
+
+    fn report(&self, checkpoint: &StewardBackfillCheckpoint, rpc_errors: u64) {
+        datapoint_info!(
+            "steward_writer_service-backfill",
+            ("start_slot", checkpoint.start_slot, i64),
+            ("end_slot", checkpoint.end_slot, i64),
+            ("slots_covered", checkpoint.slots_covered(), i64),
+            ("events_written", checkpoint.events_written, i64),
+            ("rpc_errors", rpc_errors, i64),
+            ("completed", checkpoint.completed, bool),
+            "cluster" => self.cluster_name,
+        );
+    }
+}
+
+/// Split `start_slot..=end_slot` into windows of `window_slots` aligned on multiples of
+/// `window_slots`, so the same windows come out when the range is extended
+fn slot_windows(start_slot: u64, end_slot: u64, window_slots: u64) -> Vec<(u64, u64)> {
+    let mut windows = vec![];
+    let mut start = start_slot;
+    while start <= end_slot {
+        let end = (start / window_slots + 1)
+            .saturating_mul(window_slots)
+            .saturating_sub(1)
+            .min(end_slot);
+        windows.push((start, end));
+        if end == u64::MAX {
+            break;
+        }
+        start = end + 1;
+    }
+
+    windows
+}
+
+/// Signature to page back from so the first page of a window starts at `end_slot`
+///
+/// `getSignaturesForAddress` pages by signature, so the window is anchored on the first
+/// transaction of the first block after `end_slot`. `None` when there is no such block yet, paging
+/// then starts at the tip.
+async fn anchor_signature(
+    rpc_client: &RpcClient,
+    end_slot: u64,
+) -> Result<Option<Signature>, ClientError> {
+    let Some(slot) = rpc_client
+        .get_blocks_with_limit(end_slot + 1, 1)
+        .await?
+        .first()
+        .copied()
+    else {
+        return Ok(None);
+    };
+
+    let block = rpc_client
+        .get_block_with_config(
+            slot,
+            RpcBlockConfig {
+                encoding: None,
+                transaction_details: Some(TransactionDetails::Signatures),
+                rewards: Some(false),
+                commitment: Some(CommitmentConfig::finalized()),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await?;
+
+    Ok(block
+        .signatures
+        .and_then(|signatures| signatures.first().cloned())
+        .and_then(|signature| Signature::from_str(&signature).ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_windows() {
+        assert_eq!(
+            slot_windows(150, 420, 100),
+            vec![(150, 199), (200, 299), (300, 399), (400, 420)]
+        );
+        assert_eq!(slot_windows(200, 299, 100), vec![(200, 299)]);
+        assert_eq!(slot_windows(5, 5, 100), vec![(5, 5)]);
+        assert!(slot_windows(10, 5, 100).is_empty());
+    }
+}
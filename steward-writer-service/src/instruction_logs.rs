@@ -0,0 +1,162 @@
+//! Attribution of transaction log lines to the instruction that emitted them.
+//!
+//! The runtime logs `Program <id> invoke [<depth>]` when an instruction starts and
+//! `Program <id> success` / `Program <id> failed: <err>` when it ends. Depth 1 is a top-level
+//! instruction, deeper invocations are its inner instructions, numbered in invocation order like
+//! the transaction's `inner_instructions`.
+
+/// Log line with the instruction that emitted it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionLog {
+    /// Index of the top-level instruction
+    pub instruction_idx: u32,
+
+    /// Index among the inner instructions of `instruction_idx`, `None` for the top-level
+    /// instruction itself
+    pub inner_instruction_idx: Option<u32>,
+
+    /// Program executing when the line was logged
+    pub program_id: Option<String>,
+
+    /// Log line
+    pub log: String,
+}
+
+/// Attribute each log line of a transaction to the instruction executing when it was logged
+pub fn attribute_logs(logs: Vec<String>) -> Vec<InstructionLog> {
+    let mut attributed = Vec::with_capacity(logs.len());
+
+    let mut instruction_idx: Option<u32> = None;
+    let mut next_inner_instruction_idx = 0;
+    // (program id, inner instruction index) of every instruction being executed
+    let mut stack: Vec<(String, Option<u32>)> = Vec::new();
+
+    for log in logs {
+        if let Some((program_id, depth)) = parse_invoke(&log) {
+            if depth <= 1 {
+                instruction_idx = Some(instruction_idx.map_or(0, |idx| idx + 1));
+                next_inner_instruction_idx = 0;
+                stack.clear();
+                stack.push((program_id, None));
+            } else {
+                stack.truncate(depth - 1);
+                stack.push((program_id, Some(next_inner_instruction_idx)));
+                next_inner_instruction_idx += 1;
+            }
+        }
+
+        let frame = stack.last();
+        let is_exit = is_program_exit(&log);
+        attributed.push(InstructionLog {
+            instruction_idx: instruction_idx.unwrap_or(0),
+            inner_instruction_idx: frame.and_then(|(_, inner)| *inner),
+            program_id: frame.map(|(program_id, _)| program_id.clone()),
+            log,
+        });
+
+        if is_exit {
+            stack.pop();
+        }
+    }
+
+    attributed
+}
+
+/// Parse `Program <id> invoke [<depth>]`
+fn parse_invoke(log: &str) -> Option<(String, usize)> {
+    let (program_id, depth) = log
+        .strip_prefix("Program ")?
+        .strip_suffix(']')?
+        .split_once(" invoke [")?;
+
+    Some((program_id.to_string(), depth.parse().ok()?))
+}
+
+/// Whether the line is `Program <id> success` or `Program <id> failed: <err>`
+fn is_program_exit(log: &str) -> bool {
+    let Some((program_id, status)) = log
+        .strip_prefix("Program ")
+        .and_then(|rest| rest.split_once(' '))
+    else {
+        return false;
+    };
+
+    // Excludes `Program log:`, `Program data:` and `Program return:` lines
+    !program_id.ends_with(':') && (status == "success" || status.starts_with("failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEWARD: &str = "Stewardf95sJbmtcZsyagb2dg4Mo8eVQho8gpECvLx8";
+    const STAKE_POOL: &str = "SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy";
+    const STAKE: &str = "Stake11111111111111111111111111111111111111";
+
+    #[test]
+    fn test_top_level_instructions() {
+        let attributed = attribute_logs(vec![
+            format!("Program {STEWARD} invoke [1]"),
+            "Program data: AAAA".to_string(),
+            format!("Program {STEWARD} success"),
+            format!("Program {STEWARD} invoke [1]"),
+            "Program data: BBBB".to_string(),
+            format!("Program {STEWARD} success"),
+        ]);
+
+        let data: Vec<(u32, Option<u32>)> = attributed
+            .iter()
+            .filter(|l| l.log.starts_with("Program data:"))
+            .map(|l| (l.instruction_idx, l.inner_instruction_idx))
+            .collect();
+        assert_eq!(data, vec![(0, None), (1, None)]);
+    }
+
+    #[test]
+    fn test_inner_instructions() {
+        let attributed = attribute_logs(vec![
+            format!("Program {STEWARD} invoke [1]"),
+            format!("Program {STAKE_POOL} invoke [2]"),
+            format!("Program {STAKE} invoke [3]"),
+            format!("Program {STAKE} success"),
+            "Program log: pool success".to_string(),
+            format!("Program {STAKE_POOL} success"),
+            "Program data: AAAA".to_string(),
+            format!("Program {STAKE_POOL} invoke [2]"),
+            format!("Program {STAKE_POOL} success"),
+            "Program data: BBBB".to_string(),
+            format!("Program {STEWARD} success"),
+        ]);
+
+        // `Program log: ... success` does not end the stake pool instruction
+        let pool_log = &attributed[4];
+        assert_eq!(pool_log.program_id.as_deref(), Some(STAKE_POOL));
+        assert_eq!(pool_log.inner_instruction_idx, Some(0));
+
+        // Stake program invoked by the stake pool is the second inner instruction
+        assert_eq!(attributed[2].inner_instruction_idx, Some(1));
+
+        let data: Vec<(Option<u32>, Option<&str>)> = attributed
+            .iter()
+            .filter(|l| l.log.starts_with("Program data:"))
+            .map(|l| (l.inner_instruction_idx, l.program_id.as_deref()))
+            .collect();
+        assert_eq!(data, vec![(None, Some(STEWARD)), (None, Some(STEWARD))]);
+
+        // Third inner instruction of the same top-level instruction
+        assert_eq!(attributed[7].inner_instruction_idx, Some(2));
+    }
+
+    #[test]
+    fn test_failed_instruction() {
+        let attributed = attribute_logs(vec![
+            format!("Program {STEWARD} invoke [1]"),
+            format!("Program {STAKE_POOL} invoke [2]"),
+            format!("Program {STAKE_POOL} failed: custom program error: 0x1"),
+            "Program data: AAAA".to_string(),
+        ]);
+
+        assert_eq!(attributed[3].program_id.as_deref(), Some(STEWARD));
+        assert_eq!(attributed[3].inner_instruction_idx, None);
+    }
+}
@@ -0,0 +1,320 @@
+//! Websocket live mode.
+//!
+//! Events are decoded from `logsSubscribe` notifications as soon as their transaction is
+//! confirmed, and written with `commitment: "confirmed"`. The signature poller keeps running next
+//! to the subscription: it rewrites those events once finalized, picks up the transactions missed
+//! while the websocket was disconnected and drops the confirmed events that never finalized.
+
+use std::{str::FromStr, time::Duration};
+
+use futures::StreamExt;
+use kobe_core::{db_models::steward_events::StewardEventsStore, rpc_utils::retry_get_transaction};
+use log::{error, info, warn};
+use solana_client::{
+    nonblocking::{
+        pubsub_client::{PubsubClient, PubsubClientError},
+        rpc_client::RpcClient,
+    },
+    rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+};
+use solana_metrics::datapoint_info;
+use solana_sdk::{
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::TransactionError,
+};
+use tokio::sync::{mpsc, Notify};
+
+use crate::{transaction_events, transaction_signer, write_events};
+
+/// Delay before reconnecting a closed subscription
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Notifications buffered between the subscription and the writer
+///
+/// When the writer falls behind the subscription stops reading the websocket instead of growing
+/// the buffer; transactions the node drops in the meantime are picked up by the signature poller.
+const NOTIFICATION_BUFFER: usize = 1_000;
+
+/// Logs of a transaction mentioning the subscribed program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogsNotification {
+    pub signature: String,
+    pub slot: u64,
+    pub err: Option<TransactionError>,
+    pub logs: Vec<String>,
+}
+
+/// Subscribe to the logs of `program_id` and write its events until the process exits
+///
+/// Reconnects whenever the subscription closes, notifying `gap_fill` so the signature poller
+/// fetches the transactions emitted in the meantime.
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    ws_url: &str,
+    program_id: &Pubkey,
+    stake_pool: &Pubkey,
+    store: &StewardEventsStore,
+    rpc_client: &RpcClient,
+    gap_fill: &Notify,
+    dry_run: bool,
+    cluster_name: &str,
+) {
+    loop {
+        let (sender, mut receiver) = mpsc::channel(NOTIFICATION_BUFFER);
+
+        let subscription = async move {
+            let sender = sender;
+            stream_logs(ws_url, program_id, CommitmentConfig::confirmed(), &sender).await
+        };
+        let writer = async {
+            while let Some(notification) = receiver.recv().await {
+                let signature = notification.signature.clone();
+                let slot = notification.slot;
+                match write_notification(
+                    notification,
+                    program_id,
+                    stake_pool,
+                    store,
+                    rpc_client,
+                    dry_run,
+                )
+                .await
+                {
+                    Ok(0) => {}
+                    Ok(events) => datapoint_info!(
+                        "steward_writer_service-live_events",
+                        ("events", events, i64),
+                        ("slot", slot, i64),
+                        "cluster" => cluster_name,
+                    ),
+                    Err(e) => error!("Error writing events of transaction {signature}: {e:?}"),
+                }
+            }
+        };
+
+        match tokio::join!(subscription, writer).0 {
+            Ok(()) => warn!("Logs subscription closed"),
+            Err(e) => error!("Logs subscription failed: {e}"),
+        }
+        datapoint_info!(
+            "steward_writer_service-ws_disconnect",
+            ("count", 1, i64),
+            "cluster" => cluster_name,
+        );
+
+        gap_fill.notify_one();
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Forward the logs of every transaction mentioning `program_id` to `sender`, until the
+/// subscription is closed
+pub async fn stream_logs(
+    ws_url: &str,
+    program_id: &Pubkey,
+    commitment: CommitmentConfig,
+    sender: &mpsc::Sender<LogsNotification>,
+) -> Result<(), PubsubClientError> {
+    let client = PubsubClient::new(ws_url).await?;
+    let (mut notifications, unsubscribe) = client
+        .logs_subscribe(
+            RpcTransactionLogsFilter::Mentions(vec![program_id.to_string()]),
+            RpcTransactionLogsConfig {
+                commitment: Some(commitment),
+            },
+        )
+        .await?;
+    info!("Subscribed to logs of {program_id}");
+
+    while let Some(response) = notifications.next().await {
+        let notification = LogsNotification {
+            signature: response.value.signature,
+            slot: response.context.slot,
+            err: response.value.err,
+            logs: response.value.logs,
+        };
+        if sender.send(notification).await.is_err() {
+            break;
+        }
+    }
+
+    unsubscribe().await;
+    Ok(())
+}
+
+/// Decode and write the events of a notification, returning the number of events written
+async fn write_notification(
+    notification: LogsNotification,
+    program_id: &Pubkey,
+    stake_pool: &Pubkey,
+    store: &StewardEventsStore,
+    rpc_client: &RpcClient,
+    dry_run: bool,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    if !notification
+        .logs
+        .iter()
+        .any(|log| log.starts_with("Program data:"))
+    {
+        return Ok(0);
+    }
+
+    // Signer and block time are not part of the notification
+    let signature = Signature::from_str(&notification.signature)?;
+    let transaction =
+        retry_get_transaction(rpc_client, &signature, CommitmentConfig::confirmed()).await?;
+    let Some(signer) = transaction_signer(&signature, &transaction) else {
+        return Ok(0);
+    };
+
+    let events = transaction_events(
+        &signature,
+        notification.slot,
+        notification.err,
+        transaction.block_time,
+        &signer,
+        notification.logs,
+        program_id,
+        stake_pool,
+        CommitmentLevel::Confirmed,
+    )
+    .await;
+    let num_events = events.len();
+    write_events(events, store, dry_run).await?;
+
+    Ok(num_events)
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_client::anchor_lang::Event;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use futures::SinkExt;
+    use jito_steward::events::StateTransition;
+    use serde_json::{json, Value};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+    use super::*;
+
+    const SUBSCRIPTION_ID: u64 = 7;
+
+    /// Local stand-in for the pubsub endpoint of an RPC node: acknowledges the first
+    /// subscription, pushes `notifications` and closes the connection, returning the request
+    async fn serve_logs(notifications: Vec<Value>) -> (String, tokio::task::JoinHandle<Value>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+
+            let request: Value = loop {
+                if let Message::Text(text) = ws.next().await.unwrap().unwrap() {
+                    break serde_json::from_str(&text).unwrap();
+                }
+            };
+            let ack = json!({"jsonrpc": "2.0", "result": SUBSCRIPTION_ID, "id": request["id"]});
+            ws.send(Message::Text(ack.to_string())).await.unwrap();
+
+            for result in notifications {
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": "logsNotification",
+                    "params": {"result": result, "subscription": SUBSCRIPTION_ID},
+                });
+                ws.send(Message::Text(notification.to_string()))
+                    .await
+                    .unwrap();
+            }
+            ws.close(None).await.unwrap();
+
+            request
+        });
+
+        (url, handle)
+    }
+
+    fn state_transition_logs() -> Vec<String> {
+        let event = StateTransition {
+            epoch: 800,
+            slot: 345_600_000,
+            previous_state: "Idle".to_string(),
+            new_state: "ComputeInstantUnstake".to_string(),
+        };
+        let program = jito_steward::id();
+
+        vec![
+            format!("Program {program} invoke [1]"),
+            format!("Program data: {}", STANDARD.encode(event.data())),
+            format!("Program {program} success"),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_stream_logs() {
+        let signature = Signature::from([7; 64]).to_string();
+        let logs = state_transition_logs();
+        let (url, server) = serve_logs(vec![json!({
+            "context": {"slot": 345_600_000},
+            "value": {"signature": signature.clone(), "err": null, "logs": logs.clone()},
+        })])
+        .await;
+
+        let program_id = jito_steward::id();
+        let (sender, mut receiver) = mpsc::channel(NOTIFICATION_BUFFER);
+        stream_logs(&url, &program_id, CommitmentConfig::confirmed(), &sender)
+            .await
+            .unwrap();
+        drop(sender);
+
+        let request = server.await.unwrap();
+        assert_eq!(request["method"], "logsSubscribe");
+        assert_eq!(
+            request["params"][0],
+            json!({"mentions": [program_id.to_string()]})
+        );
+        assert_eq!(request["params"][1]["commitment"], "confirmed");
+
+        assert_eq!(
+            receiver.recv().await,
+            Some(LogsNotification {
+                signature,
+                slot: 345_600_000,
+                err: None,
+                logs,
+            })
+        );
+        assert_eq!(receiver.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_notification_events() {
+        let signature = Signature::from([7; 64]);
+        let signer = Pubkey::new_unique();
+        let stake_pool = Pubkey::new_unique();
+
+        let events = transaction_events(
+            &signature,
+            345_600_001,
+            None,
+            Some(1_700_000_000),
+            &signer,
+            state_transition_logs(),
+            &jito_steward::id(),
+            &stake_pool,
+            CommitmentLevel::Confirmed,
+        )
+        .await;
+
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.event_type, "StateTransition");
+        assert_eq!(event.epoch, 800);
+        assert_eq!(event.slot, 345_600_001);
+        assert_eq!(event.commitment.as_deref(), Some("confirmed"));
+        assert!(!event.is_finalized());
+    }
+}
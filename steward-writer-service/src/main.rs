@@ -1,6 +1,7 @@
-use std::{str::FromStr, time::Duration};
+use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
 
 use anchor_client::handle_program_log;
+use base64::{engine::general_purpose::STANDARD, Engine};
 use clap::{Parser, Subcommand};
 use jito_steward::{
     events::{
@@ -10,22 +11,43 @@ use jito_steward::{
     },
     score::{InstantUnstakeComponentsV3, ScoreComponentsV4},
 };
+use kobe_core::cluster_config::ClusterConfig;
+use kobe_core::db_models::steward_backfill_checkpoints::StewardBackfillCheckpointStore;
 use kobe_core::db_models::steward_events::{StewardEvent, StewardEventsStore};
+use kobe_core::db_models::steward_parameters::StewardParametersStore;
+use kobe_core::db_models::steward_state_snapshots::StewardStateSnapshotStore;
 use kobe_core::rpc_utils::{retry_get_slot, retry_get_transactions};
+use kobe_core::validators_app::Cluster;
 use log::{debug, error, info};
 use mongodb::{Client, Collection};
+use solana_cli_config::Config;
 use solana_client::{
     nonblocking::rpc_client::RpcClient, rpc_client::GetConfirmedSignaturesForAddress2Config,
     rpc_response::RpcConfirmedTransactionStatusWithSignature,
 };
 use solana_metrics::datapoint_info;
 use solana_sdk::{
-    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature,
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    pubkey::Pubkey,
+    signature::Signature,
     transaction::TransactionError,
 };
 use solana_transaction_status::{
     option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
 };
+use tokio::sync::Notify;
+
+use crate::{
+    backfill::Backfill,
+    instruction_logs::{attribute_logs, InstructionLog},
+    snapshot::StateSnapshotter,
+};
+
+mod backfill;
+mod instruction_logs;
+mod live;
+mod redecode;
+mod snapshot;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -45,13 +67,25 @@ struct Args {
     #[clap(long, env)]
     rpc_url: String,
 
-    /// Program ID
+    /// Solana cluster e.g. testnet, mainnet, devnet, localnet
+    #[clap(long, env, default_value = "mainnet")]
+    solana_cluster: String,
+
+    /// Cluster config file (TOML), defaults to the profiles embedded in kobe-core
     #[clap(long, env)]
-    program_id: Pubkey,
+    cluster_config: Option<PathBuf>,
 
-    /// Stake pool address
+    /// Program ID, overrides the steward program of the cluster config
     #[clap(long, env)]
-    stake_pool: Pubkey,
+    program_id: Option<Pubkey>,
+
+    /// Stake pool address, overrides the stake pool of the cluster config
+    #[clap(long, env)]
+    stake_pool: Option<Pubkey>,
+
+    /// Steward config account address, overrides the cluster config
+    #[clap(long, env)]
+    steward_config: Option<Pubkey>,
 
     /// Whether to dry run before writing to db
     #[clap(long, env, action)]
@@ -60,12 +94,24 @@ struct Args {
     /// Cluster name for metrics
     #[clap(long, env, default_value = "mainnet")]
     cluster_name: String,
+
+    /// Seconds between two polls of the program signatures
+    #[clap(long, env, default_value_t = 300)]
+    polling_interval_secs: u64,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Listen for new events
     Listen,
+    /// Stream new events over a websocket logs subscription, polling signatures to fill gaps
+    Live {
+        /// Websocket URL, derived from the RPC URL by default
+        #[clap(long, env)]
+        ws_url: Option<String>,
+    },
+    /// Re-decode events stored as `Unknown` with the current event decoders
+    Redecode,
     /// Backfill events from a specific slot range
     Backfill {
         /// Start slot for backfilling
@@ -74,6 +120,22 @@ enum Commands {
         /// End slot for backfilling (optional)
         #[clap(long)]
         end_slot: Option<u64>,
+        /// Slots per window, windows are aligned on multiples of this size so their checkpoints
+        /// are found again when resuming
+        #[clap(long, default_value_t = 100_000, value_parser = clap::value_parser!(u64).range(1..))]
+        window_slots: u64,
+        /// Number of windows backfilled concurrently
+        #[clap(long, default_value_t = 4, value_parser = clap::value_parser!(usize).range(1..))]
+        concurrency: usize,
+    },
+    /// Snapshot the steward state and validator list at points of each epoch
+    SnapshotState {
+        /// Epoch progress thresholds to snapshot at (0.0-1.0, default 50%, 75%, 90%)
+        #[clap(long, env, value_delimiter = ',', default_value = "0.5,0.75,0.9")]
+        epoch_progress_thresholds: Vec<f64>,
+        /// Poll interval in seconds
+        #[clap(long, env, default_value = "60")]
+        poll_interval_secs: u64,
     },
 }
 
@@ -81,6 +143,14 @@ enum Commands {
 async fn main() {
     env_logger::init();
     let args = Args::parse();
+
+    let cluster = Cluster::get_cluster(&args.solana_cluster).expect("Failed to get cluster");
+    let cluster_config = ClusterConfig::load(args.cluster_config.as_deref(), cluster)
+        .expect("Failed to load cluster config");
+    let program_id = args.program_id.unwrap_or(cluster_config.steward_program_id);
+    let stake_pool = args.stake_pool.unwrap_or(cluster_config.stake_pool_address);
+    let steward_config = args.steward_config.unwrap_or(cluster_config.steward_config);
+
     // Connect to MongoDB
     let client = Client::with_uri_str(&args.mongo_connection_uri)
         .await
@@ -89,37 +159,82 @@ async fn main() {
 
     let events_collection: Collection<StewardEvent> = db.collection(StewardEventsStore::COLLECTION);
     let store = StewardEventsStore::new(events_collection);
+    let checkpoints = StewardBackfillCheckpointStore::new(
+        db.collection(StewardBackfillCheckpointStore::COLLECTION),
+    );
+    let snapshots =
+        StewardStateSnapshotStore::new(db.collection(StewardStateSnapshotStore::COLLECTION));
+    let parameters = StewardParametersStore::new(db.collection(StewardParametersStore::COLLECTION));
 
     // Connect to RPC node
-    let client = RpcClient::new_with_timeout_and_commitment(
+    let client = Arc::new(RpcClient::new_with_timeout_and_commitment(
         args.rpc_url,
         Duration::from_secs(20),
         CommitmentConfig::finalized(),
-    );
+    ));
+
+    let polling_duration = Duration::from_secs(args.polling_interval_secs);
+    let gap_fill = Notify::new();
+    let poller = async {
+        loop {
+            if let Err(e) = listen(
+                &program_id,
+                &stake_pool,
+                &store,
+                &client,
+                polling_duration,
+                &gap_fill,
+                args.dry_run,
+                &args.cluster_name,
+            )
+            .await
+            {
+                error!("Error in listen loop: {e:?}");
+            }
+        }
+    };
 
     match args.command {
         Commands::Listen => {
             info!("Listening for new events");
-            let polling_duration = Duration::from_secs(300); // Configurable polling duration (5 mins)
-            loop {
-                if let Err(e) = listen(
-                    &args.program_id,
-                    &args.stake_pool,
+            poller.await;
+        }
+        Commands::Live { ref ws_url } => {
+            let ws_url = ws_url
+                .clone()
+                .unwrap_or_else(|| Config::compute_websocket_url(&client.url()));
+            info!("Streaming new events from {ws_url}");
+            tokio::join!(
+                poller,
+                live::run(
+                    &ws_url,
+                    &program_id,
+                    &stake_pool,
                     &store,
                     &client,
-                    polling_duration,
+                    &gap_fill,
                     args.dry_run,
                     &args.cluster_name,
                 )
-                .await
-                {
-                    error!("Error in listen loop: {e:?}");
-                }
+            );
+        }
+        Commands::Redecode => {
+            if let Err(e) = redecode::redecode_unknown_events(
+                &store,
+                &program_id,
+                args.dry_run,
+                &args.cluster_name,
+            )
+            .await
+            {
+                error!("Error: {e:?}");
             }
         }
         Commands::Backfill {
             start_slot,
             end_slot,
+            window_slots,
+            concurrency,
         } => {
             info!("Backfilling events from slot {start_slot} to {end_slot:?}");
             let end_slot = if let Some(end_slot) = end_slot {
@@ -134,37 +249,71 @@ async fn main() {
                 }
             };
 
-            // Implement backfilling logic here
-            if let Err(e) = fetch_historical_program_transactions(
-                &args.program_id,
-                &client,
-                &args.stake_pool,
-                &store,
-                start_slot,
-                end_slot,
-                args.dry_run,
-            )
-            .await
+            let backfill = Backfill {
+                program_id: &program_id,
+                stake_pool: &stake_pool,
+                rpc_client: &client,
+                store: &store,
+                checkpoints: &checkpoints,
+                dry_run: args.dry_run,
+                cluster_name: &args.cluster_name,
+            };
+            if let Err(e) = backfill
+                .run(start_slot, end_slot, window_slots, concurrency)
+                .await
+            {
+                error!("Error: {e:?}");
+            }
+        }
+        Commands::SnapshotState {
+            ref epoch_progress_thresholds,
+            poll_interval_secs,
+        } => {
+            info!("Snapshotting steward state at {epoch_progress_thresholds:?} of each epoch");
+            let snapshotter = StateSnapshotter {
+                program_id: &program_id,
+                steward_config: &steward_config,
+                rpc_client: &client,
+                store: &snapshots,
+                parameters_store: &parameters,
+                dry_run: args.dry_run,
+                cluster_name: &args.cluster_name,
+            };
+            if let Err(e) = snapshotter
+                .run(
+                    epoch_progress_thresholds,
+                    Duration::from_secs(poll_interval_secs),
+                )
+                .await
             {
-                info!("Error: {e:?}");
+                error!("Error: {e:?}");
             }
         }
     }
 }
 
+/// Poll the program signatures every `polling_duration`, or as soon as `gap_fill` is notified
+///
+/// Writes finalized events, upgrading the ones already written at `confirmed` by the live mode,
+/// then drops the confirmed events that never finalized.
+#[allow(clippy::too_many_arguments)]
 async fn listen(
     program_id: &Pubkey,
     stake_pool: &Pubkey,
     store: &StewardEventsStore,
     rpc_client: &RpcClient,
     polling_duration: Duration,
+    gap_fill: &Notify,
     dry_run: bool,
     cluster_name: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut interval = tokio::time::interval(polling_duration);
 
     loop {
-        interval.tick().await;
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = gap_fill.notified() => info!("Filling gaps"),
+        }
 
         let (latest_signature, slot) = match store.get_latest_signature_and_slot().await? {
             Some((sig, slot)) => (sig, slot),
@@ -218,25 +367,37 @@ async fn listen(
                 .first()
                 .map(|status| Signature::from_str(&status.signature).unwrap());
 
-            fetch_and_process_transactions(rpc_client, &rpc_signatures, stake_pool, store, dry_run)
-                .await?;
+            fetch_and_process_transactions(
+                rpc_client,
+                &rpc_signatures,
+                program_id,
+                stake_pool,
+                store,
+                dry_run,
+            )
+            .await?;
+        }
+
+        if !dry_run {
+            if let Some((_, finalized_slot)) = store.get_latest_signature_and_slot().await? {
+                let deleted = store.delete_unfinalized_before(finalized_slot).await?;
+                if deleted > 0 {
+                    info!("Deleted {deleted} events that never finalized");
+                }
+            }
         }
     }
 }
 
+/// Fetch the transactions of `signatures` and write their events, returning the number of events
 async fn fetch_and_process_transactions(
     rpc_client: &RpcClient,
     signatures: &[RpcConfirmedTransactionStatusWithSignature],
+    program_id: &Pubkey,
     stake_pool: &Pubkey,
     store: &StewardEventsStore,
     dry_run: bool,
-) -> Result<
-    Vec<(
-        RpcConfirmedTransactionStatusWithSignature,
-        EncodedConfirmedTransactionWithStatusMeta,
-    )>,
-    Box<dyn std::error::Error>,
-> {
+) -> Result<usize, Box<dyn std::error::Error>> {
     let transaction_signatures: Vec<Signature> = signatures
         .iter()
         .map(|status| Signature::from_str(&status.signature).unwrap())
@@ -260,9 +421,7 @@ async fn fetch_and_process_transactions(
             transaction_data.push((status.clone(), tx));
         }
     }
-    process_transactions(&transaction_data, stake_pool, store, dry_run).await?;
-
-    Ok(transaction_data)
+    process_transactions(&transaction_data, program_id, stake_pool, store, dry_run).await
 }
 
 async fn process_transactions(
@@ -270,10 +429,11 @@ async fn process_transactions(
         RpcConfirmedTransactionStatusWithSignature,
         EncodedConfirmedTransactionWithStatusMeta,
     )],
+    program_id: &Pubkey,
     stake_pool: &Pubkey,
     store: &StewardEventsStore,
     dry_run: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<usize, Box<dyn std::error::Error>> {
     // If the slot from `signatures` doesn't match the slot in `transactions`, print it out
     for (status, tx) in transactions.iter() {
         if tx.slot != status.slot {
@@ -297,188 +457,176 @@ async fn process_transactions(
 
         let signature = Signature::from_str(signature).unwrap();
 
-        let EncodedConfirmedTransactionWithStatusMeta { transaction, .. } = encoded_tx_with_meta;
-
-        let signer: Pubkey = match transaction.transaction.decode() {
-            Some(tx) => match tx.message.static_account_keys().first() {
-                Some(signer) => *signer,
-                None => {
-                    error!("No signer found in transaction {signature}");
-                    continue;
-                }
-            },
-            None => {
-                error!("No transaction found in encoded transaction {signature}");
-                continue;
-            }
+        let Some(signer) = transaction_signer(&signature, encoded_tx_with_meta) else {
+            continue;
         };
 
-        let epoch = get_epoch_from_slot(*slot);
-        let instruction_idx = 0;
-
         // Process logs
         if let Some(meta) = &encoded_tx_with_meta.transaction.meta {
             if let OptionSerializer::Some(log_messages) = meta.log_messages.clone() {
-                for log in log_messages.into_iter() {
-                    match parse_log(
-                        log,
+                events.extend(
+                    transaction_events(
                         &signature,
-                        instruction_idx as u32,
+                        *slot,
+                        err.clone(),
+                        *block_time,
                         &signer,
+                        log_messages,
+                        program_id,
                         stake_pool,
-                        *block_time,
-                        err.clone(),
-                        epoch,
-                        *slot,
+                        CommitmentLevel::Finalized,
                     )
-                    .await
-                    {
-                        Ok(Some(event)) => events.push(event),
-                        Ok(None) => {}
-                        Err(e) => error!(
-                            "Error parsing log message for transaction {:?}: {:?}",
-                            signature,
-                            e.to_string()
-                        ),
-                    }
-                }
+                    .await,
+                );
             }
         }
     }
 
-    match dry_run {
-        true => {
-            info!("upserting {events:#?}");
-        }
-        false => {
-            if let Err(e) = store.bulk_upsert(events).await {
-                error!("Error inserting events: {e:?}");
+    let num_events = events.len();
+    write_events(events, store, dry_run).await?;
+
+    Ok(num_events)
+}
+
+/// Fee payer of a transaction
+fn transaction_signer(
+    signature: &Signature,
+    encoded_tx_with_meta: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Option<Pubkey> {
+    match encoded_tx_with_meta.transaction.transaction.decode() {
+        Some(tx) => match tx.message.static_account_keys().first() {
+            Some(signer) => Some(*signer),
+            None => {
+                error!("No signer found in transaction {signature}");
+                None
             }
+        },
+        None => {
+            error!("No transaction found in encoded transaction {signature}");
+            None
         }
     }
-
-    Ok(())
 }
 
-const NUM_TRANSACTIONS: usize = 1000;
-
-async fn fetch_historical_program_transactions(
+/// Decode the steward events of a transaction from its logs, tagged with the `commitment` the
+/// logs were read at
+#[allow(clippy::too_many_arguments)]
+async fn transaction_events(
+    signature: &Signature,
+    slot: u64,
+    err: Option<TransactionError>,
+    block_time: Option<i64>,
+    signer: &Pubkey,
+    log_messages: Vec<String>,
     program_id: &Pubkey,
-    rpc_client: &RpcClient,
     stake_pool: &Pubkey,
-    store: &StewardEventsStore,
-    start_slot: u64,
-    end_slot: u64,
-    dry_run: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
-    info!("Backfilling transactions between slots {start_slot} and {end_slot}");
-    let mut before = None;
-    let mut should_break = false;
+    commitment: CommitmentLevel,
+) -> Vec<StewardEvent> {
+    let epoch = get_epoch_from_slot(slot);
+    let tx_error = err.map(|e| e.to_string());
+    let steward_program = program_id.to_string();
 
-    loop {
-        let signatures = match rpc_client
-            .get_signatures_for_address_with_config(
-                program_id,
-                GetConfirmedSignaturesForAddress2Config {
-                    before,
-                    until: None,
-                    limit: Some(NUM_TRANSACTIONS),
-                    commitment: Some(CommitmentConfig::confirmed()),
-                },
-            )
-            .await
-        {
-            Ok(signatures) => signatures,
-            Err(e) => {
-                info!("Error fetching RPC signatures: {e}");
-                continue;
+    let mut events = Vec::new();
+    // Number of events emitted so far by each top-level instruction
+    let mut event_counts: HashMap<u32, u32> = HashMap::new();
+    for InstructionLog {
+        instruction_idx,
+        inner_instruction_idx,
+        program_id,
+        log,
+    } in attribute_logs(log_messages)
+    {
+        let parsed = parse_log(
+            &log,
+            signature,
+            instruction_idx,
+            signer,
+            program_id,
+            stake_pool,
+            block_time,
+            tx_error.clone(),
+            epoch,
+            slot,
+        )
+        .await;
+        // Keep the events of the steward program no decoder knows yet
+        let parsed = match parsed {
+            Ok(None) if program_id.as_deref() == Some(steward_program.as_str()) => {
+                Ok(unknown_event(
+                    &log,
+                    signature,
+                    instruction_idx,
+                    signer,
+                    stake_pool,
+                    block_time,
+                    tx_error.clone(),
+                    epoch,
+                    slot,
+                ))
             }
+            parsed => parsed,
         };
-
-        // Get signatures in chronological order
-        let signatures_vec = signatures.into_iter().collect::<Vec<_>>();
-
-        // Set before to the oldest signature for next iteration
-        before = signatures_vec
-            .last()
-            .map(|status| Signature::from_str(&status.signature).unwrap());
-
-        // Filter out signatures before start_slot and check if we should break after processing
-        let valid_signatures: Vec<RpcConfirmedTransactionStatusWithSignature> = signatures_vec
-            .into_iter()
-            .filter_map(|status| {
-                if status.slot < start_slot {
-                    should_break = true;
-                    None
-                } else if status.slot <= end_slot {
-                    Some(status)
-                } else {
-                    None
-                }
-            })
-            .rev() // We still want to process in chronological order
-            .collect::<Vec<_>>();
-
-        if valid_signatures.is_empty() {
-            continue;
-        }
-
-        info!(
-            "Processing {} transactions starting at slot {}",
-            valid_signatures.len(),
-            valid_signatures[0].slot
-        );
-
-        let transaction_signatures: Vec<Signature> = valid_signatures
-            .iter()
-            .map(|status| Signature::from_str(&status.signature).unwrap())
-            .collect();
-
-        let transactions = retry_get_transactions(rpc_client, &transaction_signatures).await?;
-
-        // Align transactions with signatures
-        let mut transaction_data = vec![];
-        for tx in transactions.into_iter() {
-            let target_signature = if let Some(tx) = tx.transaction.transaction.decode() {
-                *tx.signatures.first().unwrap()
-            } else {
-                continue;
-            };
-            let transaction_status = valid_signatures
-                .iter()
-                .find(|status| Signature::from_str(&status.signature).unwrap() == target_signature);
-            if let Some(status) = transaction_status {
-                transaction_data.push((status.clone(), tx));
+        match parsed {
+            Ok(Some(mut event)) => {
+                let event_idx = event_counts.entry(instruction_idx).or_default();
+                event.inner_instruction_idx = inner_instruction_idx;
+                event.event_idx = *event_idx;
+                event.commitment = Some(commitment.to_string());
+                *event_idx += 1;
+                events.push(event);
             }
+            Ok(None) => {}
+            Err(e) => error!(
+                "Error parsing log message for transaction {:?}: {:?}",
+                signature,
+                e.to_string()
+            ),
         }
+    }
 
-        process_transactions(&transaction_data, stake_pool, store, dry_run).await?;
+    events
+}
 
-        if should_break {
-            break;
+async fn write_events(
+    events: Vec<StewardEvent>,
+    store: &StewardEventsStore,
+    dry_run: bool,
+) -> Result<(), mongodb::error::Error> {
+    match dry_run {
+        true => {
+            info!("upserting {events:#?}");
+        }
+        false => {
+            if let Err(e) = store.bulk_upsert(events).await {
+                error!("Error inserting events: {e:?}");
+                return Err(e);
+            }
         }
     }
+
     Ok(())
 }
 
+const NUM_TRANSACTIONS: usize = 1000;
+
 #[allow(clippy::too_many_arguments)]
 async fn parse_log(
-    log: String,
+    log: &str,
     signature: &Signature,
     instruction_idx: u32,
     signer: &Pubkey,
+    program_id: &Pubkey,
     stake_pool: &Pubkey,
     timestamp: Option<i64>,
-    transaction_err: Option<TransactionError>,
+    tx_error: Option<String>,
     epoch: u64,
     slot: u64,
 ) -> Result<Option<StewardEvent>, Box<dyn std::error::Error>> {
     // Parse the log
-    let program = jito_steward::id().to_string();
-    let tx_error = transaction_err.map(|e| e.to_string());
+    let program = program_id.to_string();
 
     // DecreaseComponents
-    if let Ok((Some(event), _, _)) = handle_program_log::<DecreaseComponents>(&program, &log) {
+    if let Ok((Some(event), _, _)) = handle_program_log::<DecreaseComponents>(&program, log) {
         let steward_event = StewardEvent::from_decrease_components(
             event,
             signature,
@@ -494,8 +642,7 @@ async fn parse_log(
     }
 
     // InstantUnstakeComponents
-    if let Ok((Some(event), _, _)) = handle_program_log::<InstantUnstakeComponents>(&program, &log)
-    {
+    if let Ok((Some(event), _, _)) = handle_program_log::<InstantUnstakeComponents>(&program, log) {
         let steward_event = StewardEvent::from_instant_unstake_components(
             event,
             signature,
@@ -510,8 +657,7 @@ async fn parse_log(
     }
 
     // InstantUnstakeComponentsV3
-    if let Ok((Some(event), _, _)) =
-        handle_program_log::<InstantUnstakeComponentsV3>(&program, &log)
+    if let Ok((Some(event), _, _)) = handle_program_log::<InstantUnstakeComponentsV3>(&program, log)
     {
         let steward_event = StewardEvent::from_instant_unstake_components_v3(
             event,
@@ -527,7 +673,7 @@ async fn parse_log(
     }
 
     // RebalanceEvent
-    if let Ok((Some(event), _, _)) = handle_program_log::<RebalanceEvent>(&program, &log) {
+    if let Ok((Some(event), _, _)) = handle_program_log::<RebalanceEvent>(&program, log) {
         let steward_event = StewardEvent::from_rebalance_event(
             event,
             signature,
@@ -542,7 +688,7 @@ async fn parse_log(
     }
 
     // DirectedRebalanceEvent
-    if let Ok((Some(event), _, _)) = handle_program_log::<DirectedRebalanceEvent>(&program, &log) {
+    if let Ok((Some(event), _, _)) = handle_program_log::<DirectedRebalanceEvent>(&program, log) {
         let steward_event = StewardEvent::from_directed_rebalance_event(
             event,
             signature,
@@ -557,7 +703,7 @@ async fn parse_log(
     }
 
     // ScoreComponents
-    if let Ok((Some(event), _, _)) = handle_program_log::<ScoreComponents>(&program, &log) {
+    if let Ok((Some(event), _, _)) = handle_program_log::<ScoreComponents>(&program, log) {
         let steward_event = StewardEvent::from_score_components(
             event,
             signature,
@@ -572,7 +718,7 @@ async fn parse_log(
     }
 
     // ScoreComponentsV4
-    if let Ok((Some(event), _, _)) = handle_program_log::<ScoreComponentsV4>(&program, &log) {
+    if let Ok((Some(event), _, _)) = handle_program_log::<ScoreComponentsV4>(&program, log) {
         let steward_event = StewardEvent::from_score_components_v4(
             event,
             signature,
@@ -587,7 +733,7 @@ async fn parse_log(
     }
 
     // StateTransition
-    if let Ok((Some(event), _, _)) = handle_program_log::<StateTransition>(&program, &log) {
+    if let Ok((Some(event), _, _)) = handle_program_log::<StateTransition>(&program, log) {
         let steward_event = StewardEvent::from_state_transition(
             event,
             signature,
@@ -603,7 +749,7 @@ async fn parse_log(
 
     // AutoRemoveValidatorEvent
     if let Ok((Some(event), _, _)) =
-        handle_program_log::<AutoRemoveValidatorEvent>(&program.to_string(), &log)
+        handle_program_log::<AutoRemoveValidatorEvent>(&program.to_string(), log)
     {
         let steward_event = StewardEvent::from_auto_remove_validator_event(
             event,
@@ -621,7 +767,7 @@ async fn parse_log(
 
     // AutoAddValidatorEvent
     if let Ok((Some(event), _, _)) =
-        handle_program_log::<AutoAddValidatorEvent>(&program.to_string(), &log)
+        handle_program_log::<AutoAddValidatorEvent>(&program.to_string(), log)
     {
         let steward_event = StewardEvent::from_auto_add_validator_event(
             event,
@@ -639,7 +785,7 @@ async fn parse_log(
 
     // EpochMaintenanceEvent
     if let Ok((Some(event), _, _)) =
-        handle_program_log::<EpochMaintenanceEvent>(&program.to_string(), &log)
+        handle_program_log::<EpochMaintenanceEvent>(&program.to_string(), log)
     {
         let steward_event = StewardEvent::from_epoch_maintenance_event(
             event,
@@ -658,6 +804,37 @@ async fn parse_log(
     Ok(None)
 }
 
+/// `Program data:` payload none of the decoders in [`parse_log`] recognizes
+#[allow(clippy::too_many_arguments)]
+fn unknown_event(
+    log: &str,
+    signature: &Signature,
+    instruction_idx: u32,
+    signer: &Pubkey,
+    stake_pool: &Pubkey,
+    timestamp: Option<i64>,
+    tx_error: Option<String>,
+    epoch: u64,
+    slot: u64,
+) -> Option<StewardEvent> {
+    let data = log.strip_prefix("Program data: ")?;
+    let bytes = STANDARD.decode(data).ok()?;
+    let discriminator = bytes.get(..8)?;
+
+    Some(StewardEvent::from_unknown_event(
+        discriminator,
+        data,
+        signature,
+        instruction_idx,
+        tx_error,
+        signer,
+        stake_pool,
+        timestamp,
+        epoch,
+        slot,
+    ))
+}
+
 fn get_epoch_from_slot(slot: u64) -> u64 {
     // Calculate the epoch from the slot
 
@@ -0,0 +1,198 @@
+//! Re-decoding of events stored as `Unknown`.
+//!
+//! Events emitted by a steward release the writer did not know yet are stored with the raw
+//! `Program data:` payload. Once a decoder for their type is added to [`parse_log`], re-decoding
+//! replaces them with the decoded event under the same (signature, instruction_idx, event_idx).
+
+use std::str::FromStr;
+
+use kobe_core::db_models::steward_events::{StewardEvent, StewardEventsStore};
+use log::{error, info};
+use solana_metrics::datapoint_info;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::parse_log;
+
+/// Re-decode every `Unknown` event, returning the number of events decoded
+pub async fn redecode_unknown_events(
+    store: &StewardEventsStore,
+    program_id: &Pubkey,
+    dry_run: bool,
+    cluster_name: &str,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let unknown_events = store
+        .find_by_event_type(StewardEvent::UNKNOWN_EVENT_TYPE, None, None)
+        .await?;
+    info!("Re-decoding {} unknown events", unknown_events.len());
+
+    let mut decoded = 0;
+    let mut remaining = 0;
+    for unknown_event in unknown_events.iter() {
+        match redecode(unknown_event, program_id).await {
+            Ok(Some(event)) => {
+                info!(
+                    "Decoded {} emitted by transaction {}",
+                    event.event_type, event.signature
+                );
+                if dry_run {
+                    info!("replacing with {event:#?}");
+                } else {
+                    store.replace(&event).await?;
+                }
+                decoded += 1;
+            }
+            Ok(None) => remaining += 1,
+            Err(e) => {
+                error!(
+                    "Error re-decoding event of transaction {}: {e:?}",
+                    unknown_event.signature
+                );
+                remaining += 1;
+            }
+        }
+    }
+
+    info!("Decoded {decoded} events, {remaining} remain unknown");
+    datapoint_info!(
+        "steward_writer_service-redecode",
+        ("decoded", decoded, i64),
+        ("remaining", remaining, i64),
+        "cluster" => cluster_name,
+    );
+
+    Ok(decoded)
+}
+
+/// Decode an `Unknown` event with the current decoders, keeping its key and commitment
+async fn redecode(
+    unknown_event: &StewardEvent,
+    program_id: &Pubkey,
+) -> Result<Option<StewardEvent>, Box<dyn std::error::Error>> {
+    let Some(data) = unknown_event
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get_str("data").ok())
+    else {
+        return Err("unknown event has no data".into());
+    };
+
+    let event = parse_log(
+        &format!("Program data: {data}"),
+        &Signature::from_str(&unknown_event.signature)?,
+        unknown_event.instruction_idx,
+        &Pubkey::from_str(&unknown_event.signer)?,
+        program_id,
+        &Pubkey::from_str(&unknown_event.stake_pool)?,
+        unknown_event
+            .timestamp
+            .map(|timestamp| timestamp.timestamp_millis() / 1000),
+        unknown_event.tx_error.clone(),
+        unknown_event.epoch,
+        unknown_event.slot,
+    )
+    .await?;
+
+    Ok(event.map(|mut event| {
+        event.inner_instruction_idx = unknown_event.inner_instruction_idx;
+        event.event_idx = unknown_event.event_idx;
+        event.commitment = unknown_event.commitment.clone();
+        event
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_client::anchor_lang::Event;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use jito_steward::events::StateTransition;
+    use solana_sdk::commitment_config::CommitmentLevel;
+
+    use super::*;
+    use crate::transaction_events;
+
+    /// Events of a transaction where `program` emits `data`, decoded for `program_id`
+    async fn events_of(program: &Pubkey, program_id: &Pubkey, data: &[u8]) -> Vec<StewardEvent> {
+        transaction_events(
+            &Signature::from([3; 64]),
+            345_600_000,
+            None,
+            Some(1_700_000_000),
+            &Pubkey::new_unique(),
+            vec![
+                "Program ComputeBudget111111111111111111111111111111 invoke [1]".to_string(),
+                "Program ComputeBudget111111111111111111111111111111 success".to_string(),
+                format!("Program {program} invoke [1]"),
+                format!("Program data: {}", STANDARD.encode(data)),
+                format!("Program {program} success"),
+            ],
+            program_id,
+            &Pubkey::new_unique(),
+            CommitmentLevel::Finalized,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_unknown_event_stored() {
+        let events = events_of(&jito_steward::id(), &jito_steward::id(), &[0xab; 24]).await;
+
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.event_type, StewardEvent::UNKNOWN_EVENT_TYPE);
+        assert_eq!(event.instruction_idx, 1);
+        let metadata = event.metadata.as_ref().unwrap();
+        assert_eq!(
+            metadata.get_str("discriminator").unwrap(),
+            "abababababababab"
+        );
+        assert_eq!(
+            metadata.get_str("data").unwrap(),
+            STANDARD.encode([0xab; 24])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_event_of_configured_program() {
+        let program_id = Pubkey::new_unique();
+
+        let events = events_of(&program_id, &program_id, &[0xab; 24]).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, StewardEvent::UNKNOWN_EVENT_TYPE);
+
+        // Payloads of other programs are not steward events
+        assert!(events_of(&jito_steward::id(), &program_id, &[0xab; 24])
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_redecode() {
+        let state_transition = StateTransition {
+            epoch: 800,
+            slot: 345_600_000,
+            previous_state: "Idle".to_string(),
+            new_state: "ComputeScores".to_string(),
+        };
+        let mut unknown_event = events_of(&jito_steward::id(), &jito_steward::id(), &[0xab; 24])
+            .await
+            .remove(0);
+        unknown_event.event_idx = 2;
+        unknown_event
+            .metadata
+            .as_mut()
+            .unwrap()
+            .insert("data", STANDARD.encode(state_transition.data()));
+
+        let event = redecode(&unknown_event, &jito_steward::id())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(event.event_type, "StateTransition");
+        assert_eq!(event.signature, unknown_event.signature);
+        assert_eq!(event.instruction_idx, 1);
+        assert_eq!(event.event_idx, 2);
+        assert_eq!(event.timestamp, unknown_event.timestamp);
+        assert_eq!(event.commitment.as_deref(), Some("finalized"));
+    }
+}
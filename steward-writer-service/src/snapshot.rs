@@ -0,0 +1,132 @@
+//! Snapshots of the steward state.
+//!
+//! The steward state account and the stake pool validator list are read at configurable points of
+//! each epoch and stored as one document per validator, so scores, ranks and target delegations
+//! can be followed over time. The steward config parameters are recorded with each snapshot, so
+//! past scores are explained with the thresholds they were computed with.
+
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use kobe_core::db_models::{
+    steward_parameters::{StewardParameters, StewardParametersStore},
+    steward_state_snapshots::{StewardStateSnapshot, StewardStateSnapshotStore},
+};
+use log::{error, info};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_metrics::datapoint_info;
+use solana_sdk::pubkey::Pubkey;
+use stakenet_sdk::utils::accounts::get_all_steward_accounts;
+
+pub struct StateSnapshotter<'a> {
+    pub program_id: &'a Pubkey,
+    pub steward_config: &'a Pubkey,
+    pub rpc_client: &'a Arc<RpcClient>,
+    pub store: &'a StewardStateSnapshotStore,
+    pub parameters_store: &'a StewardParametersStore,
+    pub dry_run: bool,
+    pub cluster_name: &'a str,
+}
+
+impl StateSnapshotter<'_> {
+    /// Snapshot the steward state once the epoch reaches each of `epoch_progress_thresholds`,
+    /// checking the epoch progress every `poll_interval`
+    pub async fn run(
+        &self,
+        epoch_progress_thresholds: &[f64],
+        poll_interval: Duration,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut last_epoch: Option<u64> = None;
+        let mut thresholds_hit = HashSet::new();
+
+        loop {
+            let epoch_info = self.rpc_client.get_epoch_info().await?;
+            let current_epoch = epoch_info.epoch;
+            let progress = epoch_info.slot_index as f64 / epoch_info.slots_in_epoch as f64;
+
+            if last_epoch != Some(current_epoch) {
+                thresholds_hit.clear();
+                last_epoch = Some(current_epoch);
+            }
+
+            // A single snapshot covers every threshold reached since the last poll
+            let reached: Vec<usize> = epoch_progress_thresholds
+                .iter()
+                .enumerate()
+                .filter(|&(idx, &threshold)| {
+                    progress >= threshold && !thresholds_hit.contains(&idx)
+                })
+                .map(|(idx, _)| idx)
+                .collect();
+            if let Some(threshold) = reached
+                .iter()
+                .map(|&idx| epoch_progress_thresholds[idx])
+                .reduce(f64::max)
+            {
+                let threshold_pct = threshold * 100.0;
+
+                match self.snapshot().await {
+                    Ok(validators) => {
+                        info!(
+                            "Snapshotted {validators} validators at {threshold_pct:.0}% of epoch {current_epoch}"
+                        );
+                        thresholds_hit.extend(reached);
+
+                        datapoint_info!(
+                            "steward_writer_service-state_snapshot",
+                            ("epoch", current_epoch, i64),
+                            ("threshold_pct", threshold_pct as i64, i64),
+                            ("validators", validators, i64),
+                            ("success", 1, i64),
+                            "cluster" => self.cluster_name,
+                        );
+                    }
+                    Err(e) => {
+                        error!(
+                            "Error snapshotting at {threshold_pct:.0}% of epoch {current_epoch}: {e}"
+                        );
+
+                        datapoint_info!(
+                            "steward_writer_service-state_snapshot",
+                            ("epoch", current_epoch, i64),
+                            ("success", 0, i64),
+                            "cluster" => self.cluster_name,
+                        );
+                    }
+                }
+            }
+
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Snapshot the current steward state, returning the number of validators snapshotted
+    pub async fn snapshot(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        let accounts =
+            get_all_steward_accounts(self.rpc_client, self.program_id, self.steward_config).await?;
+        let epoch_info = self.rpc_client.get_epoch_info().await?;
+
+        let snapshot = StewardStateSnapshot::from_steward_accounts(
+            &accounts,
+            epoch_info.epoch,
+            epoch_info.absolute_slot,
+        );
+        let parameters = StewardParameters::from_parameters(
+            &accounts.config_account.parameters,
+            epoch_info.epoch,
+            epoch_info.absolute_slot,
+        );
+        let validators = snapshot.len();
+
+        if self.dry_run {
+            info!(
+                "Snapshot at slot {}: {parameters:#?} {snapshot:#?}",
+                epoch_info.absolute_slot
+            );
+        } else {
+            self.parameters_store.upsert(&parameters).await?;
+            self.store.upsert_snapshot(&snapshot).await?;
+        }
+
+        Ok(validators)
+    }
+}
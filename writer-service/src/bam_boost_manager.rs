@@ -1,12 +1,13 @@
-use std::{collections::HashMap, str::FromStr, sync::Arc};
+use std::{collections::HashMap, sync::Arc};
 
 use borsh::BorshDeserialize;
 use jito_bam_boost_merkle_tree::bam_boost_entry::BamBoostEntry;
 use jito_program_client::bam_boost::config::Config;
 use kobe_core::{
-    constants::JITOSOL_MINT,
+    cluster_config::ClusterConfig,
     db_models::bam_boost_validators::{BamBoostValidator, BamBoostValidatorsStore},
-    validators_app::{Client, Cluster, ValidatorsAppResponseEntry},
+    validator_metadata::ValidatorMetadataSource,
+    validators_app::{Cluster, ValidatorsAppResponseEntry},
 };
 use mongodb::Database;
 use solana_client::nonblocking::rpc_client::RpcClient;
@@ -18,30 +19,31 @@ pub struct BamBoostManager {
     /// RPC client
     pub rpc_client: Arc<RpcClient>,
 
-    /// Validators app client
-    pub validators_app_client: Arc<Client>,
+    /// Source of validator metadata (validators.app or on-chain)
+    pub validator_metadata_source: Arc<ValidatorMetadataSource>,
 
     /// Cluster [Mainnet, Testnet, Devnet]
     pub cluster: Cluster,
 
     /// Jito BAM Boost Program ID
     pub jito_bam_boost_program_id: Pubkey,
+
+    /// JitoSOL mint
+    pub jitosol_mint: Pubkey,
 }
 
 impl BamBoostManager {
     pub fn new(
         rpc_client: Arc<RpcClient>,
-        validators_app_client: Arc<Client>,
-        cluster: Cluster,
+        validator_metadata_source: Arc<ValidatorMetadataSource>,
+        cluster_config: &ClusterConfig,
     ) -> Self {
         Self {
             rpc_client,
-            validators_app_client,
-            cluster,
-            jito_bam_boost_program_id: Pubkey::from_str(
-                "BoostxbPp2ENYHGcTLYt1obpcY13HE4NojdqNWdzqSSb",
-            )
-            .unwrap(),
+            validator_metadata_source,
+            cluster: cluster_config.cluster,
+            jito_bam_boost_program_id: cluster_config.bam_boost_program_id,
+            jitosol_mint: cluster_config.jitosol_mint,
         }
     }
 
@@ -77,7 +79,7 @@ impl BamBoostManager {
         let network = match self.cluster {
             Cluster::MainnetBeta => "mainnet",
             Cluster::Testnet => "testnet",
-            Cluster::Devnet => {
+            Cluster::Devnet | Cluster::Localnet => {
                 return Err(AppError::InvalidOperation(
                     "Failed to read cluster".to_string(),
                 ))
@@ -122,14 +124,12 @@ impl BamBoostManager {
         let epoch_info = self.rpc_client.get_epoch_info().await?;
         let current_epoch = epoch_info.epoch;
 
-        let validators_app_client = self.validators_app_client.clone();
-        let network_validators = tokio::task::spawn_blocking(move || {
-            validators_app_client.validators(None, None, current_epoch)
-        })
-        .await??;
+        let network_validators = self
+            .validator_metadata_source
+            .validators(current_epoch)
+            .await?;
         let network_validators_map: HashMap<Option<String>, &ValidatorsAppResponseEntry> =
             network_validators
-                .as_ref()
                 .iter()
                 .map(|v| (v.account.clone(), v))
                 .collect();
@@ -155,7 +155,7 @@ impl BamBoostManager {
                             Some(validator_entry) => &validator_entry.name,
                             None => &None,
                         };
-                        let distributor_pda = self.distributor_address(JITOSOL_MINT, epoch);
+                        let distributor_pda = self.distributor_address(self.jitosol_mint, epoch);
 
                         let claim_status_pda = self.claim_status_address(
                             Pubkey::from_str(&entry.pubkey).unwrap(),
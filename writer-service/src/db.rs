@@ -6,8 +6,11 @@ use kobe_core::{
         STAKER_REWARDS_COLLECTION_NAME, STAKE_POOL_STATS_COLLECTION_NAME, VALIDATOR_COLLECTION_NAME,
     },
     db_models::{
-        mev_rewards::StakerRewards, stake_pool_stats::StakePoolStats, validators::Validator,
+        mev_rewards::StakerRewards,
+        stake_pool_stats::StakePoolStats,
+        validators::{Validator, ValidatorStore},
     },
+    fetcher::fetch_inflation_rewards,
 };
 use log::{error, info, warn};
 use mongodb::{
@@ -178,6 +181,53 @@ pub async fn write_validator_info(
     upsert_to_db(&collection, &validators, epoch).await
 }
 
+/// Record the inflation rewards actually paid in `epoch` next to the estimates
+///
+/// Returns early if every validator in `epoch` already has paid rewards recorded. Fails without
+/// writing anything while the epoch's rewards are not yet available, so the next run retries.
+/// A validator without any reward is recorded as paid zero, so it is not queried again.
+pub async fn write_inflation_rewards(
+    db: &Database,
+    stake_pool_manager: &StakePoolManager,
+    epoch: u64,
+    validator_list_address: &Pubkey,
+) -> Result<()> {
+    let store = ValidatorStore::new(db.collection::<Validator>(VALIDATOR_COLLECTION_NAME));
+    let validators = store.find_without_paid_inflation_rewards(epoch).await?;
+    if validators.is_empty() {
+        return Ok(());
+    }
+
+    let vote_accounts: Vec<Pubkey> = validators.iter().map(|v| v.vote_account()).collect();
+    let rewards = fetch_inflation_rewards(
+        &stake_pool_manager.rpc_client,
+        &vote_accounts,
+        &stake_pool_manager.cluster_config,
+        validator_list_address,
+        epoch,
+    )
+    .await?;
+
+    let start = Instant::now();
+    for validator in validators.iter() {
+        let rewards = rewards
+            .get(&validator.vote_account())
+            .copied()
+            .unwrap_or_default();
+        store
+            .update_inflation_rewards(epoch, &validator.vote_account, &rewards)
+            .await?;
+    }
+
+    info!(
+        "done writing inflation rewards for {} validators in epoch {epoch}, took {}ms",
+        validators.len(),
+        start.elapsed().as_millis()
+    );
+
+    Ok(())
+}
+
 pub async fn setup_mongo_client(uri: &str) -> Result<MongodbClient> {
     let client_options = ClientOptions::parse(uri).await?;
     Ok(MongodbClient::with_options(client_options)?)
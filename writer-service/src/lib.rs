@@ -1,7 +1,11 @@
 use std::{sync::Arc, time::Duration};
 
 use kobe_core::{
+    cluster_config::ClusterConfig,
     constants::DATABASE_NAME,
+    validator_metadata::{
+        OnChainValidatorMetadata, ValidatorMetadataSource, ValidatorMetadataSourceKind,
+    },
     validators_app::{Client as ValidatorsAppClient, Cluster},
 };
 use log::{error, info};
@@ -15,7 +19,9 @@ use tokio::time::{sleep_until, Instant};
 
 use crate::{
     bam_boost_manager::BamBoostManager,
-    db::{write_mev_claims_info, write_stake_pool_info, write_validator_info},
+    db::{
+        write_inflation_rewards, write_mev_claims_info, write_stake_pool_info, write_validator_info,
+    },
     result::Result,
     stake_pool_manager::StakePoolManager,
 };
@@ -67,17 +73,20 @@ impl KobeWriterService {
     #[allow(clippy::too_many_arguments)]
     pub async fn new(
         mongo_connection_uri: &str,
-        cluster: Cluster,
+        cluster_config: ClusterConfig,
         rpc_url: String,
-        tip_distribution_program_id: String,
-        priority_fee_distribution_program_id: String,
         mainnet_gcp_server_names: Vec<String>,
-        steward_config: Pubkey,
         bam_api_base_url: Option<String>,
+        validator_metadata_source_kind: ValidatorMetadataSourceKind,
     ) -> Result<Self> {
         let mongodb_client = db::setup_mongo_client(mongo_connection_uri).await?;
 
-        let stake_pool_address = stake_pool_manager::resolve_stake_pool_address(&cluster)?;
+        let cluster = cluster_config.cluster;
+        let stake_pool_address = cluster_config.stake_pool_address;
+        let tip_distribution_program_id = cluster_config.tip_distribution_program_id.to_string();
+        let priority_fee_distribution_program_id = cluster_config
+            .priority_fee_distribution_program_id
+            .to_string();
 
         let db = mongodb_client.database(DATABASE_NAME);
         let rpc_client = RpcClient::new_with_timeout_and_commitment(
@@ -89,26 +98,40 @@ impl KobeWriterService {
 
         let stake_pool = get_stake_pool(&rpc_client, &stake_pool_address).await?;
 
-        // Calls a blocking reqwest method when initializing the client
-        let validators_app_client = tokio::task::spawn_blocking(move || {
-            ValidatorsAppClient::new_with_cluster(cluster)
-                .expect("Could not initialize Validators App client")
-        })
-        .await
-        .expect("Failed to initialize Validators App client");
-        let validators_app_client = Arc::new(validators_app_client);
+        let validator_metadata_source = match validator_metadata_source_kind {
+            ValidatorMetadataSourceKind::ValidatorsApp => {
+                // Calls a blocking reqwest method when initializing the client
+                let validators_app_client = tokio::task::spawn_blocking(move || {
+                    ValidatorsAppClient::new_with_cluster(cluster)
+                        .expect("Could not initialize Validators App client")
+                })
+                .await
+                .expect("Failed to initialize Validators App client");
+                ValidatorMetadataSource::ValidatorsApp(Arc::new(validators_app_client))
+            }
+            ValidatorMetadataSourceKind::OnChain => {
+                ValidatorMetadataSource::OnChain(OnChainValidatorMetadata::new(
+                    rpc_client.clone(),
+                    cluster_config.validator_history_program_id,
+                ))
+            }
+        };
+        info!("Using {validator_metadata_source_kind} validator metadata source");
+        let validator_metadata_source = Arc::new(validator_metadata_source);
+
+        let bam_boost_manager = BamBoostManager::new(
+            rpc_client.clone(),
+            validator_metadata_source.clone(),
+            &cluster_config,
+        );
 
         let stake_pool_manager = StakePoolManager::new(
             rpc_client.clone(),
-            validators_app_client.clone(),
+            validator_metadata_source,
             bam_api_base_url,
-            cluster,
-            steward_config,
+            cluster_config,
         );
 
-        let bam_boost_manager =
-            BamBoostManager::new(rpc_client.clone(), validators_app_client, cluster);
-
         Ok(Self {
             db,
             stake_pool_manager,
@@ -127,8 +150,8 @@ impl KobeWriterService {
     /// In this mode, the service processes:
     ///
     /// Every 10 min
-    /// - Collect validator information from validators app and on-chain, then update and insert
-    ///   into DB
+    /// - Collect validator information from the validator metadata source and on-chain, then update
+    ///   and insert into DB
     /// - Collect MEV Claim information from on-chain and GCP server, then update into DB
     ///
     /// Hourly
@@ -224,6 +247,27 @@ impl KobeWriterService {
             }
         }
 
+        // Rewards for the previous epoch are paid at the start of this one; until then this fails
+        // and is retried on the next run
+        if let Some(previous_epoch) = epoch.checked_sub(1) {
+            match write_inflation_rewards(
+                &self.db,
+                &self.stake_pool_manager,
+                previous_epoch,
+                &self.stake_pool.validator_list,
+            )
+            .await
+            {
+                Ok(_) => {
+                    datapoint_info!("inflation_rewards_written", ("success", 1, i64), "cluster" => self.cluster.to_string());
+                }
+                Err(e) => {
+                    datapoint_info!("inflation_rewards_written", ("success", 0, i64), "cluster" => self.cluster.to_string());
+                    error!("Writing inflation rewards failed: {e:?}");
+                }
+            }
+        }
+
         // On epoch boundaries, this function may fail, so we log the error and continue
         match write_mev_claims_info(
             &self.db,
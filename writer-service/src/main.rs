@@ -1,9 +1,13 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 use env_logger::{Builder, Target};
-use kobe_core::validators_app::Cluster;
-use kobe_writer_service::{result::Result, KobeWriterService};
+use kobe_core::{
+    cluster_config::ClusterConfig, constants::DATABASE_NAME, migrations::MigrationRunner,
+    validator_metadata::ValidatorMetadataSourceKind, validators_app::Cluster,
+};
+use kobe_writer_service::{db::setup_mongo_client, result::Result, KobeWriterService};
 use log::{error, info, set_boxed_logger, set_max_level, LevelFilter};
-use solana_clap_utils::input_validators::is_url_or_moniker;
 use solana_metrics::set_host_id;
 use solana_sdk::pubkey::Pubkey;
 use tokio::runtime::Runtime;
@@ -23,13 +27,17 @@ struct Args {
     #[arg(long, env)]
     mongo_db_name: String,
 
-    /// Solana cluster e.g. testnet, mainnet, devnet
+    /// Solana cluster e.g. testnet, mainnet, devnet, localnet
     #[arg(long, short, env, default_value_t=String::from("testnet"))]
     solana_cluster: String,
 
-    /// Whether to create the index
-    #[arg(long)]
-    create_index: bool,
+    /// Cluster config file (TOML), defaults to the profiles embedded in kobe-core
+    #[arg(long, env)]
+    cluster_config: Option<PathBuf>,
+
+    /// Apply pending schema migrations (indexes, backfills) before starting
+    #[arg(long, env)]
+    run_migrations: bool,
 
     /// Sentry api url
     #[arg(long, env)]
@@ -39,33 +47,27 @@ struct Args {
     #[clap(long, env)]
     bam_api_base_url: Option<String>,
 
+    /// Validator metadata source: validators-app or on-chain
+    ///
+    /// on-chain does not require VALIDATORS_APP_TOKEN, but leaves validators.app scores empty
+    #[arg(long, env, default_value = "validators-app")]
+    validator_metadata_source: ValidatorMetadataSourceKind,
+
     // Whether to start running a live loop or single epoch backfill
     #[command(subcommand)]
     command: Commands,
 
-    /// Tip distribution program id
-    #[arg(
-        long,
-        env,
-        default_value = "4R3gSG8BpU4t19KYj8CfnbtRpnT8gtk4dvTHxVRwc2r7"
-    )]
-    tip_distribution_program_id: String,
-
-    /// Priority fee distribution program id
-    #[arg(
-        long,
-        env,
-        default_value = "Priority6weCZ5HwDn29NxLFpb7TDp2iLZ6XKc5e8d3"
-    )]
-    priority_fee_distribution_program_id: String,
-
-    /// Steward config pubkey
-    #[arg(
-        long,
-        env,
-        default_value = "jitoVjT9jRUyeXHzvCwzPgHj7yWNRhLcUoXtes4wtjv"
-    )]
-    steward_config_pubkey: Pubkey,
+    /// Tip distribution program id, overrides the cluster config
+    #[arg(long, env)]
+    tip_distribution_program_id: Option<Pubkey>,
+
+    /// Priority fee distribution program id, overrides the cluster config
+    #[arg(long, env)]
+    priority_fee_distribution_program_id: Option<Pubkey>,
+
+    /// Steward config pubkey, overrides the cluster config
+    #[arg(long, env)]
+    steward_config_pubkey: Option<Pubkey>,
 
     /// Mainnet gcp server names
     #[arg(long, env, value_delimiter = ',')]
@@ -108,20 +110,38 @@ fn main() -> Result<()> {
     ));
     info!("Sentry guard initialized");
 
-    is_url_or_moniker(args.solana_cluster.clone()).expect("Cluster arg malformed!");
+    let mut cluster_config = ClusterConfig::load(args.cluster_config.as_deref(), cluster)?;
+    if let Some(tip_distribution_program_id) = args.tip_distribution_program_id {
+        cluster_config.tip_distribution_program_id = tip_distribution_program_id;
+    }
+    if let Some(priority_fee_distribution_program_id) = args.priority_fee_distribution_program_id {
+        cluster_config.priority_fee_distribution_program_id = priority_fee_distribution_program_id;
+    }
+    if let Some(steward_config) = args.steward_config_pubkey {
+        cluster_config.steward_config = steward_config;
+    }
 
     let rt = Runtime::new().unwrap();
 
     rt.block_on(async {
+        if args.run_migrations {
+            let mongodb_client = setup_mongo_client(&args.mongo_connection_uri)
+                .await
+                .expect("Failed to connect to MongoDB");
+            let applied = MigrationRunner::new(mongodb_client.database(DATABASE_NAME))
+                .run()
+                .await
+                .expect("Failed to apply migrations");
+            info!("Applied migrations {applied:?}");
+        }
+
         let kobe_service = KobeWriterService::new(
             &args.mongo_connection_uri,
-            cluster,
+            cluster_config,
             args.rpc_url,
-            args.tip_distribution_program_id,
-            args.priority_fee_distribution_program_id,
             args.mainnet_gcp_server_names,
-            args.steward_config_pubkey,
             args.bam_api_base_url,
+            args.validator_metadata_source,
         )
         .await
         .expect("Failed to initialize KobeWriterService");
@@ -2,7 +2,7 @@ use std::io::Error as IoError;
 
 use backoff::Error as BackoffError;
 use bam_api_client::error::BamApiError;
-use kobe_core::error::KobeCoreError;
+use kobe_core::{db_models::error::DataStoreError, error::KobeCoreError};
 use log::SetLoggerError;
 use mongodb::error::Error as MongoError;
 use reqwest::Error as ReqwestError;
@@ -69,6 +69,9 @@ pub enum AppError {
 
     #[error(transparent)]
     KobeCore(#[from] KobeCoreError),
+
+    #[error(transparent)]
+    DataStore(#[from] DataStoreError),
 }
 
 impl From<BackoffError<ClientError>> for AppError {
@@ -1,16 +1,12 @@
-use std::{
-    collections::HashSet, str::FromStr, sync::Arc, thread::sleep, time::Duration as CoreDuration,
-};
+use std::{collections::HashSet, sync::Arc, thread::sleep, time::Duration as CoreDuration};
 
 use bam_api_client::client::BamApiClient;
 use chrono::{Duration, DurationRound, Utc};
 use kobe_core::{
-    constants::{
-        DEVNET_STAKE_POOL_ADDRESS, MAINNET_STAKE_POOL_ADDRESS, TESTNET_STAKE_POOL_ADDRESS,
-    },
+    cluster_config::ClusterConfig,
     db_models::{stake_pool_stats::StakePoolStats, validators::Validator},
     fetcher::{fetch_chain_data, fetch_mev_rewards, fetch_total_staked_lamports},
-    validators_app::{Client, Cluster},
+    validator_metadata::ValidatorMetadataSource,
 };
 use log::info;
 use solana_client::nonblocking::rpc_client::RpcClient;
@@ -24,33 +20,28 @@ pub struct StakePoolManager {
     /// RPC client
     pub rpc_client: Arc<RpcClient>,
 
-    /// Validators app client
-    pub validators_app_client: Arc<Client>,
+    /// Source of validator metadata (validators.app or on-chain)
+    pub validator_metadata_source: Arc<ValidatorMetadataSource>,
 
     /// BAM API client
     pub bam_api_client: Option<BamApiClient>,
 
-    /// Cluster [Mainnet, Testnet, Devnet]
-    pub cluster: Cluster,
-
-    /// Steward config pubkey
-    steward_config: Pubkey,
+    /// Program IDs and addresses of the cluster
+    pub cluster_config: ClusterConfig,
 }
 
 impl StakePoolManager {
     pub fn new(
         rpc_client: Arc<RpcClient>,
-        validators_app_client: Arc<Client>,
+        validator_metadata_source: Arc<ValidatorMetadataSource>,
         bam_api_base_url: Option<String>,
-        cluster: Cluster,
-        steward_config: Pubkey,
+        cluster_config: ClusterConfig,
     ) -> Self {
         let mut manager = Self {
             rpc_client,
-            validators_app_client,
+            validator_metadata_source,
             bam_api_client: None,
-            cluster,
-            steward_config,
+            cluster_config,
         };
 
         if let Some(bam_api_base_url) = bam_api_base_url {
@@ -67,11 +58,7 @@ impl StakePoolManager {
         epoch: u64,
         validator_list_address: &Pubkey,
     ) -> Result<Vec<Validator>> {
-        let validators_app_client = self.validators_app_client.clone();
-        let network_validators = tokio::task::spawn_blocking(move || {
-            validators_app_client.validators(None, None, epoch)
-        })
-        .await??;
+        let network_validators = self.validator_metadata_source.validators(epoch).await?;
 
         let bam_validator_set: HashSet<String> =
             if let Some(ref bam_api_client) = self.bam_api_client {
@@ -85,18 +72,16 @@ impl StakePoolManager {
             };
 
         let on_chain_data = fetch_chain_data(
-            network_validators.as_ref(),
+            &network_validators,
             bam_validator_set,
             self.rpc_client.clone(),
-            &self.cluster,
+            &self.cluster_config,
             epoch,
             validator_list_address,
-            &self.steward_config,
         )
         .await?;
 
         let validators: Vec<Validator> = network_validators
-            .as_ref()
             .iter()
             .filter(|v| v.epoch.is_some())
             .map(|v| {
@@ -112,16 +97,15 @@ impl StakePoolManager {
     pub async fn get_mev_rewards(&self) -> Result<u64> {
         let rpc_client = &self.rpc_client;
         let current_epoch = rpc_utils::retry_get_epoch_info(rpc_client).await?;
-        let validators_app_client = self.validators_app_client.clone();
-        let network_validators = tokio::task::spawn_blocking(move || {
-            validators_app_client.validators(None, None, current_epoch)
-        })
-        .await??;
+        let network_validators = self
+            .validator_metadata_source
+            .validators(current_epoch)
+            .await?;
 
         let total_mev_rewards = fetch_mev_rewards(
-            network_validators.as_ref(),
+            &network_validators,
             rpc_client,
-            &self.cluster,
+            &self.cluster_config,
             current_epoch,
         )
         .await?;
@@ -190,12 +174,3 @@ pub fn wait_for_next_duration(duration: Duration) {
         now = Utc::now();
     }
 }
-
-pub fn resolve_stake_pool_address(cluster: &Cluster) -> Result<Pubkey> {
-    let address_str = match cluster {
-        Cluster::Devnet => DEVNET_STAKE_POOL_ADDRESS,
-        Cluster::Testnet => TESTNET_STAKE_POOL_ADDRESS,
-        Cluster::MainnetBeta => MAINNET_STAKE_POOL_ADDRESS,
-    };
-    Ok(Pubkey::from_str(address_str)?)
-}